@@ -0,0 +1,302 @@
+//! Batch checking of spec files.
+//!
+//! Proves every `prove` obligation in a spec file and renders the results as a
+//! terminal table, JSON, or JUnit XML for CI systems.
+
+use std::time::{Duration, Instant};
+
+use colored::Colorize;
+use lolli_parse::{Obligation, SpecFile};
+use lolli_prove::{ProofOutcome, Prover, SearchLimits, UnknownReason};
+
+/// Status of a single obligation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    /// A proof was found
+    Pass,
+    /// The obligation has no proof
+    Fail,
+    /// The search ended without an answer
+    Unknown(UnknownReason),
+}
+
+impl CheckStatus {
+    fn label(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "pass",
+            CheckStatus::Fail => "fail",
+            CheckStatus::Unknown(_) => "unknown",
+        }
+    }
+}
+
+/// Outcome of checking a single obligation.
+pub struct CheckResult {
+    /// The obligation that was checked
+    pub obligation: Obligation,
+    /// What the prover concluded
+    pub status: CheckStatus,
+    /// Time spent searching
+    pub elapsed: Duration,
+}
+
+/// Outcome of checking a whole file.
+pub struct CheckReport {
+    /// Path of the checked file
+    pub path: String,
+    /// One result per obligation, in file order
+    pub results: Vec<CheckResult>,
+}
+
+impl CheckReport {
+    /// Number of obligations that were proved.
+    pub fn passed(&self) -> usize {
+        self.count(|s| s == CheckStatus::Pass)
+    }
+
+    /// Number of obligations that were shown to be unprovable.
+    pub fn failed(&self) -> usize {
+        self.count(|s| s == CheckStatus::Fail)
+    }
+
+    /// Number of obligations whose search was inconclusive.
+    pub fn unknown(&self) -> usize {
+        self.count(|s| matches!(s, CheckStatus::Unknown(_)))
+    }
+
+    /// Returns true if every obligation was proved.
+    pub fn is_success(&self) -> bool {
+        self.passed() == self.results.len()
+    }
+
+    fn count(&self, pred: impl Fn(CheckStatus) -> bool) -> usize {
+        self.results.iter().filter(|r| pred(r.status)).count()
+    }
+
+    /// Total search time.
+    pub fn elapsed(&self) -> Duration {
+        self.results.iter().map(|r| r.elapsed).sum()
+    }
+}
+
+/// Prove every obligation in `spec`, each within `depth` and `limits`.
+pub fn check_spec(path: &str, spec: &SpecFile, depth: usize, limits: &SearchLimits) -> CheckReport {
+    let results = spec
+        .obligations
+        .iter()
+        .map(|obligation| {
+            let mut prover = Prover::new(depth);
+            prover.limits = limits.clone();
+            let start = Instant::now();
+            let status = match prover.decide_two_sided(&obligation.sequent) {
+                ProofOutcome::Provable(_) => CheckStatus::Pass,
+                ProofOutcome::Refuted => CheckStatus::Fail,
+                ProofOutcome::Unknown(reason) => CheckStatus::Unknown(reason),
+            };
+            CheckResult {
+                obligation: obligation.clone(),
+                status,
+                elapsed: start.elapsed(),
+            }
+        })
+        .collect();
+
+    CheckReport {
+        path: path.to_string(),
+        results,
+    }
+}
+
+/// Render the report as a terminal table.
+pub fn render_table(report: &CheckReport) -> String {
+    let mut lines = vec![format!(
+        "{:>4}  {:>6}  {:<9}  {}",
+        "#", "Line", "Result", "Sequent"
+    )];
+
+    for (i, result) in report.results.iter().enumerate() {
+        let status = match result.status {
+            CheckStatus::Pass => format!("{:<9}", "✓ pass").green().to_string(),
+            CheckStatus::Fail => format!("{:<9}", "✗ FAIL").red().bold().to_string(),
+            CheckStatus::Unknown(_) => "? UNKNOWN".yellow().bold().to_string(),
+        };
+        lines.push(format!(
+            "{:>4}  {:>6}  {}  {}",
+            i + 1,
+            result.obligation.position.line,
+            status,
+            result.obligation.source
+        ));
+    }
+
+    lines.push(String::new());
+    let summary = format!(
+        "{} passed, {} failed, {} unknown ({} obligations in {:.2?})",
+        report.passed(),
+        report.failed(),
+        report.unknown(),
+        report.results.len(),
+        report.elapsed()
+    );
+    if report.is_success() {
+        lines.push(summary.green().to_string());
+    } else {
+        lines.push(summary.red().bold().to_string());
+    }
+
+    lines.join("\n")
+}
+
+/// Render the report as JSON.
+pub fn render_json(report: &CheckReport) -> String {
+    let obligations: Vec<String> = report
+        .results
+        .iter()
+        .map(|r| {
+            format!(
+                "    {{\"line\": {}, \"column\": {}, \"sequent\": \"{}\", \"status\": \"{}\", \"time_ms\": {:.3}}}",
+                r.obligation.position.line,
+                r.obligation.position.column,
+                escape_json(&r.obligation.source),
+                r.status.label(),
+                r.elapsed.as_secs_f64() * 1000.0
+            )
+        })
+        .collect();
+
+    format!(
+        "{{\n  \"file\": \"{}\",\n  \"passed\": {},\n  \"failed\": {},\n  \"unknown\": {},\n  \"obligations\": [\n{}\n  ]\n}}",
+        escape_json(&report.path),
+        report.passed(),
+        report.failed(),
+        report.unknown(),
+        obligations.join(",\n")
+    )
+}
+
+/// Render the report as JUnit XML.
+pub fn render_junit(report: &CheckReport) -> String {
+    let path = escape_xml(&report.path);
+    let mut lines = vec![
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string(),
+        format!(
+            r#"<testsuite name="{}" tests="{}" failures="{}" time="{:.3}">"#,
+            path,
+            report.results.len(),
+            report.results.len() - report.passed(),
+            report.elapsed().as_secs_f64()
+        ),
+    ];
+
+    for r in &report.results {
+        let name = format!(
+            "line {}: {}",
+            r.obligation.position.line,
+            escape_xml(&r.obligation.source)
+        );
+        let time = r.elapsed.as_secs_f64();
+        let message = match r.status {
+            CheckStatus::Pass => {
+                lines.push(format!(
+                    r#"  <testcase classname="{}" name="{}" time="{:.3}"/>"#,
+                    path, name, time
+                ));
+                continue;
+            }
+            CheckStatus::Fail => "not provable".to_string(),
+            CheckStatus::Unknown(reason) => format!("unknown: {}", reason),
+        };
+        lines.push(format!(
+            r#"  <testcase classname="{}" name="{}" time="{:.3}">"#,
+            path, name, time
+        ));
+        lines.push(format!(
+            r#"    <failure message="{}">{}</failure>"#,
+            escape_xml(&message),
+            escape_xml(&r.obligation.sequent.pretty())
+        ));
+        lines.push("  </testcase>".to_string());
+    }
+
+    lines.push("</testsuite>".to_string());
+    lines.join("\n")
+}
+
+fn escape_json(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lolli_parse::parse_file;
+
+    fn report(source: &str) -> CheckReport {
+        let spec = parse_file(source).unwrap();
+        check_spec("spec.ll", &spec, 100, &SearchLimits::default())
+    }
+
+    #[test]
+    fn test_check_counts() {
+        let report = report("prove A |- A\nprove A, B |- A\nprove A, B |- B * A");
+        assert_eq!(report.passed(), 2);
+        assert_eq!(report.failed(), 1);
+        assert_eq!(report.results[1].status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn test_check_unknown_counts_as_failure() {
+        let spec = parse_file("prove A * B, 1 |- B * A").unwrap();
+        let report = check_spec("spec.ll", &spec, 1, &SearchLimits::default());
+        assert_eq!(
+            report.results[0].status,
+            CheckStatus::Unknown(UnknownReason::DepthLimit)
+        );
+        assert_eq!(report.failed(), 0);
+        assert_eq!(report.unknown(), 1);
+        assert!(!report.is_success());
+        assert!(render_junit(&report).contains("unknown: search cut off"));
+    }
+
+    #[test]
+    fn test_render_json() {
+        let json = render_json(&report("prove A |- A\nprove A |- A * A"));
+        assert!(json.contains("\"file\": \"spec.ll\""));
+        assert!(json.contains("\"passed\": 1"));
+        assert!(json.contains("\"sequent\": \"A |- A * A\", \"status\": \"fail\""));
+    }
+
+    #[test]
+    fn test_render_junit() {
+        let xml = render_junit(&report("prove A |- A\nprove A |- A * A"));
+        assert!(xml.contains(r#"tests="2" failures="1""#));
+        assert!(xml.contains(r#"name="line 1: A |- A""#));
+        assert_eq!(xml.matches("<failure").count(), 1);
+    }
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!(escape_json("a \"b\"\n"), "a \\\"b\\\"\\n");
+        assert_eq!(escape_xml("A -o B & <C>"), "A -o B &amp; &lt;C&gt;");
+    }
+}
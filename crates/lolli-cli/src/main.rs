@@ -3,11 +3,17 @@
 //! A toolkit for working with linear logic — parsing formulas, searching for proofs,
 //! extracting computational content, and compiling to Rust.
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use colored::Colorize;
-use lolli_extract::{extract_term, normalize};
-use lolli_parse::{parse_formula, parse_sequent};
-use lolli_prove::Prover;
+use lolli_extract::{extract_term, normalize, normalize_with, Strategy, Trace};
+use lolli_parse::{parse_formula, parse_sequent, parse_term};
+use lolli_prove::{
+    check_balance, strategy_by_name, PartialProof, ProofOutcome, ProofState, Prover, SearchLimits,
+    Tactic, UnknownReason, STRATEGY_NAMES,
+};
+use std::time::Duration;
+
+mod check;
 
 #[derive(Parser)]
 #[command(name = "lolli")]
@@ -47,6 +53,47 @@ enum Commands {
         /// Output format: tree, latex, dot
         #[arg(short, long, default_value = "tree")]
         format: String,
+
+        /// If there is no proof, report leaked and missing resources and the
+        /// deepest partial proof
+        #[arg(short, long)]
+        explain: bool,
+
+        #[command(flatten)]
+        limits: LimitArgs,
+
+        /// Search strategy: dfs, iterative-deepening, or smallest-first (depth-first,
+        /// trying the alternative that leaves the smallest sequent first)
+        #[arg(short, long, default_value = "dfs")]
+        strategy: String,
+    },
+
+    /// Suggest the fewest formulas to add to make a sequent provable
+    Abduce {
+        /// Sequent to repair (e.g., "Pool |- Pool * Available")
+        sequent: String,
+
+        /// Comma-separated formulas that may be added (default: the atoms of the sequent)
+        #[arg(short, long)]
+        candidates: Option<String>,
+
+        /// Maximum number of formulas to add
+        #[arg(short, long, default_value = "4")]
+        max: usize,
+
+        /// Maximum search depth
+        #[arg(short, long, default_value = "100")]
+        depth: usize,
+    },
+
+    /// Compute the resources left over after proving a goal
+    Residual {
+        /// Context and goal (e.g., "Pool, Available, Available |- Pool * Available")
+        sequent: String,
+
+        /// Maximum search depth
+        #[arg(short, long, default_value = "100")]
+        depth: usize,
     },
 
     /// Extract a term from a proof
@@ -57,6 +104,32 @@ enum Commands {
         /// Normalize the extracted term
         #[arg(short, long)]
         normalize: bool,
+
+        /// List the terms of every proof, one per way of linking atoms and choosing sides
+        #[arg(short, long)]
+        all: bool,
+
+        /// Print each reduction step of normalizing the extracted term
+        #[arg(short, long, conflicts_with = "all")]
+        trace: bool,
+
+        /// Reduction strategy: normal-order, call-by-name, call-by-value
+        #[arg(short, long, default_value = "normal-order")]
+        strategy: String,
+
+        /// With --all, stop after this many proofs
+        #[arg(long, default_value = "20")]
+        limit: usize,
+
+        /// Maximum search depth
+        #[arg(short, long, default_value = "100")]
+        depth: usize,
+    },
+
+    /// Infer the most general type of a λ-term
+    Type {
+        /// Term to type (e.g., "\\x. \\y. (x, y)")
+        term: String,
     },
 
     /// Generate Rust code from a proof
@@ -74,7 +147,7 @@ enum Commands {
         /// Sequent to prove
         sequent: String,
 
-        /// Output format: tree, latex, dot, svg
+        /// Output format: tree, latex, dot, net (proof net as DOT), svg
         #[arg(short, long, default_value = "tree")]
         format: String,
 
@@ -83,10 +156,82 @@ enum Commands {
         output: Option<String>,
     },
 
+    /// Prove every obligation in a spec file
+    Check {
+        /// Spec file with atom/def/prove declarations
+        file: String,
+
+        /// Maximum search depth
+        #[arg(short, long, default_value = "100")]
+        depth: usize,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = ReportFormat::Table)]
+        format: ReportFormat,
+
+        #[command(flatten)]
+        limits: LimitArgs,
+
+        /// Write the report to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Check a hand-written proof script
+    Verify {
+        /// Proof script with one `rule: sequent` line per step
+        file: String,
+    },
+
     /// Run interactive REPL
     Repl,
 }
 
+/// How `lolli check` renders its report
+#[derive(Clone, Copy, ValueEnum)]
+enum ReportFormat {
+    /// Pass/fail table
+    Table,
+    /// JSON document
+    Json,
+    /// JUnit XML, for CI
+    Junit,
+}
+
+/// Bounds on each proof search
+#[derive(Args)]
+struct LimitArgs {
+    /// Give up on a search after this many seconds
+    #[arg(long, value_parser = parse_seconds)]
+    timeout: Option<Duration>,
+
+    /// Give up on a search after exploring this many sequents
+    #[arg(long)]
+    max_sequents: Option<usize>,
+
+    /// Give up on a search once its caches hold this many entries
+    #[arg(long)]
+    max_cache_entries: Option<usize>,
+}
+
+impl LimitArgs {
+    fn limits(&self) -> SearchLimits {
+        SearchLimits {
+            max_sequents: self.max_sequents,
+            timeout: self.timeout,
+            max_cache_entries: self.max_cache_entries,
+        }
+    }
+}
+
+/// Parse a number of seconds, which must be finite and non-negative.
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    s.parse()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| "expected a finite, non-negative number of seconds".to_string())
+}
+
 fn main() {
     let cli = Cli::parse();
 
@@ -151,7 +296,19 @@ fn main() {
             sequent,
             depth,
             format,
+            explain,
+            limits,
+            strategy,
         } => {
+            let Some(strategy) = strategy_by_name(&strategy) else {
+                eprintln!(
+                    "{} Unknown strategy '{}' (expected one of: {})",
+                    "Error:".red().bold(),
+                    strategy,
+                    STRATEGY_NAMES.join(", ")
+                );
+                std::process::exit(1);
+            };
             match parse_sequent(&sequent) {
                 Ok(s) => {
                     println!("{}", "Sequent:".green().bold());
@@ -165,9 +322,11 @@ fn main() {
                     println!();
 
                     let mut prover = Prover::new(depth);
+                    prover.limits = limits.limits();
+                    prover.strategy = strategy;
 
-                    match prover.prove(&one_sided) {
-                        Some(proof) => {
+                    match prover.decide(&one_sided) {
+                        ProofOutcome::Provable(proof) => {
                             println!("{}", "✓ PROVABLE".green().bold());
                             println!();
                             println!("{}", "Proof:".cyan().bold());
@@ -187,9 +346,30 @@ fn main() {
                             println!("{} {}", "Depth:".yellow(), proof.depth());
                             println!("{} {}", "Cut count:".yellow(), proof.cut_count());
                         }
-                        None => {
+                        ProofOutcome::Refuted => {
                             println!("{}", "✗ NOT PROVABLE".red().bold());
-                            println!("  (within depth limit of {})", depth);
+                            match check_balance(&one_sided) {
+                                Err(unbalanced) => {
+                                    println!("  (atom counts do not balance)");
+                                    for imbalance in unbalanced {
+                                        println!("    {}", imbalance);
+                                    }
+                                }
+                                Ok(()) => println!("  (search space exhausted)"),
+                            }
+                            if explain {
+                                print_explanation(&mut prover, &s);
+                            }
+                        }
+                        ProofOutcome::Unknown(reason) => {
+                            println!("{}", "? UNKNOWN".yellow().bold());
+                            println!("  (no proof found; {})", reason);
+                            if reason == UnknownReason::DepthLimit {
+                                println!("  Try a larger --depth than {}", depth);
+                            }
+                            if explain {
+                                print_explanation(&mut prover, &s);
+                            }
                         }
                     }
                 }
@@ -200,7 +380,120 @@ fn main() {
             }
         }
 
-        Commands::Extract { sequent, normalize: should_normalize } => {
+        Commands::Abduce {
+            sequent,
+            candidates,
+            max,
+            depth,
+        } => {
+            let s = match parse_sequent(&sequent) {
+                Ok(s) => s,
+                Err(e) => {
+                    eprintln!("{} {}", "Error:".red().bold(), e);
+                    std::process::exit(1);
+                }
+            };
+            let candidates: Vec<Formula> = match candidates
+                .iter()
+                .flat_map(|list| list.split(','))
+                .map(|f| parse_formula(f.trim()))
+                .collect()
+            {
+                Ok(candidates) => candidates,
+                Err(e) => {
+                    eprintln!("{} {}", "Error:".red().bold(), e);
+                    std::process::exit(1);
+                }
+            };
+
+            println!("{}", "Sequent:".green().bold());
+            println!("  {}", s.pretty());
+            println!();
+
+            let mut prover = Prover::new(depth);
+            match prover.abduce(&s, &candidates, max) {
+                Some(fix) if fix.is_empty() => {
+                    println!("{}", "✓ PROVABLE".green().bold());
+                    println!("  (nothing to add)");
+                }
+                Some(fix) => {
+                    println!("{}", "Suggested fix:".cyan().bold());
+                    for line in fix.suggestions() {
+                        println!("  {}", line);
+                    }
+                    println!();
+                    println!("{}", "✓ PROVABLE after the fix".green().bold());
+                    println!("  {}", fix.apply(&s).pretty());
+                    println!();
+                    println!("{}", "Proof:".cyan().bold());
+                    print_proof_tree(&fix.proof, 0);
+                }
+                None => {
+                    println!("{}", "✗ NO FIX FOUND".red().bold());
+                    println!("  (no addition of at most {} formulas makes it provable)", max);
+                    if candidates.is_empty() {
+                        println!("  Leftover hypotheses need a consumer, e.g. --candidates \"A -o 1\"");
+                    }
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Residual { sequent, depth } => {
+            let s = match parse_sequent(&sequent) {
+                Ok(s) => s,
+                Err(e) => {
+                    eprintln!("{} {}", "Error:".red().bold(), e);
+                    std::process::exit(1);
+                }
+            };
+            let [goal] = s.succedent.as_slice() else {
+                eprintln!("{} expected a single goal formula after ⊢", "Error:".red().bold());
+                std::process::exit(1);
+            };
+
+            println!("{}", "Sequent:".green().bold());
+            println!("  {}", s.pretty());
+            println!();
+
+            let mut prover = Prover::new(depth);
+            match prover.residual(&s.antecedent, goal) {
+                Some(residual) => {
+                    println!("{}", "Residual:".cyan().bold());
+                    println!("  {}", residual.formula().pretty());
+                    println!();
+                    println!("{} {}", "✓ PROVABLE".green().bold(), residual.sequent.pretty());
+                    println!();
+                    println!("{}", "Proof:".cyan().bold());
+                    print_proof_tree(&residual.proof, 0);
+                }
+                None => {
+                    println!("{}", "✗ GOAL NOT REACHABLE".red().bold());
+                    println!("  (no leftover atoms make the goal provable)");
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Commands::Extract {
+            sequent,
+            normalize: should_normalize,
+            all,
+            trace,
+            strategy,
+            limit,
+            depth,
+        } => {
+            let Some(strategy) = Strategy::from_name(&strategy) else {
+                let names: Vec<_> = Strategy::ALL.iter().map(Strategy::name).collect();
+                eprintln!(
+                    "{} Unknown strategy '{}' (expected one of: {})",
+                    "Error:".red().bold(),
+                    strategy,
+                    names.join(", ")
+                );
+                std::process::exit(1);
+            };
             match parse_sequent(&sequent) {
                 Ok(s) => {
                     println!("{}", "Sequent:".green().bold());
@@ -209,7 +502,44 @@ fn main() {
 
                     // Convert to one-sided and prove
                     let one_sided = s.to_one_sided();
-                    let mut prover = Prover::new(100);
+                    let mut prover = Prover::new(depth);
+
+                    if all {
+                        let mut terms: Vec<Term> = Vec::new();
+                        let mut error = None;
+                        for proof in prover.proofs(&one_sided).up_to_permutation().take(limit) {
+                            let term = match extract_term(&proof) {
+                                Ok(term) => term,
+                                Err(e) => {
+                                    error = Some(e);
+                                    continue;
+                                }
+                            };
+                            let term = if should_normalize {
+                                normalize_with(&term, strategy)
+                            } else {
+                                term
+                            };
+                            if !terms.iter().any(|t| t.alpha_eq(&term)) {
+                                terms.push(term);
+                            }
+                        }
+                        if let (true, Some(e)) = (terms.is_empty(), error) {
+                            println!("{}", "✓ Provable".green());
+                            eprintln!("{} {}", "Error:".red().bold(), e);
+                            std::process::exit(1);
+                        } else if terms.is_empty() {
+                            println!("{}", "✗ NO PROOF FOUND".red().bold());
+                            println!("  (no proof within depth {})", depth);
+                        } else {
+                            println!("{} {} distinct term(s)", "✓ Provable,".green(), terms.len());
+                            println!();
+                            for (i, term) in terms.iter().enumerate() {
+                                println!("  {}. {}", i + 1, term.pretty());
+                            }
+                        }
+                        return;
+                    }
 
                     match prover.prove(&one_sided) {
                         Some(proof) => {
@@ -217,14 +547,23 @@ fn main() {
                             println!();
 
                             // Extract term from proof
-                            let term = extract_term(&proof);
+                            let term = match extract_term(&proof) {
+                                Ok(term) => term,
+                                Err(e) => {
+                                    eprintln!("{} {}", "Error:".red().bold(), e);
+                                    std::process::exit(1);
+                                }
+                            };
 
                             println!("{}", "Extracted term:".cyan().bold());
                             println!("  {}", term.pretty());
 
-                            if should_normalize {
+                            if trace {
                                 println!();
-                                let normalized = normalize(&term);
+                                print_trace(&lolli_extract::trace(&term, strategy), strategy);
+                            } else if should_normalize {
+                                println!();
+                                let normalized = normalize_with(&term, strategy);
                                 println!("{}", "Normalized:".yellow().bold());
                                 println!("  {}", normalized.pretty());
                             }
@@ -242,6 +581,37 @@ fn main() {
             }
         }
 
+        Commands::Type { term } => {
+            let term = match parse_term(&term) {
+                Ok(term) => term,
+                Err(e) => {
+                    eprintln!("{} {}", "Error:".red().bold(), e);
+                    std::process::exit(1);
+                }
+            };
+            println!("{}", "Term:".green().bold());
+            println!("  {}", term.pretty());
+            println!();
+
+            match lolli_core::infer_type(&term) {
+                Ok(typing) => {
+                    println!("{}", "Type:".cyan().bold());
+                    println!("  {}", typing.ty.pretty());
+                    if !typing.context.is_empty() {
+                        println!();
+                        println!("{}", "Free variables:".cyan().bold());
+                        for (x, ty) in &typing.context {
+                            println!("  {} : {}", x, ty.pretty());
+                        }
+                    }
+                }
+                Err(e) => {
+                    println!("{} {}", "✗ ILL-TYPED:".red().bold(), e);
+                    std::process::exit(1);
+                }
+            }
+        }
+
         Commands::Codegen { sequent, output } => {
             match parse_sequent(&sequent) {
                 Ok(s) => {
@@ -259,7 +629,13 @@ fn main() {
                             println!();
 
                             // Extract term from proof
-                            let term = extract_term(&proof);
+                            let term = match extract_term(&proof) {
+                                Ok(term) => term,
+                                Err(e) => {
+                                    eprintln!("{} {}", "Error:".red().bold(), e);
+                                    std::process::exit(1);
+                                }
+                            };
 
                             // Generate code
                             use lolli_codegen::RustCodegen;
@@ -326,11 +702,18 @@ fn main() {
                             println!();
 
                             // Generate visualization
-                            use lolli_viz::{render_ascii, render_latex, render_dot};
+                            use lolli_viz::{render_ascii, render_latex, render_dot, render_proof_net};
 
                             let viz = match format.as_str() {
                                 "latex" => render_latex(&proof),
                                 "dot" => render_dot(&proof),
+                                "net" => match ProofNet::from_proof(&proof) {
+                                    Ok(net) => render_proof_net(&net),
+                                    Err(e) => {
+                                        eprintln!("{} {}", "Error:".red().bold(), e);
+                                        std::process::exit(1);
+                                    }
+                                },
                                 "svg" => {
                                     println!("{}", "SVG output not yet implemented".yellow());
                                     render_dot(&proof) // Fall back to DOT
@@ -368,13 +751,184 @@ fn main() {
             }
         }
 
+        Commands::Check {
+            file,
+            depth,
+            format,
+            limits,
+            output,
+        } => {
+            let source = match std::fs::read_to_string(&file) {
+                Ok(source) => source,
+                Err(e) => {
+                    eprintln!("{} Failed to read {}: {}", "Error:".red().bold(), file, e);
+                    std::process::exit(2);
+                }
+            };
+            let spec = match lolli_parse::parse_file(&source) {
+                Ok(spec) => spec,
+                Err(e) => {
+                    eprintln!("{} {}: {}", "Error:".red().bold(), file, e);
+                    std::process::exit(2);
+                }
+            };
+
+            let report = check::check_spec(&file, &spec, depth, &limits.limits());
+            let rendered = match format {
+                ReportFormat::Table => check::render_table(&report),
+                ReportFormat::Json => check::render_json(&report),
+                ReportFormat::Junit => check::render_junit(&report),
+            };
+
+            match output {
+                Some(path) => {
+                    if let Err(e) = std::fs::write(&path, &rendered) {
+                        eprintln!("{} Failed to write file: {}", "Error:".red().bold(), e);
+                        std::process::exit(2);
+                    }
+                    println!(
+                        "{} passed, {} failed, {} unknown — report written to {}",
+                        report.passed(),
+                        report.failed(),
+                        report.unknown(),
+                        path
+                    );
+                }
+                None => println!("{}", rendered),
+            }
+
+            if !report.is_success() {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::Verify { file } => {
+            let source = match std::fs::read_to_string(&file) {
+                Ok(source) => source,
+                Err(e) => {
+                    eprintln!("{} Failed to read {}: {}", "Error:".red().bold(), file, e);
+                    std::process::exit(2);
+                }
+            };
+            let script = match lolli_parse::parse_proof(&source) {
+                Ok(script) => script,
+                Err(e) => {
+                    eprintln!("{} {}: {}", "Error:".red().bold(), file, e);
+                    std::process::exit(2);
+                }
+            };
+
+            match lolli_prove::verify_proof(&script.proof) {
+                Ok(()) => {
+                    println!(
+                        "{} {}",
+                        "✓ VALID".green().bold(),
+                        script.proof.conclusion.pretty()
+                    );
+                }
+                Err(errors) => {
+                    println!(
+                        "{} {}",
+                        "✗ INVALID".red().bold(),
+                        script.proof.conclusion.pretty()
+                    );
+                    println!();
+                    for error in &errors {
+                        let line = script
+                            .position(&error.path)
+                            .map(|p| format!(":{}:{}", p.line, p.column))
+                            .unwrap_or_default();
+                        println!("  {}{}: {}", file, line, error);
+                    }
+                    println!();
+                    let renderer = lolli_viz::TreeRenderer {
+                        highlight: errors.into_iter().map(|e| e.path).collect(),
+                        ..lolli_viz::TreeRenderer::default()
+                    };
+                    println!("{}", renderer.render(&script.proof));
+                    std::process::exit(1);
+                }
+            }
+        }
+
         Commands::Repl => {
             run_repl();
         }
     }
 }
 
-use lolli_core::Proof;
+use lolli_core::{Formula, Proof, ProofNet, Sequent, Term, TwoSidedSequent};
+
+/// Print the reduction sequence of `extract --trace`, one step per line.
+fn print_trace(trace: &Trace, strategy: Strategy) {
+    println!(
+        "{} {}",
+        "Reduction".yellow().bold(),
+        format!("({}, {} steps):", strategy.name(), trace.steps.len()).yellow()
+    );
+    println!("  0. {}", trace.start.pretty());
+    for (i, step) in trace.steps.iter().enumerate() {
+        println!("  {}. {}", i + 1, step.term.pretty());
+        println!(
+            "     {}",
+            format!(
+                "{}: {} ⟶ {}",
+                step.rule,
+                step.redex.pretty(),
+                step.contractum.pretty()
+            )
+            .dimmed()
+        );
+    }
+}
+
+/// Print what `prove --explain` reports for a sequent without a proof.
+fn print_explanation(prover: &mut Prover, seq: &TwoSidedSequent) {
+    let explanation = prover.explain(seq);
+    println!();
+    println!("{}", "Explanation:".cyan().bold());
+    let summary = explanation.summary();
+    if explanation.repaired.is_none() {
+        println!("  No leaked or missing resources account for the failure");
+    } else if summary.is_empty() {
+        println!("  No leaked or missing resources found");
+    }
+    for line in summary {
+        println!("  {}", line);
+    }
+    if let Some(partial) = &explanation.partial {
+        println!();
+        println!("{}", "Deepest partial proof:".cyan().bold());
+        print_partial_tree(partial, 0);
+    }
+}
+
+/// Print a partial proof like [`print_proof_tree`], marking open goals
+fn print_partial_tree(partial: &PartialProof, indent: usize) {
+    let prefix = "  ".repeat(indent);
+    for premise in &partial.premises {
+        print_partial_tree(premise, indent + 1);
+    }
+
+    let conclusion = partial
+        .conclusion
+        .linear
+        .iter()
+        .map(|f| f.pretty())
+        .collect::<Vec<_>>()
+        .join(", ");
+    match &partial.rule {
+        None => println!("{}⊢ {}  {}", prefix, conclusion, "(open)".yellow()),
+        Some(rule) => {
+            if !partial.premises.is_empty() {
+                let line_len = conclusion.len().max(20);
+                let rule_name = format!("{:?}", rule);
+                println!("{}{}  {}", prefix, "─".repeat(line_len), rule_name.cyan());
+            }
+            println!("{}⊢ {}", prefix, conclusion);
+        }
+    }
+}
 
 /// Print a proof tree in ASCII format
 fn print_proof_tree(proof: &Proof, indent: usize) {
@@ -472,15 +1026,19 @@ fn run_repl() {
     println!("Commands:");
     println!("  {}       - Parse and analyze a formula", "formula".green());
     println!("  {}    - Prove a sequent (e.g., A, B |- A * B)", "seq |-".green());
+    println!("  {}     - Prove a sequent step by step with tactics", ":goal seq".yellow());
+    println!("  {}    - Type and normalize a λ-term", ":term term".yellow());
     println!("  {}           - Show this help", ":help".yellow());
     println!("  {}           - Exit the REPL", ":quit".yellow());
     println!();
 
     let stdin = io::stdin();
     let mut stdout = io::stdout();
+    let mut state: Option<ProofState> = None;
 
     loop {
-        print!("{} ", "lolli>".cyan().bold());
+        let prompt = if state.is_some() { "goal>" } else { "lolli>" };
+        print!("{} ", prompt.cyan().bold());
         stdout.flush().unwrap();
 
         let mut input = String::new();
@@ -500,7 +1058,31 @@ fn run_repl() {
 
         // Handle commands
         if input.starts_with(':') {
+            if let Some(sequent) = input.strip_prefix(":goal ") {
+                match parse_sequent(sequent) {
+                    Ok(s) => {
+                        let goal = ProofState::new(s.to_one_sided());
+                        print_goals(&goal);
+                        state = Some(goal);
+                    }
+                    Err(e) => println!("{} {}", "Parse error:".red(), e),
+                }
+                continue;
+            }
+            if let Some(term) = input.strip_prefix(":term ") {
+                handle_term(term);
+                continue;
+            }
             match input {
+                ":goals" => match &state {
+                    Some(goal) => print_goals(goal),
+                    None => println!("No goal; start one with {}", ":goal seq".yellow()),
+                },
+                ":abort" => {
+                    if state.take().is_some() {
+                        println!("Goal abandoned");
+                    }
+                }
                 ":quit" | ":q" | ":exit" => {
                     println!("{}", "Goodbye!".green());
                     break;
@@ -516,6 +1098,14 @@ fn run_repl() {
             continue;
         }
 
+        if let Some(goal) = &mut state {
+            let command = input.split_whitespace().next().unwrap_or_default();
+            if command == "undo" || TACTICS.contains(&command) {
+                handle_tactic(goal, input);
+                continue;
+            }
+        }
+
         // Check if it's a sequent (contains |- or ⊢)
         if input.contains("|-") || input.contains("⊢") {
             handle_sequent(input);
@@ -546,6 +1136,27 @@ fn print_repl_help() {
     println!("  A, B |- C         Two-sided sequent");
     println!("  |- A, B           One-sided sequent");
     println!();
+    println!("{}", "Tactics:".yellow());
+    println!("  :goal A, B |- A * B   Start proving a sequent step by step");
+    println!("  axiom, one, top       Close the current goal");
+    println!("  par N, bottom N       Decompose formula N of the current goal");
+    println!("  with N                Prove both halves of formula N");
+    println!("  left N, right N       Prove one half of the ⊕ formula N");
+    println!("  tensor N              Split ⊗ formula N, finding a context split");
+    println!("  split i j ...         Split the first ⊗, sending formulas i, j ... left");
+    println!("  promote N             Strip the ! of formula N");
+    println!("  derelict N, weaken N, contract N   Use, drop or copy the ? formula N");
+    println!("  auto                  Close the current goal by proof search");
+    println!("  undo                  Take back the last tactic");
+    println!("  :goals, :abort        Show the open goals, or give up");
+    println!("  N may be left out to pick the first formula that fits.");
+    println!();
+    println!("{}", "Terms:".yellow());
+    println!("  :term \\p. let (a, b) = p in (b, a)   Infer a term's type and normalize it");
+    println!("  λx. e or \\x. e, f x, (a, b), (), ⟨⟩, let (x, y) = e in e'");
+    println!("  inl e, inr e, case e of {{ inl x => e1 | inr y => e2 }}, fst e, snd e, absurd e");
+    println!("  !e, derelict e, copy e as (x, y) in e', discard e in e'");
+    println!();
     println!("{}", "Examples:".yellow());
     println!("  A -o B            Parse a formula");
     println!("  A, B |- A * B     Prove tensor introduction");
@@ -572,6 +1183,30 @@ fn handle_formula(input: &str) {
     }
 }
 
+fn handle_term(input: &str) {
+    let term = match parse_term(input) {
+        Ok(term) => term,
+        Err(e) => {
+            println!("{} {}", "Parse error:".red(), e);
+            return;
+        }
+    };
+    println!();
+    println!("{} {}", "Parsed:".green(), term.pretty());
+    match lolli_core::infer_type(&term) {
+        Ok(typing) => {
+            println!("{} {}", "Type:".cyan(), typing.pretty());
+            // Only well-typed terms are sure to normalize
+            let normalized = normalize(&term);
+            if normalized != term {
+                println!("{} {}", "Normalized:".yellow(), normalized.pretty());
+            }
+        }
+        Err(e) => println!("{} {}", "✗ ILL-TYPED:".red().bold(), e),
+    }
+    println!();
+}
+
 fn handle_sequent(input: &str) {
     match parse_sequent(input) {
         Ok(s) => {
@@ -581,8 +1216,8 @@ fn handle_sequent(input: &str) {
             let one_sided = s.to_one_sided();
             let mut prover = Prover::new(100);
 
-            match prover.prove(&one_sided) {
-                Some(proof) => {
+            match prover.decide(&one_sided) {
+                ProofOutcome::Provable(proof) => {
                     println!("{}", "✓ PROVABLE".green().bold());
                     println!();
 
@@ -592,19 +1227,33 @@ fn handle_sequent(input: &str) {
                     println!();
 
                     // Show extracted term
-                    let term = extract_term(&proof);
-                    println!("{} {}", "Extracted term:".yellow(), term.pretty());
-
-                    // Show normalized term
-                    let normalized = normalize(&term);
-                    if normalized != term {
-                        println!("{} {}", "Normalized:".yellow(), normalized.pretty());
+                    match extract_term(&proof) {
+                        Ok(term) => {
+                            println!("{} {}", "Extracted term:".yellow(), term.pretty());
+
+                            // Show normalized term
+                            let normalized = normalize(&term);
+                            if normalized != term {
+                                println!("{} {}", "Normalized:".yellow(), normalized.pretty());
+                            }
+                        }
+                        Err(e) => println!("{} {}", "No term:".yellow(), e),
                     }
                     println!();
                 }
-                None => {
+                ProofOutcome::Refuted => {
                     println!("{}", "✗ NOT PROVABLE".red().bold());
                     println!("  (in linear logic without contraction/weakening)");
+                    if let Err(unbalanced) = check_balance(&one_sided) {
+                        for imbalance in unbalanced {
+                            println!("  {} {}", "Unbalanced:".yellow(), imbalance);
+                        }
+                    }
+                    println!();
+                }
+                ProofOutcome::Unknown(reason) => {
+                    println!("{}", "? UNKNOWN".yellow().bold());
+                    println!("  (no proof found; {})", reason);
                     println!();
                 }
             }
@@ -614,3 +1263,111 @@ fn handle_sequent(input: &str) {
         }
     }
 }
+
+/// The tactic commands of the REPL's goal mode.
+const TACTICS: [&str; 15] = [
+    "axiom", "one", "top", "auto", "bottom", "par", "with", "left", "right", "tensor", "split",
+    "promote", "derelict", "weaken", "contract",
+];
+
+fn handle_tactic(state: &mut ProofState, input: &str) {
+    let result = if input == "undo" {
+        state.undo().map_err(|e| e.to_string())
+    } else {
+        state
+            .current()
+            .ok_or_else(|| "No goals left".to_string())
+            .and_then(|goal| parse_tactic(input, goal))
+            .and_then(|tactic| state.apply(tactic).map_err(|e| e.to_string()))
+    };
+    if let Err(e) = result {
+        println!("{} {}", "Error:".red(), e);
+        return;
+    }
+
+    print_goals(state);
+    if let Some(proof) = state.proof() {
+        println!("{}", "Proof:".cyan());
+        print_proof_tree(&proof, 0);
+        println!();
+    }
+}
+
+/// Read a tactic command, numbering the formulas of `goal` from 1.
+fn parse_tactic(input: &str, goal: &Sequent) -> Result<Tactic, String> {
+    let mut words = input.split_whitespace();
+    let command = words.next().unwrap_or_default();
+    let numbers = words
+        .map(|w| match w.parse::<usize>() {
+            Ok(n) if n >= 1 => Ok(n - 1),
+            _ => Err(format!("Expected a formula number, got `{}`", w)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // The given formula, or else the first one of the right shape
+    let pick = |fits: fn(&Formula) -> bool| match numbers.first() {
+        Some(&i) => Ok(i),
+        None => goal
+            .linear
+            .iter()
+            .position(fits)
+            .ok_or_else(|| format!("No formula in the goal fits `{}`", command)),
+    };
+    let is_tensor = |f: &Formula| matches!(f, Formula::Tensor(..));
+    let is_why_not = |f: &Formula| matches!(f, Formula::WhyNot(_));
+
+    Ok(match command {
+        "axiom" => Tactic::Axiom,
+        "one" => Tactic::One,
+        "top" => Tactic::Top,
+        "auto" => Tactic::Auto,
+        "bottom" => Tactic::Bottom(pick(|f| matches!(f, Formula::Bottom))?),
+        "par" => Tactic::Par(pick(|f| {
+            matches!(f, Formula::Par(..) | Formula::Lolli(..))
+        })?),
+        "with" => Tactic::With(pick(|f| matches!(f, Formula::With(..)))?),
+        "left" => Tactic::PlusLeft(pick(|f| matches!(f, Formula::Plus(..)))?),
+        "right" => Tactic::PlusRight(pick(|f| matches!(f, Formula::Plus(..)))?),
+        "tensor" => Tactic::Tensor {
+            formula: pick(is_tensor)?,
+            left: None,
+        },
+        "split" => Tactic::Tensor {
+            formula: goal
+                .linear
+                .iter()
+                .position(is_tensor)
+                .ok_or("No ⊗ formula in the goal to split")?,
+            left: Some(numbers),
+        },
+        "promote" => Tactic::Promotion(pick(|f| matches!(f, Formula::OfCourse(_)))?),
+        "derelict" => Tactic::Dereliction(pick(is_why_not)?),
+        "weaken" => Tactic::Weakening(pick(is_why_not)?),
+        "contract" => Tactic::Contraction(pick(is_why_not)?),
+        _ => return Err(format!("Unknown tactic: {}", command)),
+    })
+}
+
+/// Print the open goals, numbering the formulas of the current one.
+fn print_goals(state: &ProofState) {
+    let goals = state.goals();
+    println!();
+    let Some((current, rest)) = goals.split_first() else {
+        println!("{}", "✓ No goals left".green().bold());
+        return;
+    };
+
+    let count = if goals.len() == 1 {
+        "1 goal".to_string()
+    } else {
+        format!("{} goals", goals.len())
+    };
+    println!("{}", count.cyan().bold());
+    for (i, formula) in current.linear.iter().enumerate() {
+        println!("  {}. {}", i + 1, formula.pretty());
+    }
+    for goal in rest {
+        println!("  {}", goal.pretty().dimmed());
+    }
+    println!();
+}
@@ -23,10 +23,14 @@
 
 pub mod formula;
 pub mod proof;
+pub mod proof_net;
 pub mod sequent;
 pub mod term;
+pub mod typing;
 
 pub use formula::Formula;
-pub use proof::{Proof, Rule};
+pub use proof::{Proof, ProofPath, Rule};
+pub use proof_net::{Link, NetError, ProofNet, UnionFind};
 pub use sequent::{Sequent, TwoSidedSequent};
 pub use term::Term;
+pub use typing::{infer_type, type_check, TypeError, Typing};
@@ -90,6 +90,42 @@ impl Proof {
             1 + self.premises.iter().map(|p| p.depth()).max().unwrap_or(0)
         }
     }
+
+    /// The subproof at `path`, if there is one.
+    pub fn at(&self, path: &ProofPath) -> Option<&Proof> {
+        path.0
+            .iter()
+            .try_fold(self, |proof, &i| proof.premises.get(i))
+    }
+}
+
+/// The position of a subproof: the index of the premise taken at each step
+/// down from the root.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ProofPath(pub Vec<usize>);
+
+impl ProofPath {
+    /// The path to the root.
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    /// The path to premise `i` of the subproof at this path.
+    pub fn child(&self, i: usize) -> Self {
+        let mut steps = self.0.clone();
+        steps.push(i);
+        ProofPath(steps)
+    }
+}
+
+impl std::fmt::Display for ProofPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "root");
+        }
+        let steps: Vec<String> = self.0.iter().map(|i| format!("premise {}", i)).collect();
+        write!(f, "{}", steps.join(" → "))
+    }
 }
 
 #[cfg(test)]
@@ -123,4 +159,24 @@ mod tests {
         };
         assert_eq!(with_premise.depth(), 2);
     }
+
+    #[test]
+    fn test_path() {
+        let leaf = Proof {
+            conclusion: Sequent::new(vec![]),
+            rule: Rule::Axiom,
+            premises: vec![],
+        };
+        let proof = Proof {
+            conclusion: Sequent::new(vec![]),
+            rule: Rule::TensorIntro,
+            premises: vec![leaf.clone(), leaf],
+        };
+        let path = ProofPath::root().child(1);
+        assert_eq!(path.to_string(), "premise 1");
+        assert_eq!(path.child(0).to_string(), "premise 1 → premise 0");
+        assert_eq!(ProofPath::root().to_string(), "root");
+        assert_eq!(proof.at(&path).unwrap().rule, Rule::Axiom);
+        assert!(proof.at(&path.child(0)).is_none());
+    }
 }
@@ -0,0 +1,858 @@
+//! Proof nets for multiplicative linear logic.
+//!
+//! A proof net represents a proof of unit-free MLL, possibly with cuts, as
+//! a graph: formula occurrences joined by axiom, cut, tensor and par links.
+//! Proofs that differ only in the order of their rules have the same net.
+//!
+//! Not every graph of links is a proof. A net is correct when it satisfies
+//! the Danos–Regnier criterion: every switching, which keeps one premise of
+//! each par, is a tree. Correct nets are exactly those that
+//! [sequentialize](ProofNet::sequentialize) into a [`Proof`].
+
+use crate::{Formula, Proof, Rule, Sequent};
+
+/// A link of a proof net. Occurrences are indices into
+/// [`ProofNet::formulas`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Link {
+    /// Axiom link with conclusions `A` and `A⊥`
+    Axiom(usize, usize),
+    /// Cut link with premises `A` and `A⊥`
+    Cut(usize, usize),
+    /// Tensor link from `A` and `B` to `A ⊗ B`
+    Tensor {
+        /// The occurrence of `A`
+        left: usize,
+        /// The occurrence of `B`
+        right: usize,
+        /// The occurrence of `A ⊗ B`
+        conclusion: usize,
+    },
+    /// Par link from `A` and `B` to `A ⅋ B`, or from `A⊥` and `B` to `A ⊸ B`
+    Par {
+        /// The occurrence of `A`, or of `A⊥` for `A ⊸ B`
+        left: usize,
+        /// The occurrence of `B`
+        right: usize,
+        /// The occurrence of the par
+        conclusion: usize,
+    },
+}
+
+/// A proof structure for unit-free MLL.
+#[derive(Debug, Clone, Default)]
+pub struct ProofNet {
+    /// The formula of each occurrence
+    pub formulas: Vec<Formula>,
+    /// The links between occurrences
+    pub links: Vec<Link>,
+    /// The occurrences that no link consumes, in the order of the sequent
+    pub conclusions: Vec<usize>,
+}
+
+/// Why a proof has no net, or a net is not a proof.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum NetError {
+    /// The proof uses a rule outside unit-free MLL with cut
+    #[error("Rule {0:?} has no proof-net counterpart")]
+    UnsupportedRule(Rule),
+
+    /// A proof step's premises do not fit its conclusion
+    #[error("Premises of {rule:?} do not match conclusion {conclusion}")]
+    Mismatch {
+        /// The rule applied
+        rule: Rule,
+        /// The conclusion of the proof step
+        conclusion: String,
+    },
+
+    /// The links do not form a proof structure
+    #[error("Malformed proof structure: {0}")]
+    Malformed(String),
+
+    /// Some switching has a cycle
+    #[error("Some switching of the net has a cycle")]
+    Cyclic,
+
+    /// Some switching is not connected
+    #[error("Some switching of the net is disconnected")]
+    Disconnected,
+}
+
+/// Where a link sits relative to an occurrence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    /// The link produces the occurrence
+    Above,
+    /// The link consumes the occurrence
+    Below,
+}
+
+impl ProofNet {
+    /// Build the net of an MLL proof.
+    ///
+    /// Focusing rules are looked through. Equal formulas of a sequent are
+    /// interchangeable, so the net is one of possibly several that fit the
+    /// proof.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NetError::UnsupportedRule`] for rules outside unit-free MLL
+    /// with cut, and [`NetError::Mismatch`] if a step's premises do not fit
+    /// its conclusion.
+    pub fn from_proof(proof: &Proof) -> Result<Self, NetError> {
+        let mut net = ProofNet::default();
+        net.conclusions = net.translate(proof)?;
+        Ok(net)
+    }
+
+    /// Add the links of `proof`, returning the occurrences of its
+    /// conclusion in order.
+    fn translate(&mut self, proof: &Proof) -> Result<Vec<usize>, NetError> {
+        let linear = &proof.conclusion.linear;
+        let mismatch = || NetError::Mismatch {
+            rule: proof.rule.clone(),
+            conclusion: proof.conclusion.pretty(),
+        };
+
+        match (&proof.rule, proof.premises.as_slice()) {
+            (Rule::Axiom, []) => {
+                let [a, b] = linear.as_slice() else {
+                    return Err(mismatch());
+                };
+                if !same(&a.negate(), b) {
+                    return Err(mismatch());
+                }
+                let (a, b) = (self.occurrence(a), self.occurrence(b));
+                self.links.push(Link::Axiom(a, b));
+                Ok(vec![a, b])
+            }
+
+            (Rule::ParIntro, [premise]) => {
+                let above = self.translate(premise)?;
+                for (i, formula) in linear.iter().enumerate() {
+                    let Some((a, b)) = par_parts(formula) else {
+                        continue;
+                    };
+                    let mut pool = above.clone();
+                    let (Some(left), Some(right)) =
+                        (self.take(&mut pool, &a), self.take(&mut pool, &b))
+                    else {
+                        continue;
+                    };
+                    if let Some(mut occurrences) = self.take_rest(linear, i, &mut [pool]) {
+                        let conclusion = self.occurrence(formula);
+                        self.links.push(Link::Par {
+                            left,
+                            right,
+                            conclusion,
+                        });
+                        occurrences[i] = conclusion;
+                        return Ok(occurrences);
+                    }
+                }
+                Err(mismatch())
+            }
+
+            (Rule::TensorIntro, [p, q]) => {
+                let (above_p, above_q) = (self.translate(p)?, self.translate(q)?);
+                for (i, formula) in linear.iter().enumerate() {
+                    let Formula::Tensor(a, b) = formula else {
+                        continue;
+                    };
+                    let (mut pool_p, mut pool_q) = (above_p.clone(), above_q.clone());
+                    let (Some(left), Some(right)) =
+                        (self.take(&mut pool_p, a), self.take(&mut pool_q, b))
+                    else {
+                        continue;
+                    };
+                    if let Some(mut occurrences) = self.take_rest(linear, i, &mut [pool_p, pool_q])
+                    {
+                        let conclusion = self.occurrence(formula);
+                        self.links.push(Link::Tensor {
+                            left,
+                            right,
+                            conclusion,
+                        });
+                        occurrences[i] = conclusion;
+                        return Ok(occurrences);
+                    }
+                }
+                Err(mismatch())
+            }
+
+            (Rule::Cut(formula), [p, q]) => {
+                let (mut pool_p, mut pool_q) = (self.translate(p)?, self.translate(q)?);
+                let left = self.take(&mut pool_p, formula).ok_or_else(mismatch)?;
+                let right = self
+                    .take(&mut pool_q, &formula.negate())
+                    .ok_or_else(mismatch)?;
+                self.links.push(Link::Cut(left, right));
+                self.take_rest(linear, usize::MAX, &mut [pool_p, pool_q])
+                    .ok_or_else(mismatch)
+            }
+
+            (Rule::FocusPositive(_) | Rule::FocusNegative(_) | Rule::Blur, [premise]) => {
+                let above = self.translate(premise)?;
+                self.take_rest(linear, usize::MAX, &mut [above])
+                    .ok_or_else(mismatch)
+            }
+
+            (
+                Rule::Axiom
+                | Rule::ParIntro
+                | Rule::TensorIntro
+                | Rule::Cut(_)
+                | Rule::FocusPositive(_)
+                | Rule::FocusNegative(_)
+                | Rule::Blur,
+                _,
+            ) => Err(mismatch()),
+
+            (rule, _) => Err(NetError::UnsupportedRule(rule.clone())),
+        }
+    }
+
+    /// Add an occurrence of `formula`.
+    fn occurrence(&mut self, formula: &Formula) -> usize {
+        self.formulas.push(formula.clone());
+        self.formulas.len() - 1
+    }
+
+    /// Remove an occurrence of `formula` from `pool`.
+    fn take(&self, pool: &mut Vec<usize>, formula: &Formula) -> Option<usize> {
+        let i = pool
+            .iter()
+            .position(|&o| same(&self.formulas[o], formula))?;
+        Some(pool.remove(i))
+    }
+
+    /// The occurrences of `linear`, except the one at `skip`, drawn from
+    /// `pools` so that nothing is left over. The entry at `skip` is a
+    /// placeholder.
+    fn take_rest(
+        &self,
+        linear: &[Formula],
+        skip: usize,
+        pools: &mut [Vec<usize>],
+    ) -> Option<Vec<usize>> {
+        let mut occurrences = Vec::with_capacity(linear.len());
+        for (i, formula) in linear.iter().enumerate() {
+            if i == skip {
+                occurrences.push(usize::MAX);
+                continue;
+            }
+            let occurrence = pools.iter_mut().find_map(|pool| self.take(pool, formula))?;
+            occurrences.push(occurrence);
+        }
+        pools.iter().all(Vec::is_empty).then_some(occurrences)
+    }
+
+    /// Check that the links form a proof structure satisfying the
+    /// Danos–Regnier criterion.
+    ///
+    /// Correctness is decided by Danos' contraction: axiom, cut and tensor
+    /// links merge their occurrences outright, and a par merges with its
+    /// premises once they have been merged with each other.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NetError::Malformed`] if the links do not fit together,
+    /// and [`NetError::Cyclic`] or [`NetError::Disconnected`] if the
+    /// criterion fails.
+    pub fn check(&self) -> Result<(), NetError> {
+        self.check_structure()?;
+        if self.contract()?.count() == 1 {
+            Ok(())
+        } else {
+            Err(NetError::Disconnected)
+        }
+    }
+
+    /// Run Danos' contraction on the links as they stand, returning the
+    /// contracted vertices.
+    ///
+    /// Unlike [`ProofNet::check`] this does not ask for a complete proof
+    /// structure, so it applies to a net whose axiom links are still being
+    /// chosen: a cycle found here is in every net with more links.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NetError::Cyclic`] if some switching has a cycle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a link refers to a missing occurrence.
+    pub fn contract(&self) -> Result<UnionFind, NetError> {
+        let mut vertices = UnionFind::new(self.formulas.len());
+        let mut pars = Vec::new();
+        for link in &self.links {
+            let merged = match *link {
+                Link::Axiom(a, b) | Link::Cut(a, b) => vertices.union(a, b),
+                Link::Tensor {
+                    left,
+                    right,
+                    conclusion,
+                } => vertices.union(conclusion, left) && vertices.union(conclusion, right),
+                Link::Par {
+                    left,
+                    right,
+                    conclusion,
+                } => {
+                    pars.push((conclusion, left, right));
+                    true
+                }
+            };
+            if !merged {
+                return Err(NetError::Cyclic);
+            }
+        }
+
+        loop {
+            let before = pars.len();
+            let mut cyclic = false;
+            pars.retain(|&(conclusion, left, right)| {
+                if vertices.find(left) != vertices.find(right) {
+                    return true;
+                }
+                cyclic |= !vertices.union(conclusion, left);
+                false
+            });
+            if cyclic {
+                return Err(NetError::Cyclic);
+            }
+            if pars.len() == before {
+                break;
+            }
+        }
+        Ok(vertices)
+    }
+
+    /// Returns true if this is a correct proof net.
+    pub fn is_correct(&self) -> bool {
+        self.check().is_ok()
+    }
+
+    /// Check that every occurrence has exactly one link above it, at most
+    /// one below it, and that the conclusions are the occurrences with none
+    /// below; and that every link fits its formulas.
+    fn check_structure(&self) -> Result<(), NetError> {
+        let n = self.formulas.len();
+        let mut above = vec![0; n];
+        let mut below = vec![0; n];
+        for link in &self.links {
+            for (occurrence, side) in self.ends(link) {
+                if occurrence >= n {
+                    return Err(NetError::Malformed(format!(
+                        "link {:?} refers to a missing occurrence",
+                        link
+                    )));
+                }
+                match side {
+                    Side::Above => above[occurrence] += 1,
+                    Side::Below => below[occurrence] += 1,
+                }
+            }
+
+            let f = |o: usize| &self.formulas[o];
+            let fits = match *link {
+                Link::Axiom(a, b) | Link::Cut(a, b) => same(&f(a).negate(), f(b)),
+                Link::Tensor {
+                    left,
+                    right,
+                    conclusion,
+                } => matches!(f(conclusion), Formula::Tensor(a, b)
+                    if same(a, f(left)) && same(b, f(right))),
+                Link::Par {
+                    left,
+                    right,
+                    conclusion,
+                } => par_parts(f(conclusion))
+                    .is_some_and(|(a, b)| same(&a, f(left)) && same(&b, f(right))),
+            };
+            if !fits {
+                return Err(NetError::Malformed(format!(
+                    "link {:?} does not fit its formulas",
+                    link
+                )));
+            }
+        }
+
+        for o in 0..n {
+            if above[o] != 1 || below[o] > 1 {
+                return Err(NetError::Malformed(format!(
+                    "occurrence {} of {} is produced {} times and consumed {} times",
+                    o,
+                    self.formulas[o].pretty(),
+                    above[o],
+                    below[o]
+                )));
+            }
+        }
+        let mut conclusions = self.conclusions.clone();
+        conclusions.sort_unstable();
+        let free: Vec<usize> = (0..n).filter(|&o| below[o] == 0).collect();
+        if conclusions != free {
+            return Err(NetError::Malformed(
+                "the conclusions are not the unconsumed occurrences".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The occurrences a link joins, and on which side of each it sits.
+    fn ends(&self, link: &Link) -> Vec<(usize, Side)> {
+        match *link {
+            Link::Axiom(a, b) => vec![(a, Side::Above), (b, Side::Above)],
+            Link::Cut(a, b) => vec![(a, Side::Below), (b, Side::Below)],
+            Link::Tensor {
+                left,
+                right,
+                conclusion,
+            }
+            | Link::Par {
+                left,
+                right,
+                conclusion,
+            } => vec![
+                (left, Side::Below),
+                (right, Side::Below),
+                (conclusion, Side::Above),
+            ],
+        }
+    }
+
+    /// Turn a correct net back into a sequent proof.
+    ///
+    /// Terminal pars come last; otherwise the net is split at a tensor or
+    /// cut whose removal disconnects it, which a correct net always has.
+    ///
+    /// # Errors
+    ///
+    /// Returns the reason the net is not correct, see [`ProofNet::check`].
+    pub fn sequentialize(&self) -> Result<Proof, NetError> {
+        self.check()?;
+
+        let mut above = vec![usize::MAX; self.formulas.len()];
+        let mut below = vec![usize::MAX; self.formulas.len()];
+        for (i, link) in self.links.iter().enumerate() {
+            for (occurrence, side) in self.ends(link) {
+                match side {
+                    Side::Above => above[occurrence] = i,
+                    Side::Below => below[occurrence] = i,
+                }
+            }
+        }
+        let cuts = (0..self.links.len())
+            .filter(|&i| matches!(self.links[i], Link::Cut(..)))
+            .collect();
+        let graph = Graph { above, below };
+        Ok(self.sequentialize_part(&graph, self.conclusions.clone(), cuts))
+    }
+
+    /// A proof of the part of the net with conclusions `roots` and cut
+    /// links `cuts`.
+    fn sequentialize_part(&self, graph: &Graph, mut roots: Vec<usize>, cuts: Vec<usize>) -> Proof {
+        let conclusion = Sequent::new(roots.iter().map(|&r| self.formulas[r].clone()).collect());
+
+        // A terminal par can always go last
+        for i in 0..roots.len() {
+            if let Link::Par { left, right, .. } = self.links[graph.above[roots[i]]] {
+                roots.remove(i);
+                roots.extend([left, right]);
+                return Proof {
+                    conclusion,
+                    rule: Rule::ParIntro,
+                    premises: vec![self.sequentialize_part(graph, roots, cuts)],
+                };
+            }
+        }
+
+        if cuts.is_empty() {
+            if let [a, b] = roots[..] {
+                if self.links[graph.above[a]] == Link::Axiom(a, b)
+                    || self.links[graph.above[a]] == Link::Axiom(b, a)
+                {
+                    return Proof {
+                        conclusion,
+                        rule: Rule::Axiom,
+                        premises: vec![],
+                    };
+                }
+            }
+        }
+
+        // Otherwise some terminal tensor or cut splits the part in two
+        let terminal = roots
+            .iter()
+            .map(|&r| graph.above[r])
+            .filter(|&l| matches!(self.links[l], Link::Tensor { .. }))
+            .chain(cuts.iter().copied());
+        for split in terminal {
+            let (left, right, rule) = match self.links[split] {
+                Link::Tensor { left, right, .. } => (left, right, Rule::TensorIntro),
+                Link::Cut(left, right) => (left, right, Rule::Cut(self.formulas[left].clone())),
+                _ => unreachable!("only tensors and cuts split"),
+            };
+            let reached = self.reachable(graph, &roots, split, left);
+            if reached[right] {
+                continue;
+            }
+
+            let (mut roots_left, mut roots_right): (Vec<usize>, Vec<usize>) = roots
+                .iter()
+                .copied()
+                .filter(|&r| graph.above[r] != split)
+                .partition(|&r| reached[r]);
+            roots_left.push(left);
+            roots_right.push(right);
+            let (cuts_left, cuts_right): (Vec<usize>, Vec<usize>) = cuts
+                .iter()
+                .copied()
+                .filter(|&c| c != split)
+                .partition(|&c| match self.links[c] {
+                    Link::Cut(a, _) => reached[a],
+                    _ => unreachable!("cuts are cut links"),
+                });
+            return Proof {
+                conclusion,
+                rule,
+                premises: vec![
+                    self.sequentialize_part(graph, roots_left, cuts_left),
+                    self.sequentialize_part(graph, roots_right, cuts_right),
+                ],
+            };
+        }
+        unreachable!("a correct net without terminal pars has a splitting tensor or cut")
+    }
+
+    /// The occurrences connected to `start` in the part of the net below
+    /// `roots`, once the link `removed` is taken out.
+    fn reachable(&self, graph: &Graph, roots: &[usize], removed: usize, start: usize) -> Vec<bool> {
+        let mut reached = vec![false; self.formulas.len()];
+        reached[start] = true;
+        let mut stack = vec![start];
+        while let Some(occurrence) = stack.pop() {
+            let mut links = vec![graph.above[occurrence]];
+            if !roots.contains(&occurrence) && graph.below[occurrence] != usize::MAX {
+                links.push(graph.below[occurrence]);
+            }
+            for link in links {
+                if link == removed {
+                    continue;
+                }
+                for (next, _) in self.ends(&self.links[link]) {
+                    if !reached[next] {
+                        reached[next] = true;
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+        reached
+    }
+
+    /// Returns true if both nets have the same conclusions and cut
+    /// formulas, in order, and link the same atom occurrences.
+    ///
+    /// Two proofs that differ only in the order of their rules have
+    /// equivalent nets.
+    pub fn equivalent(&self, other: &ProofNet) -> bool {
+        match (self.addresses(), other.addresses()) {
+            (Some(mut a), Some(mut b)) => {
+                a.sort();
+                b.sort();
+                a == b
+            }
+            _ => false,
+        }
+    }
+
+    /// The conclusion and cut formulas, and each axiom link as the
+    /// positions of its two occurrences.
+    fn addresses(&self) -> Option<Vec<(String, String)>> {
+        // Where each occurrence sits: a root and the path down to it
+        let mut address = vec![None; self.formulas.len()];
+        let mut roots = Vec::new();
+        for (i, &c) in self.conclusions.iter().enumerate() {
+            roots.push((c, format!("c{}", i)));
+        }
+        let mut cut = 0;
+        for link in &self.links {
+            if let Link::Cut(a, b) = *link {
+                roots.push((a, format!("k{}l", cut)));
+                roots.push((b, format!("k{}r", cut)));
+                cut += 1;
+            }
+        }
+
+        let mut producers = vec![None; self.formulas.len()];
+        for link in &self.links {
+            if let Link::Tensor { conclusion, .. } | Link::Par { conclusion, .. } = *link {
+                producers[conclusion] = Some(link);
+            }
+        }
+        let mut keys = Vec::new();
+        for (root, path) in roots {
+            keys.push((path.clone(), self.formulas.get(root)?.desugar().pretty()));
+            let mut stack = vec![(root, path)];
+            while let Some((occurrence, path)) = stack.pop() {
+                match producers[occurrence] {
+                    Some(Link::Tensor { left, right, .. } | Link::Par { left, right, .. }) => {
+                        stack.push((*left, format!("{}.0", path)));
+                        stack.push((*right, format!("{}.1", path)));
+                    }
+                    _ => address[occurrence] = Some(path),
+                }
+            }
+        }
+
+        for link in &self.links {
+            if let Link::Axiom(a, b) = *link {
+                let (a, b) = (address[a].clone()?, address[b].clone()?);
+                keys.push(if a < b { (a, b) } else { (b, a) });
+            }
+        }
+        Some(keys)
+    }
+}
+
+/// The link above and below each occurrence, `usize::MAX` for none.
+struct Graph {
+    above: Vec<usize>,
+    below: Vec<usize>,
+}
+
+/// The parts of a par formula: `(A, B)` for `A ⅋ B`, `(A⊥, B)` for `A ⊸ B`.
+fn par_parts(formula: &Formula) -> Option<(Formula, Formula)> {
+    match formula {
+        Formula::Par(a, b) => Some((a.as_ref().clone(), b.as_ref().clone())),
+        Formula::Lolli(a, b) => Some((a.negate(), b.as_ref().clone())),
+        _ => None,
+    }
+}
+
+/// Equality up to reading `A ⊸ B` as `A⊥ ⅋ B`.
+fn same(a: &Formula, b: &Formula) -> bool {
+    a == b || a.desugar() == b.desugar()
+}
+
+/// Disjoint sets of occurrences.
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+}
+
+impl UnionFind {
+    /// `n` occurrences, each in a set of its own.
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+        }
+    }
+
+    /// The representative of the set of `x`.
+    pub fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    /// Merge the sets of `a` and `b`; returns false if they were one set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        self.parent[a] = b;
+        a != b
+    }
+
+    /// The number of sets.
+    pub fn count(&mut self) -> usize {
+        (0..self.parent.len())
+            .filter(|&x| self.find(x) == x)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atom(name: &str) -> Formula {
+        Formula::atom(name)
+    }
+
+    fn neg(name: &str) -> Formula {
+        Formula::neg_atom(name)
+    }
+
+    fn step(linear: Vec<Formula>, rule: Rule, premises: Vec<Proof>) -> Proof {
+        Proof {
+            conclusion: Sequent::new(linear),
+            rule,
+            premises,
+        }
+    }
+
+    fn axiom(name: &str) -> Proof {
+        step(vec![neg(name), atom(name)], Rule::Axiom, vec![])
+    }
+
+    /// ⊢ A⊥ ⅋ B⊥, C⊥ ⅋ D⊥, (A ⊗ B) ⊗ (C ⊗ D), with the two pars in either
+    /// order
+    fn two_pars(first_left: bool) -> Proof {
+        let par_ab = Formula::par(neg("A"), neg("B"));
+        let par_cd = Formula::par(neg("C"), neg("D"));
+        let ab = Formula::tensor(atom("A"), atom("B"));
+        let cd = Formula::tensor(atom("C"), atom("D"));
+        let goal = Formula::tensor(ab.clone(), cd.clone());
+
+        let left = step(
+            vec![neg("A"), neg("B"), ab.clone()],
+            Rule::TensorIntro,
+            vec![axiom("A"), axiom("B")],
+        );
+        let right = step(
+            vec![neg("C"), neg("D"), cd.clone()],
+            Rule::TensorIntro,
+            vec![axiom("C"), axiom("D")],
+        );
+        let top = step(
+            vec![neg("A"), neg("B"), neg("C"), neg("D"), goal.clone()],
+            Rule::TensorIntro,
+            vec![left, right],
+        );
+        let middle = if first_left {
+            vec![par_ab.clone(), neg("C"), neg("D"), goal.clone()]
+        } else {
+            vec![neg("A"), neg("B"), par_cd.clone(), goal.clone()]
+        };
+        let middle = step(middle, Rule::ParIntro, vec![top]);
+        step(vec![par_ab, par_cd, goal], Rule::ParIntro, vec![middle])
+    }
+
+    #[test]
+    fn test_from_proof() {
+        let net = ProofNet::from_proof(&two_pars(true)).unwrap();
+        assert_eq!(net.conclusions.len(), 3);
+        assert_eq!(
+            net.links
+                .iter()
+                .filter(|l| matches!(l, Link::Axiom(..)))
+                .count(),
+            4
+        );
+        assert!(net.is_correct());
+    }
+
+    #[test]
+    fn test_rule_permutations_give_equivalent_nets() {
+        let a = ProofNet::from_proof(&two_pars(true)).unwrap();
+        let b = ProofNet::from_proof(&two_pars(false)).unwrap();
+        assert!(a.equivalent(&b));
+        assert!(!a.equivalent(&ProofNet::from_proof(&axiom("A")).unwrap()));
+    }
+
+    #[test]
+    fn test_sequentialize_round_trip() {
+        let net = ProofNet::from_proof(&two_pars(false)).unwrap();
+        let proof = net.sequentialize().unwrap();
+        assert_eq!(proof.conclusion.linear, two_pars(false).conclusion.linear);
+        assert!(ProofNet::from_proof(&proof).unwrap().equivalent(&net));
+    }
+
+    #[test]
+    fn test_cut() {
+        // Cut A between two axioms: ⊢ A⊥, A
+        let proof = step(
+            vec![neg("A"), atom("A")],
+            Rule::Cut(atom("A")),
+            vec![axiom("A"), axiom("A")],
+        );
+        let net = ProofNet::from_proof(&proof).unwrap();
+        assert!(net.links.contains(&Link::Cut(1, 2)));
+        let back = net.sequentialize().unwrap();
+        assert_eq!(back.cut_count(), 1);
+    }
+
+    #[test]
+    fn test_incorrect_nets() {
+        // ⊢ A ⊗ A⊥ with its premises linked to each other
+        let cyclic = ProofNet {
+            formulas: vec![atom("A"), neg("A"), Formula::tensor(atom("A"), neg("A"))],
+            links: vec![
+                Link::Axiom(0, 1),
+                Link::Tensor {
+                    left: 0,
+                    right: 1,
+                    conclusion: 2,
+                },
+            ],
+            conclusions: vec![2],
+        };
+        assert_eq!(cyclic.check(), Err(NetError::Cyclic));
+
+        // ⊢ A ⅋ A⊥, B ⅋ B⊥ falls apart in two
+        let par = |o: usize| {
+            vec![
+                Link::Axiom(o, o + 1),
+                Link::Par {
+                    left: o,
+                    right: o + 1,
+                    conclusion: o + 2,
+                },
+            ]
+        };
+        let disconnected = ProofNet {
+            formulas: vec![
+                atom("A"),
+                neg("A"),
+                Formula::par(atom("A"), neg("A")),
+                atom("B"),
+                neg("B"),
+                Formula::par(atom("B"), neg("B")),
+            ],
+            links: [par(0), par(3)].concat(),
+            conclusions: vec![2, 5],
+        };
+        assert_eq!(disconnected.check(), Err(NetError::Disconnected));
+        assert!(disconnected.sequentialize().is_err());
+    }
+
+    #[test]
+    fn test_contract_partial_net() {
+        // ⊢ A ⊗ A⊥ before its axiom link is chosen
+        let mut net = ProofNet {
+            formulas: vec![atom("A"), neg("A"), Formula::tensor(atom("A"), neg("A"))],
+            links: vec![Link::Tensor {
+                left: 0,
+                right: 1,
+                conclusion: 2,
+            }],
+            conclusions: vec![2],
+        };
+        assert!(matches!(net.check(), Err(NetError::Malformed(_))));
+        let mut vertices = net.contract().unwrap();
+        assert_eq!(vertices.count(), 1);
+        assert_eq!(vertices.find(0), vertices.find(1));
+
+        net.links.push(Link::Axiom(0, 1));
+        assert_eq!(net.contract().unwrap_err(), NetError::Cyclic);
+    }
+
+    #[test]
+    fn test_unsupported_rule() {
+        let one = step(vec![Formula::One], Rule::OneIntro, vec![]);
+        assert_eq!(
+            ProofNet::from_proof(&one).unwrap_err(),
+            NetError::UnsupportedRule(Rule::OneIntro)
+        );
+
+        let wrong = step(vec![atom("A"), atom("A")], Rule::Axiom, vec![]);
+        assert!(matches!(
+            ProofNet::from_proof(&wrong),
+            Err(NetError::Mismatch { .. })
+        ));
+    }
+}
@@ -66,6 +66,21 @@ impl Sequent {
         }
     }
 
+    /// The linear zone with the focused formula, desugared: what a rule
+    /// consumes and produces, whatever the focus and notation.
+    pub fn linear_zone(&self) -> Vec<Formula> {
+        self.linear
+            .iter()
+            .chain(&self.focus)
+            .map(Formula::desugar)
+            .collect()
+    }
+
+    /// The unrestricted zone, desugared.
+    pub fn unrestricted_zone(&self) -> Vec<Formula> {
+        self.unrestricted.iter().map(Formula::desugar).collect()
+    }
+
     /// Pretty print the sequent.
     pub fn pretty(&self) -> String {
         let formulas: Vec<String> = self.linear.iter().map(|f| f.pretty()).collect();
@@ -139,4 +154,16 @@ mod tests {
         assert_eq!(unfocused.linear.len(), 2);
         assert!(unfocused.focus.is_none());
     }
+
+    #[test]
+    fn test_zones() {
+        let (a, b) = (Formula::atom("A"), Formula::atom("B"));
+        let seq = Sequent {
+            linear: vec![a.clone()],
+            unrestricted: vec![Formula::lolli(a.clone(), b.clone())],
+            focus: Some(b.clone()),
+        };
+        assert_eq!(seq.linear_zone(), vec![a.clone(), b.clone()]);
+        assert_eq!(seq.unrestricted_zone(), vec![Formula::par(a.negate(), b)]);
+    }
 }
@@ -114,78 +114,118 @@ impl Term {
         }
     }
 
-    /// Substitute a term for a variable.
+    /// Substitute a term for the free occurrences of a variable.
+    ///
+    /// Substitution is capture-avoiding: a binder that would capture a free
+    /// variable of `replacement` is renamed first, so substituting `y` for
+    /// `x` in `λy. x` gives `λy0. y`.
     pub fn substitute(&self, var: &str, replacement: &Term) -> Term {
+        self.subst(var, replacement, &replacement.free_vars())
+    }
+
+    /// Substitute terms for several variables at once.
+    ///
+    /// Unlike a chain of [`Term::substitute`] calls, a variable brought in by
+    /// one replacement is never replaced by a later one: substituting `y` for
+    /// `x` and `x` for `y` in `(x, y)` gives `(y, x)`.
+    pub fn substitute_many(&self, substitution: &[(&str, &Term)]) -> Term {
+        let mut avoid = self.free_vars();
+        for (var, replacement) in substitution {
+            avoid.insert(var.to_string());
+            avoid.extend(replacement.free_vars());
+        }
+
+        // Rename the variables apart from every replacement, then fill them in
+        let mut term = self.clone();
+        let mut renamed = Vec::new();
+        for (var, replacement) in substitution {
+            let fresh = fresh_var(var, &avoid);
+            avoid.insert(fresh.clone());
+            term = term.substitute(var, &Term::Var(fresh.clone()));
+            renamed.push((fresh, *replacement));
+        }
+        for (fresh, replacement) in renamed {
+            term = term.substitute(&fresh, replacement);
+        }
+        term
+    }
+
+    /// Check whether two terms are equal up to the names of bound variables.
+    ///
+    /// `λx. x` and `λy. y` are α-equivalent, while `λx. y` and `λy. y` are not.
+    pub fn alpha_eq(&self, other: &Term) -> bool {
+        alpha_eq_in(self, other, &mut Vec::new())
+    }
+
+    fn subst(&self, var: &str, replacement: &Term, fv: &HashSet<String>) -> Term {
+        let go = |e: &Term| Box::new(e.subst(var, replacement, fv));
         match self {
             Term::Var(v) if v == var => replacement.clone(),
-            Term::Var(v) => Term::Var(v.clone()),
-            Term::Unit => Term::Unit,
-            Term::Trivial => Term::Trivial,
-            Term::Pair(a, b) => Term::Pair(
-                Box::new(a.substitute(var, replacement)),
-                Box::new(b.substitute(var, replacement)),
-            ),
-            Term::LetPair(x, y, pair, body) => {
-                let new_pair = pair.substitute(var, replacement);
-                let new_body = if x == var || y == var {
-                    body.as_ref().clone()
-                } else {
-                    body.substitute(var, replacement)
-                };
-                Term::LetPair(x.clone(), y.clone(), Box::new(new_pair), Box::new(new_body))
-            }
-            Term::Abs(x, body) if x == var => Term::Abs(x.clone(), body.clone()),
+            Term::Var(_) | Term::Unit | Term::Trivial => self.clone(),
+            Term::Pair(a, b) => Term::Pair(go(a), go(b)),
+            Term::App(f, a) => Term::App(go(f), go(a)),
+            Term::Inl(e) => Term::Inl(go(e)),
+            Term::Inr(e) => Term::Inr(go(e)),
+            Term::Fst(e) => Term::Fst(go(e)),
+            Term::Snd(e) => Term::Snd(go(e)),
+            Term::Abort(e) => Term::Abort(go(e)),
+            Term::Promote(e) => Term::Promote(go(e)),
+            Term::Derelict(e) => Term::Derelict(go(e)),
+            Term::Discard(discarded, body) => Term::Discard(go(discarded), go(body)),
             Term::Abs(x, body) => {
-                Term::Abs(x.clone(), Box::new(body.substitute(var, replacement)))
-            }
-            Term::App(f, a) => Term::App(
-                Box::new(f.substitute(var, replacement)),
-                Box::new(a.substitute(var, replacement)),
-            ),
-            Term::Inl(e) => Term::Inl(Box::new(e.substitute(var, replacement))),
-            Term::Inr(e) => Term::Inr(Box::new(e.substitute(var, replacement))),
-            Term::Case(scrut, x, left, y, right) => {
-                let new_scrut = scrut.substitute(var, replacement);
-                let new_left = if x == var {
-                    left.as_ref().clone()
-                } else {
-                    left.substitute(var, replacement)
-                };
-                let new_right = if y == var {
-                    right.as_ref().clone()
-                } else {
-                    right.substitute(var, replacement)
-                };
-                Term::Case(
-                    Box::new(new_scrut),
-                    x.clone(),
-                    Box::new(new_left),
-                    y.clone(),
-                    Box::new(new_right),
-                )
+                let (names, body) = body.subst_under(&[x], var, replacement, fv);
+                let [x] = names;
+                Term::Abs(x, Box::new(body))
+            }
+            Term::LetPair(x, y, pair, body) => {
+                let (names, body) = body.subst_under(&[x, y], var, replacement, fv);
+                let [x, y] = names;
+                Term::LetPair(x, y, go(pair), Box::new(body))
             }
-            Term::Fst(e) => Term::Fst(Box::new(e.substitute(var, replacement))),
-            Term::Snd(e) => Term::Snd(Box::new(e.substitute(var, replacement))),
-            Term::Abort(e) => Term::Abort(Box::new(e.substitute(var, replacement))),
-            Term::Promote(e) => Term::Promote(Box::new(e.substitute(var, replacement))),
-            Term::Derelict(e) => Term::Derelict(Box::new(e.substitute(var, replacement))),
-            Term::Discard(discarded, body) => Term::Discard(
-                Box::new(discarded.substitute(var, replacement)),
-                Box::new(body.substitute(var, replacement)),
-            ),
             Term::Copy(src, x, y, body) => {
-                let new_src = src.substitute(var, replacement);
-                let new_body = if x == var || y == var {
-                    body.as_ref().clone()
-                } else {
-                    body.substitute(var, replacement)
-                };
-                Term::Copy(Box::new(new_src), x.clone(), y.clone(), Box::new(new_body))
+                let (names, body) = body.subst_under(&[x, y], var, replacement, fv);
+                let [x, y] = names;
+                Term::Copy(go(src), x, y, Box::new(body))
+            }
+            Term::Case(scrut, x, left, y, right) => {
+                let ([x], left) = left.subst_under(&[x], var, replacement, fv);
+                let ([y], right) = right.subst_under(&[y], var, replacement, fv);
+                Term::Case(go(scrut), x, Box::new(left), y, Box::new(right))
             }
         }
     }
 
+    /// Substitute in the scope of `names`, renaming those that would capture
+    /// a free variable of the replacement.
+    fn subst_under<const N: usize>(
+        &self,
+        names: &[&String; N],
+        var: &str,
+        replacement: &Term,
+        fv: &HashSet<String>,
+    ) -> ([String; N], Term) {
+        let mut names = names.map(|name| name.clone());
+        let body_fv = self.free_vars();
+        if names.iter().any(|name| name == var) || !body_fv.contains(var) {
+            return (names, self.clone());
+        }
+
+        let mut avoid: HashSet<String> = fv.union(&body_fv).cloned().collect();
+        avoid.extend(names.iter().cloned());
+        avoid.insert(var.to_string());
+        let mut body = self.clone();
+        for name in names.iter_mut().filter(|name| fv.contains(name.as_str())) {
+            let fresh = fresh_var(name, &avoid);
+            avoid.insert(fresh.clone());
+            body = body.substitute(name, &Term::Var(fresh.clone()));
+            *name = fresh;
+        }
+        (names, body.subst(var, replacement, fv))
+    }
+
     /// Pretty print the term.
+    ///
+    /// The output reads back with `lolli_parse::parse_term`.
     pub fn pretty(&self) -> String {
         match self {
             Term::Var(v) => v.clone(),
@@ -196,9 +236,9 @@ impl Term {
                 format!("let ({}, {}) = {} in {}", x, y, pair.pretty(), body.pretty())
             }
             Term::Abs(x, body) => format!("λ{}. {}", x, body.pretty()),
-            Term::App(f, a) => format!("({} {})", f.pretty(), a.pretty()),
-            Term::Inl(e) => format!("inl {}", e.pretty()),
-            Term::Inr(e) => format!("inr {}", e.pretty()),
+            Term::App(f, a) => format!("({} {})", f.pretty_operand(), a.pretty_operand()),
+            Term::Inl(e) => format!("inl {}", e.pretty_operand()),
+            Term::Inr(e) => format!("inr {}", e.pretty_operand()),
             Term::Case(scrut, x, left, y, right) => {
                 format!(
                     "case {} of {{ inl {} => {} | inr {} => {} }}",
@@ -209,17 +249,91 @@ impl Term {
                     right.pretty()
                 )
             }
-            Term::Fst(e) => format!("fst {}", e.pretty()),
-            Term::Snd(e) => format!("snd {}", e.pretty()),
-            Term::Abort(e) => format!("absurd {}", e.pretty()),
-            Term::Promote(e) => format!("!{}", e.pretty()),
-            Term::Derelict(e) => format!("derelict {}", e.pretty()),
-            Term::Discard(_, body) => format!("discard in {}", body.pretty()),
+            Term::Fst(e) => format!("fst {}", e.pretty_operand()),
+            Term::Snd(e) => format!("snd {}", e.pretty_operand()),
+            Term::Abort(e) => format!("absurd {}", e.pretty_operand()),
+            Term::Promote(e) => format!("!{}", e.pretty_operand()),
+            Term::Derelict(e) => format!("derelict {}", e.pretty_operand()),
+            Term::Discard(discarded, body) => {
+                format!("discard {} in {}", discarded.pretty(), body.pretty())
+            }
             Term::Copy(src, x, y, body) => {
                 format!("copy {} as ({}, {}) in {}", src.pretty(), x, y, body.pretty())
             }
         }
     }
+
+    /// Pretty print the term as an operand of application or a prefix form.
+    ///
+    /// Binders extend as far right as possible, so they are parenthesized:
+    /// `(λx. x) y` would otherwise read as `λx. (x y)`.
+    fn pretty_operand(&self) -> String {
+        match self {
+            Term::Abs(..) | Term::LetPair(..) | Term::Discard(..) | Term::Copy(..) => {
+                format!("({})", self.pretty())
+            }
+            _ => self.pretty(),
+        }
+    }
+}
+
+/// A variable named after `base` that is not in `avoid`: `x0`, `x1`, ...
+pub fn fresh_var(base: &str, avoid: &HashSet<String>) -> String {
+    let stem = base.trim_end_matches(|c: char| c.is_ascii_digit());
+    (0..)
+        .map(|i| format!("{}{}", stem, i))
+        .find(|name| !avoid.contains(name))
+        .expect("some name is fresh")
+}
+
+/// α-equivalence, with `bound` pairing the binders of both terms in scope.
+fn alpha_eq_in<'a>(left: &'a Term, right: &'a Term, bound: &mut Vec<(&'a str, &'a str)>) -> bool {
+    match (left, right) {
+        (Term::Var(x), Term::Var(y)) => {
+            // The innermost binder of either name decides
+            match bound.iter().rev().find(|(l, r)| l == x || r == y) {
+                Some((l, r)) => l == x && r == y,
+                None => x == y,
+            }
+        }
+        (Term::Unit, Term::Unit) | (Term::Trivial, Term::Trivial) => true,
+        (Term::Pair(a, b), Term::Pair(c, d))
+        | (Term::App(a, b), Term::App(c, d))
+        | (Term::Discard(a, b), Term::Discard(c, d)) => {
+            alpha_eq_in(a, c, bound) && alpha_eq_in(b, d, bound)
+        }
+        (Term::Inl(a), Term::Inl(b))
+        | (Term::Inr(a), Term::Inr(b))
+        | (Term::Fst(a), Term::Fst(b))
+        | (Term::Snd(a), Term::Snd(b))
+        | (Term::Abort(a), Term::Abort(b))
+        | (Term::Promote(a), Term::Promote(b))
+        | (Term::Derelict(a), Term::Derelict(b)) => alpha_eq_in(a, b, bound),
+        (Term::Abs(x, a), Term::Abs(y, b)) => alpha_eq_under(&[(x, y)], a, b, bound),
+        (Term::LetPair(x1, y1, p, a), Term::LetPair(x2, y2, q, b))
+        | (Term::Copy(p, x1, y1, a), Term::Copy(q, x2, y2, b)) => {
+            alpha_eq_in(p, q, bound) && alpha_eq_under(&[(x1, x2), (y1, y2)], a, b, bound)
+        }
+        (Term::Case(s, x1, l1, y1, r1), Term::Case(t, x2, l2, y2, r2)) => {
+            alpha_eq_in(s, t, bound)
+                && alpha_eq_under(&[(x1, x2)], l1, l2, bound)
+                && alpha_eq_under(&[(y1, y2)], r1, r2, bound)
+        }
+        _ => false,
+    }
+}
+
+fn alpha_eq_under<'a>(
+    names: &[(&'a String, &'a String)],
+    left: &'a Term,
+    right: &'a Term,
+    bound: &mut Vec<(&'a str, &'a str)>,
+) -> bool {
+    let scope = bound.len();
+    bound.extend(names.iter().map(|(l, r)| (l.as_str(), r.as_str())));
+    let equal = alpha_eq_in(left, right, bound);
+    bound.truncate(scope);
+    equal
 }
 
 #[cfg(test)]
@@ -246,4 +360,90 @@ mod tests {
         let result = t.substitute("x", &Term::Unit);
         assert_eq!(result, Term::Unit);
     }
+
+    fn var(x: &str) -> Term {
+        Term::Var(x.to_string())
+    }
+
+    fn abs(x: &str, body: Term) -> Term {
+        Term::Abs(x.to_string(), Box::new(body))
+    }
+
+    fn pair(a: Term, b: Term) -> Term {
+        Term::Pair(Box::new(a), Box::new(b))
+    }
+
+    #[test]
+    fn test_substitute_avoids_capture() {
+        // (λy. (x, y))[y/x] = λy0. (y, y0)
+        let t = abs("y", pair(var("x"), var("y")));
+        assert_eq!(
+            t.substitute("x", &var("y")),
+            abs("y0", pair(var("y"), var("y0")))
+        );
+
+        // Bound occurrences are left alone
+        assert_eq!(t.substitute("y", &Term::Unit), t);
+
+        // copy c as (y, z) in (x, y, z) renames only the capturing binder
+        let copy = Term::Copy(
+            Box::new(var("c")),
+            "y".to_string(),
+            "z".to_string(),
+            Box::new(pair(var("x"), pair(var("y"), var("z")))),
+        );
+        let Term::Copy(_, y, z, body) = copy.substitute("x", &var("y")) else {
+            panic!("substitution changed the term's shape");
+        };
+        assert_eq!((y.as_str(), z.as_str()), ("y0", "z"));
+        assert_eq!(*body, pair(var("y"), pair(var("y0"), var("z"))));
+    }
+
+    #[test]
+    fn test_substitute_many() {
+        // (x, y)[y/x, x/y] swaps rather than giving (x, x)
+        let t = pair(var("x"), var("y"));
+        assert_eq!(
+            t.substitute_many(&[("x", &var("y")), ("y", &var("x"))]),
+            pair(var("y"), var("x"))
+        );
+    }
+
+    #[test]
+    fn test_alpha_eq() {
+        assert!(abs("x", var("x")).alpha_eq(&abs("y", var("y"))));
+        assert_ne!(abs("x", var("x")), abs("y", var("y")));
+        assert!(!abs("x", var("y")).alpha_eq(&abs("y", var("y"))));
+        assert!(!var("x").alpha_eq(&var("y")));
+
+        // λx. λy. (x, y) against λy. λx. (y, x), and against its mirror
+        let k = abs("x", abs("y", pair(var("x"), var("y"))));
+        assert!(k.alpha_eq(&abs("y", abs("x", pair(var("y"), var("x"))))));
+        assert!(!k.alpha_eq(&abs("y", abs("x", pair(var("x"), var("y"))))));
+
+        // Shadowing: the innermost binder wins
+        let shadow = abs("x", abs("x", var("x")));
+        assert!(shadow.alpha_eq(&abs("a", abs("b", var("b")))));
+        assert!(!shadow.alpha_eq(&abs("a", abs("b", var("a")))));
+
+        let case = |x: &str, y: &str| {
+            Term::Case(
+                Box::new(var("s")),
+                x.to_string(),
+                Box::new(Term::Inr(Box::new(var(x)))),
+                y.to_string(),
+                Box::new(Term::Inl(Box::new(var(y)))),
+            )
+        };
+        assert!(case("a", "b").alpha_eq(&case("b", "a")));
+    }
+
+    #[test]
+    fn test_substitution_respects_alpha_eq() {
+        // α-equivalent terms stay α-equivalent under substitution
+        let t = abs("y", pair(var("x"), var("y")));
+        let u = abs("z", pair(var("x"), var("z")));
+        let r = pair(var("y"), var("z"));
+        assert!(t.substitute("x", &r).alpha_eq(&u.substitute("x", &r)));
+    }
 }
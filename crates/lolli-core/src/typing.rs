@@ -0,0 +1,1166 @@
+//! Linear type checking and inference for terms.
+//!
+//! This module checks the judgement `Γ ⊢ t : A`, where `Γ` gives each free
+//! variable of `t` a [`Formula`] and every variable must be used exactly
+//! once. Exponentials are explicit: a variable of type `!A` is duplicated
+//! with `copy`, dropped with `discard` and used with `derelict`.
+//!
+//! Types may contain type variables, solved by unification, so the same
+//! rules also infer the most general type of a term (see [`infer_type`]).
+//! A pair is a tensor pair unless its components share their variables, in
+//! which case it is a with pair.
+//!
+//! The components of a with pair and the branches of a case are additive:
+//! each must use the same variables. `⟨⟩` and `absurd` may consume any
+//! variables not used elsewhere.
+
+use std::collections::HashMap;
+
+use crate::{Formula, Term};
+
+/// Why a term does not have a type.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TypeError {
+    /// A variable not bound by the context or the term
+    #[error("unbound variable {0}")]
+    Unbound(String),
+
+    /// A linear variable used more than once
+    #[error("variable {0} used twice")]
+    UsedTwice(String),
+
+    /// A linear variable never used
+    #[error("variable {0} unused")]
+    Unused(String),
+
+    /// A variable used in one additive branch but not the other
+    #[error("{var} unused in {branch}")]
+    UnusedInBranch {
+        /// The variable
+        var: String,
+        /// The branch that does not use it, such as "inr branch"
+        branch: &'static str,
+    },
+
+    /// A promoted term uses a variable whose type is not `!A`
+    #[error("promotion uses linear variable {0}")]
+    Promotion(String),
+
+    /// A term has a different type from the one expected
+    #[error("{term} has type {found}, expected {expected}")]
+    Mismatch {
+        /// The term
+        term: String,
+        /// Its type
+        found: String,
+        /// The expected type
+        expected: String,
+    },
+
+    /// A term is eliminated as if it had a type of another shape
+    #[error("{term} has type {found}, expected {shape}")]
+    Shape {
+        /// The term
+        term: String,
+        /// Its type
+        found: String,
+        /// What the elimination needs, such as "a function"
+        shape: &'static str,
+    },
+
+    /// An introduction form checked against a type it cannot have
+    #[error("{term} cannot have type {expected}")]
+    Intro {
+        /// The term
+        term: String,
+        /// The expected type
+        expected: String,
+    },
+}
+
+/// Check `Γ ⊢ t : A`, where `context` is `Γ`.
+///
+/// Types are compared modulo [`Formula::desugar`], so `A ⊸ B` and `A⊥ ⅋ B`
+/// are the same type.
+///
+/// # Errors
+///
+/// Returns the first [`TypeError`] found, such as a variable used twice or
+/// unused in one branch of a case.
+///
+/// # Example
+///
+/// ```
+/// use lolli_core::{type_check, Formula, Term, TypeError};
+///
+/// let a = Formula::atom("A");
+/// let pair = Formula::tensor(a.clone(), a.clone());
+/// let x = || Box::new(Term::Var("x".to_string()));
+///
+/// // x : A ⊢ (x, x) : A ⊗ A uses x twice
+/// let context = vec![("x".to_string(), a)];
+/// let err = type_check(&context, &Term::Pair(x(), x()), &pair).unwrap_err();
+/// assert_eq!(err, TypeError::UsedTwice("x".to_string()));
+/// ```
+pub fn type_check(
+    context: &[(String, Formula)],
+    term: &Term,
+    ty: &Formula,
+) -> Result<(), TypeError> {
+    let mut checker = Checker::default();
+    for (name, ty) in context {
+        checker.bind(name, ty.clone());
+    }
+    checker.check(term, ty)?;
+    checker.unbind(context.len())?;
+    checker.discharge()
+}
+
+/// The most general typing of a term, from [`infer_type`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Typing {
+    /// The type of each free variable, in order of first occurrence
+    pub context: Vec<(String, Formula)>,
+    /// The type of the term
+    pub ty: Formula,
+}
+
+impl Typing {
+    /// Pretty print the typing as a judgement, such as `f : (A ⊸ B), x : A ⊢ B`.
+    pub fn pretty(&self) -> String {
+        let context: Vec<String> = self
+            .context
+            .iter()
+            .map(|(x, ty)| format!("{} : {}", x, ty.pretty()))
+            .collect();
+        if context.is_empty() {
+            format!("⊢ {}", self.ty.pretty())
+        } else {
+            format!("{} ⊢ {}", context.join(", "), self.ty.pretty())
+        }
+    }
+}
+
+/// Infer the most general type of a term and of its free variables.
+///
+/// The atoms of the types are type variables, named `A`, `B`, … in order
+/// of appearance.
+///
+/// # Errors
+///
+/// Returns a [`TypeError`] if the term has no type, such as when it uses a
+/// variable twice.
+///
+/// # Example
+///
+/// ```
+/// use lolli_core::{infer_type, Term};
+///
+/// // λx. λy. (x, y) : A ⊸ B ⊸ A ⊗ B
+/// let var = |x: &str| Box::new(Term::Var(x.to_string()));
+/// let pair = Term::Abs(
+///     "x".to_string(),
+///     Box::new(Term::Abs("y".to_string(), Box::new(Term::Pair(var("x"), var("y"))))),
+/// );
+/// let typing = infer_type(&pair).unwrap();
+/// assert_eq!(typing.ty.pretty(), "(A ⊸ (B ⊸ (A ⊗ B)))");
+/// ```
+pub fn infer_type(term: &Term) -> Result<Typing, TypeError> {
+    let mut free = Vec::new();
+    free_vars_in_order(term, &mut Vec::new(), &mut free);
+
+    let mut checker = Checker::default();
+    for x in &free {
+        let ty = checker.fresh();
+        checker.bind(x, ty);
+    }
+    let ty = checker.infer(term)?;
+    let types: Vec<Formula> = checker.scope.iter().map(|b| b.ty.clone()).collect();
+    checker.unbind(free.len())?;
+    checker.discharge()?;
+
+    // Name the type variables after the atoms not taken
+    let mut names = HashMap::new();
+    let context: Vec<(String, Formula)> = free
+        .into_iter()
+        .zip(types)
+        .map(|(x, ty)| (x, checker.resolve(&ty)))
+        .collect();
+    let ty = checker.resolve(&ty);
+    let mut taken = Vec::new();
+    for f in context.iter().map(|(_, ty)| ty).chain([&ty]) {
+        atoms(f, &mut taken);
+    }
+    let mut name = |f: &Formula| rename(f, &mut names, &mut taken);
+    let context = context
+        .iter()
+        .map(|(x, ty)| (x.clone(), name(ty)))
+        .collect();
+    let ty = name(&ty);
+    Ok(Typing { context, ty })
+}
+
+/// How much of a variable has been used so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Usage {
+    Unused,
+    Used,
+    /// Unused, but a `⊤` may consume it
+    Absorbable,
+}
+
+/// A variable in scope.
+struct Binding {
+    name: String,
+    ty: Formula,
+    usage: Usage,
+}
+
+#[derive(Default)]
+struct Checker {
+    /// Variables in scope, innermost last
+    scope: Vec<Binding>,
+    /// The types of the type variables solved so far
+    solved: HashMap<String, Formula>,
+    /// Number of type variables created
+    vars: usize,
+    /// Discarded terms whose type was unknown, which must be `1` or `!A`
+    discarded: Vec<(Term, Formula)>,
+}
+
+impl Checker {
+    fn check(&mut self, term: &Term, ty: &Formula) -> Result<(), TypeError> {
+        let intro = |c: &Self| TypeError::Intro {
+            term: term.pretty(),
+            expected: c.show(ty),
+        };
+
+        match term {
+            Term::Abs(x, body) => {
+                let (a, b) = self.split(ty, Formula::lolli).ok_or_else(|| intro(self))?;
+                self.bind(x, a);
+                self.check(body, &b)?;
+                self.unbind(1)
+            }
+
+            Term::Pair(l, r) => match self.head(ty) {
+                Formula::Tensor(a, b) => {
+                    self.check(l, &a)?;
+                    self.check(r, &b)
+                }
+                Formula::With(a, b) => self
+                    .branches(
+                        ["first component", "second component"],
+                        |c| c.check(l, &a),
+                        |c, ()| c.check(r, &b),
+                    )
+                    .map(|_| ()),
+                _ => {
+                    let found = self.infer(term)?;
+                    self.expect(term, &found, ty)
+                }
+            },
+
+            Term::Unit => {
+                if self.unify(ty, &Formula::One) {
+                    Ok(())
+                } else {
+                    Err(intro(self))
+                }
+            }
+            Term::Trivial => {
+                if !self.unify(ty, &Formula::Top) {
+                    return Err(intro(self));
+                }
+                self.absorb();
+                Ok(())
+            }
+
+            Term::Inl(e) | Term::Inr(e) => {
+                let (a, b) = self.split(ty, Formula::plus).ok_or_else(|| intro(self))?;
+                let part = if matches!(term, Term::Inl(_)) { a } else { b };
+                self.check(e, &part)
+            }
+
+            Term::Promote(e) => {
+                let a = self.split_bang(ty).ok_or_else(|| intro(self))?;
+                let before = self.usages();
+                self.check(e, &a)?;
+                self.promoted(&before)
+            }
+
+            Term::Abort(e) => {
+                self.check(e, &Formula::Zero)?;
+                self.absorb();
+                Ok(())
+            }
+
+            Term::Case(scrut, x, left, y, right) => {
+                let (a, b) = self.infer_split(scrut, Formula::plus, "a sum")?;
+                self.branches(
+                    ["inl branch", "inr branch"],
+                    |c| {
+                        c.bind(x, a);
+                        c.check(left, ty)?;
+                        c.unbind(1)
+                    },
+                    |c, ()| {
+                        c.bind(y, b);
+                        c.check(right, ty)?;
+                        c.unbind(1)
+                    },
+                )
+                .map(|_| ())
+            }
+
+            Term::LetPair(x, y, pair, body) => {
+                let (a, b) = self.infer_split(pair, Formula::tensor, "a tensor")?;
+                self.bind(x, a);
+                self.bind(y, b);
+                self.check(body, ty)?;
+                self.unbind(2)
+            }
+
+            Term::Copy(src, x, y, body) => {
+                let a = Formula::of_course(self.infer_bang(src)?);
+                self.bind(x, a.clone());
+                self.bind(y, a);
+                self.check(body, ty)?;
+                self.unbind(2)
+            }
+
+            Term::Discard(discarded, body) => {
+                self.infer_discardable(discarded)?;
+                self.check(body, ty)
+            }
+
+            Term::Var(_) | Term::App(..) | Term::Fst(_) | Term::Snd(_) | Term::Derelict(_) => {
+                let found = self.infer(term)?;
+                self.expect(term, &found, ty)
+            }
+        }
+    }
+
+    fn infer(&mut self, term: &Term) -> Result<Formula, TypeError> {
+        match term {
+            Term::Var(x) => self.use_var(x),
+
+            Term::App(f, arg) => {
+                let (a, b) = self.infer_split(f, Formula::lolli, "a function")?;
+                self.check(arg, &a)?;
+                Ok(b)
+            }
+
+            Term::Fst(e) | Term::Snd(e) => {
+                let (a, b) = self.infer_split(e, Formula::with, "a with")?;
+                Ok(if matches!(term, Term::Fst(_)) { a } else { b })
+            }
+
+            Term::Derelict(e) => self.infer_bang(e),
+
+            Term::Unit => Ok(Formula::One),
+            Term::Trivial => {
+                self.absorb();
+                Ok(Formula::Top)
+            }
+
+            Term::Abs(x, body) => {
+                let a = self.fresh();
+                self.bind(x, a.clone());
+                let b = self.infer(body)?;
+                self.unbind(1)?;
+                Ok(Formula::lolli(a, b))
+            }
+
+            Term::Pair(l, r) => {
+                // A tensor pair, or a with pair if the components share
+                // their variables
+                let usages = self.usages();
+                let solved = self.solved.clone();
+                let discarded = self.discarded.len();
+                let tensor = self
+                    .infer(l)
+                    .and_then(|a| Ok(Formula::tensor(a, self.infer(r)?)));
+                if tensor.is_ok() {
+                    return tensor;
+                }
+                self.scope.truncate(usages.len());
+                for (binding, usage) in self.scope.iter_mut().zip(usages) {
+                    binding.usage = usage;
+                }
+                self.solved = solved;
+                self.discarded.truncate(discarded);
+                self.branches(
+                    ["first component", "second component"],
+                    |c| c.infer(l),
+                    |c, _| c.infer(r),
+                )
+                .map(|(a, b)| Formula::with(a, b))
+                .or(tensor)
+            }
+
+            Term::Inl(e) => Ok(Formula::plus(self.infer(e)?, self.fresh())),
+            Term::Inr(e) => {
+                let a = self.fresh();
+                Ok(Formula::plus(a, self.infer(e)?))
+            }
+
+            Term::Promote(e) => {
+                let before = self.usages();
+                let a = self.infer(e)?;
+                self.promoted(&before)?;
+                Ok(Formula::of_course(a))
+            }
+
+            Term::Abort(e) => {
+                self.check(e, &Formula::Zero)?;
+                self.absorb();
+                Ok(self.fresh())
+            }
+
+            Term::LetPair(x, y, pair, body) => {
+                let (a, b) = self.infer_split(pair, Formula::tensor, "a tensor")?;
+                self.bind(x, a);
+                self.bind(y, b);
+                let ty = self.infer(body)?;
+                self.unbind(2)?;
+                Ok(ty)
+            }
+
+            Term::Copy(src, x, y, body) => {
+                let a = Formula::of_course(self.infer_bang(src)?);
+                self.bind(x, a.clone());
+                self.bind(y, a);
+                let ty = self.infer(body)?;
+                self.unbind(2)?;
+                Ok(ty)
+            }
+
+            Term::Discard(discarded, body) => {
+                self.infer_discardable(discarded)?;
+                self.infer(body)
+            }
+
+            Term::Case(scrut, x, left, y, right) => {
+                let (a, b) = self.infer_split(scrut, Formula::plus, "a sum")?;
+                self.branches(
+                    ["inl branch", "inr branch"],
+                    |c| {
+                        c.bind(x, a);
+                        let ty = c.infer(left)?;
+                        c.unbind(1)?;
+                        Ok(ty)
+                    },
+                    |c, ty| {
+                        c.bind(y, b);
+                        c.check(right, ty)?;
+                        c.unbind(1)
+                    },
+                )
+                .map(|(ty, ())| ty)
+            }
+        }
+    }
+
+    /// Infer the type of `term`, which must have the shape `make(A, B)`.
+    fn infer_split(
+        &mut self,
+        term: &Term,
+        make: fn(Formula, Formula) -> Formula,
+        shape: &'static str,
+    ) -> Result<(Formula, Formula), TypeError> {
+        let ty = self.infer(term)?;
+        self.split(&ty, make)
+            .ok_or_else(|| self.shape(term, &ty, shape))
+    }
+
+    fn infer_bang(&mut self, term: &Term) -> Result<Formula, TypeError> {
+        let ty = self.infer(term)?;
+        self.split_bang(&ty)
+            .ok_or_else(|| self.shape(term, &ty, "a !"))
+    }
+
+    /// Only `!A` and `1` may be discarded. A type not known yet is left
+    /// open until [`Checker::discharge`].
+    fn infer_discardable(&mut self, term: &Term) -> Result<(), TypeError> {
+        let ty = self.infer(term)?;
+        self.discardable(term, ty)
+    }
+
+    fn discardable(&mut self, term: &Term, ty: Formula) -> Result<(), TypeError> {
+        match self.head(&ty) {
+            Formula::One | Formula::OfCourse(_) => Ok(()),
+            Formula::Atom(v) if is_var(&v) => {
+                self.discarded.push((term.clone(), ty));
+                Ok(())
+            }
+            _ => Err(self.shape(term, &ty, "a ! or 1")),
+        }
+    }
+
+    /// Check the discarded terms whose type was left open. A type still
+    /// unknown becomes `!A`.
+    fn discharge(&mut self) -> Result<(), TypeError> {
+        for (term, ty) in std::mem::take(&mut self.discarded) {
+            if matches!(self.head(&ty), Formula::Atom(v) if is_var(&v)) {
+                self.split_bang(&ty);
+            } else {
+                self.discardable(&term, ty)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// After a promotion, check that it only used variables of type `!A`.
+    fn promoted(&mut self, before: &[Usage]) -> Result<(), TypeError> {
+        for (i, &was) in before.iter().enumerate() {
+            let binding = &self.scope[i];
+            if binding.usage == was {
+                continue;
+            }
+            if binding.usage == Usage::Used {
+                let ty = binding.ty.clone();
+                if self.split_bang(&ty).is_none() {
+                    return Err(TypeError::Promotion(self.scope[i].name.clone()));
+                }
+            } else if !matches!(self.head(&binding.ty), Formula::OfCourse(_)) {
+                // A ⊤ inside the promotion cannot consume a linear variable
+                self.scope[i].usage = was;
+            }
+        }
+        Ok(())
+    }
+
+    fn bind(&mut self, name: &str, ty: Formula) {
+        self.scope.push(Binding {
+            name: name.to_string(),
+            ty,
+            usage: Usage::Unused,
+        });
+    }
+
+    /// Leave the scope of the innermost `n` variables, which must be used.
+    fn unbind(&mut self, n: usize) -> Result<(), TypeError> {
+        for _ in 0..n {
+            let binding = self.scope.pop().expect("a bound variable");
+            if binding.usage == Usage::Unused {
+                return Err(TypeError::Unused(binding.name));
+            }
+        }
+        Ok(())
+    }
+
+    fn use_var(&mut self, name: &str) -> Result<Formula, TypeError> {
+        let binding = self
+            .scope
+            .iter_mut()
+            .rev()
+            .find(|b| b.name == name)
+            .ok_or_else(|| TypeError::Unbound(name.to_string()))?;
+        if binding.usage == Usage::Used {
+            return Err(TypeError::UsedTwice(name.to_string()));
+        }
+        binding.usage = Usage::Used;
+        Ok(binding.ty.clone())
+    }
+
+    /// Let a `⊤` consume every variable not used so far.
+    fn absorb(&mut self) {
+        for binding in &mut self.scope {
+            if binding.usage == Usage::Unused {
+                binding.usage = Usage::Absorbable;
+            }
+        }
+    }
+
+    fn usages(&self) -> Vec<Usage> {
+        self.scope.iter().map(|b| b.usage).collect()
+    }
+
+    /// Check two additive branches, which must use the same variables.
+    ///
+    /// The right branch is given what the left one returned.
+    fn branches<T, U>(
+        &mut self,
+        names: [&'static str; 2],
+        left: impl FnOnce(&mut Self) -> Result<T, TypeError>,
+        right: impl FnOnce(&mut Self, &T) -> Result<U, TypeError>,
+    ) -> Result<(T, U), TypeError> {
+        let before = self.usages();
+        let result = left(self)?;
+        let after_left = self.usages();
+        for (binding, &usage) in self.scope.iter_mut().zip(&before) {
+            binding.usage = usage;
+        }
+        let other = right(self, &result)?;
+
+        for (binding, &l) in self.scope.iter_mut().zip(&after_left) {
+            let r = binding.usage;
+            binding.usage = match (l, r) {
+                (Usage::Used, Usage::Unused) | (Usage::Unused, Usage::Used) => {
+                    let branch = if l == Usage::Unused {
+                        names[0]
+                    } else {
+                        names[1]
+                    };
+                    return Err(TypeError::UnusedInBranch {
+                        var: binding.name.clone(),
+                        branch,
+                    });
+                }
+                (Usage::Used, _) | (_, Usage::Used) => Usage::Used,
+                (Usage::Unused, _) | (_, Usage::Unused) => Usage::Unused,
+                _ => Usage::Absorbable,
+            };
+        }
+        Ok((result, other))
+    }
+
+    /// A new type variable.
+    fn fresh(&mut self) -> Formula {
+        self.vars += 1;
+        Formula::Atom(format!("'{}", self.vars))
+    }
+
+    /// `ty` with its outermost solved type variables replaced.
+    fn head(&self, ty: &Formula) -> Formula {
+        match ty {
+            Formula::Atom(v) => match self.solved.get(v) {
+                Some(solution) => self.head(solution),
+                None => ty.clone(),
+            },
+            Formula::NegAtom(v) => match self.solved.get(v) {
+                Some(solution) => self.head(&solution.negate()),
+                None => ty.clone(),
+            },
+            _ => ty.clone(),
+        }
+    }
+
+    /// `ty` with every solved type variable replaced.
+    fn resolve(&self, ty: &Formula) -> Formula {
+        let r = |f: &Formula| Box::new(self.resolve(f));
+        match self.head(ty) {
+            Formula::Tensor(a, b) => Formula::Tensor(r(&a), r(&b)),
+            Formula::Par(a, b) => Formula::Par(r(&a), r(&b)),
+            Formula::Lolli(a, b) => Formula::Lolli(r(&a), r(&b)),
+            Formula::With(a, b) => Formula::With(r(&a), r(&b)),
+            Formula::Plus(a, b) => Formula::Plus(r(&a), r(&b)),
+            Formula::OfCourse(a) => Formula::OfCourse(r(&a)),
+            Formula::WhyNot(a) => Formula::WhyNot(r(&a)),
+            ty => ty,
+        }
+    }
+
+    fn show(&self, ty: &Formula) -> String {
+        self.resolve(ty).pretty()
+    }
+
+    /// Make `a` and `b` the same type, solving type variables.
+    fn unify(&mut self, a: &Formula, b: &Formula) -> bool {
+        let (a, b) = (self.head(a), self.head(b));
+        match (&a, &b) {
+            _ if a == b => true,
+            (Formula::Atom(v), t) | (t, Formula::Atom(v)) if is_var(v) => self.solve(v, t.clone()),
+            (Formula::NegAtom(v), t) | (t, Formula::NegAtom(v)) if is_var(v) => {
+                self.solve(v, t.negate())
+            }
+            (Formula::Lolli(a1, b1), Formula::Lolli(a2, b2))
+            | (Formula::Tensor(a1, b1), Formula::Tensor(a2, b2))
+            | (Formula::Par(a1, b1), Formula::Par(a2, b2))
+            | (Formula::With(a1, b1), Formula::With(a2, b2))
+            | (Formula::Plus(a1, b1), Formula::Plus(a2, b2)) => {
+                self.unify(a1, a2) && self.unify(b1, b2)
+            }
+            (Formula::OfCourse(a), Formula::OfCourse(b))
+            | (Formula::WhyNot(a), Formula::WhyNot(b)) => self.unify(a, b),
+            // A ⊸ B is A⊥ ⅋ B
+            (Formula::Lolli(x, y), t) | (t, Formula::Lolli(x, y)) => {
+                let par = Formula::par(x.negate(), y.as_ref().clone());
+                self.unify(&par, t)
+            }
+            _ => false,
+        }
+    }
+
+    fn solve(&mut self, v: &str, ty: Formula) -> bool {
+        let mut vars = Vec::new();
+        atoms(&self.resolve(&ty), &mut vars);
+        if vars.iter().any(|x| x == v) {
+            return false;
+        }
+        self.solved.insert(v.to_string(), ty);
+        true
+    }
+
+    /// The parts of `ty` if it has the shape `make(A, B)`.
+    fn split(
+        &mut self,
+        ty: &Formula,
+        make: fn(Formula, Formula) -> Formula,
+    ) -> Option<(Formula, Formula)> {
+        let (a, b) = (self.fresh(), self.fresh());
+        self.unify(ty, &make(a.clone(), b.clone()))
+            .then_some((a, b))
+    }
+
+    /// `A` if `ty` is `!A`.
+    fn split_bang(&mut self, ty: &Formula) -> Option<Formula> {
+        let a = self.fresh();
+        self.unify(ty, &Formula::of_course(a.clone())).then_some(a)
+    }
+
+    fn expect(
+        &mut self,
+        term: &Term,
+        found: &Formula,
+        expected: &Formula,
+    ) -> Result<(), TypeError> {
+        if self.unify(found, expected) {
+            Ok(())
+        } else {
+            Err(TypeError::Mismatch {
+                term: term.pretty(),
+                found: self.show(found),
+                expected: self.show(expected),
+            })
+        }
+    }
+
+    fn shape(&self, term: &Term, ty: &Formula, shape: &'static str) -> TypeError {
+        TypeError::Shape {
+            term: term.pretty(),
+            found: self.show(ty),
+            shape,
+        }
+    }
+}
+
+/// Whether an atom is a type variable.
+fn is_var(name: &str) -> bool {
+    name.starts_with('\'')
+}
+
+/// Collect the names of the atoms of `ty`.
+fn atoms(ty: &Formula, out: &mut Vec<String>) {
+    match ty {
+        Formula::Atom(a) | Formula::NegAtom(a) => {
+            if !out.contains(a) {
+                out.push(a.clone());
+            }
+        }
+        Formula::Tensor(a, b)
+        | Formula::Par(a, b)
+        | Formula::Lolli(a, b)
+        | Formula::With(a, b)
+        | Formula::Plus(a, b) => {
+            atoms(a, out);
+            atoms(b, out);
+        }
+        Formula::OfCourse(a) | Formula::WhyNot(a) => atoms(a, out),
+        Formula::One | Formula::Bottom | Formula::Top | Formula::Zero => {}
+    }
+}
+
+/// Give the type variables of `ty` names not in `taken`.
+fn rename(ty: &Formula, names: &mut HashMap<String, String>, taken: &mut Vec<String>) -> Formula {
+    let mut r = |f: &Formula| Box::new(rename(f, names, taken));
+    match ty {
+        Formula::Atom(v) if is_var(v) => Formula::Atom(var_name(v, names, taken)),
+        Formula::NegAtom(v) if is_var(v) => Formula::NegAtom(var_name(v, names, taken)),
+        Formula::Tensor(a, b) => Formula::Tensor(r(a), r(b)),
+        Formula::Par(a, b) => Formula::Par(r(a), r(b)),
+        Formula::Lolli(a, b) => Formula::Lolli(r(a), r(b)),
+        Formula::With(a, b) => Formula::With(r(a), r(b)),
+        Formula::Plus(a, b) => Formula::Plus(r(a), r(b)),
+        Formula::OfCourse(a) => Formula::OfCourse(r(a)),
+        Formula::WhyNot(a) => Formula::WhyNot(r(a)),
+        _ => ty.clone(),
+    }
+}
+
+/// The name of type variable `v`: the first of `A`, …, `Z`, `A1`, … not
+/// taken.
+fn var_name(v: &str, names: &mut HashMap<String, String>, taken: &mut Vec<String>) -> String {
+    if let Some(name) = names.get(v) {
+        return name.clone();
+    }
+    let name = (0..)
+        .map(|i: usize| {
+            let letter = char::from(b'A' + (i % 26) as u8);
+            match i / 26 {
+                0 => letter.to_string(),
+                n => format!("{}{}", letter, n),
+            }
+        })
+        .find(|name| !taken.contains(name))
+        .expect("an unused name");
+    taken.push(name.clone());
+    names.insert(v.to_string(), name.clone());
+    name
+}
+
+/// Collect the free variables of `term` in order of first occurrence.
+fn free_vars_in_order(term: &Term, bound: &mut Vec<String>, out: &mut Vec<String>) {
+    let mut under = |names: &[&String], body: &Term, out: &mut Vec<String>| {
+        let n = bound.len();
+        bound.extend(names.iter().map(|x| x.to_string()));
+        free_vars_in_order(body, bound, out);
+        bound.truncate(n);
+    };
+    match term {
+        Term::Var(x) => {
+            if !bound.contains(x) && !out.contains(x) {
+                out.push(x.clone());
+            }
+        }
+        Term::Unit | Term::Trivial => {}
+        Term::Abs(x, body) => under(&[x], body, out),
+        Term::LetPair(x, y, e, body) | Term::Copy(e, x, y, body) => {
+            under(&[], e, out);
+            under(&[x, y], body, out);
+        }
+        Term::Case(e, x, left, y, right) => {
+            under(&[], e, out);
+            under(&[x], left, out);
+            under(&[y], right, out);
+        }
+        Term::Pair(a, b) | Term::App(a, b) | Term::Discard(a, b) => {
+            under(&[], a, out);
+            under(&[], b, out);
+        }
+        Term::Inl(e)
+        | Term::Inr(e)
+        | Term::Fst(e)
+        | Term::Snd(e)
+        | Term::Abort(e)
+        | Term::Promote(e)
+        | Term::Derelict(e) => under(&[], e, out),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var(name: &str) -> Box<Term> {
+        Box::new(Term::Var(name.to_string()))
+    }
+
+    fn atom(name: &str) -> Formula {
+        Formula::atom(name)
+    }
+
+    fn ctx(bindings: &[(&str, Formula)]) -> Vec<(String, Formula)> {
+        bindings
+            .iter()
+            .map(|(name, ty)| (name.to_string(), ty.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_swap() {
+        // λp. let (a, b) = p in (b, a) : A ⊗ B ⊸ B ⊗ A
+        let swap = Term::Abs(
+            "p".to_string(),
+            Box::new(Term::LetPair(
+                "a".to_string(),
+                "b".to_string(),
+                var("p"),
+                Box::new(Term::Pair(var("b"), var("a"))),
+            )),
+        );
+        let ty = Formula::lolli(
+            Formula::tensor(atom("A"), atom("B")),
+            Formula::tensor(atom("B"), atom("A")),
+        );
+        assert_eq!(type_check(&[], &swap, &ty), Ok(()));
+
+        let wrong = Formula::lolli(
+            Formula::tensor(atom("A"), atom("B")),
+            Formula::tensor(atom("A"), atom("B")),
+        );
+        assert!(matches!(
+            type_check(&[], &swap, &wrong),
+            Err(TypeError::Mismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_linearity() {
+        let a = atom("A");
+        let context = ctx(&[("x", a.clone()), ("y", a.clone())]);
+        assert_eq!(
+            type_check(&context, &Term::Var("x".to_string()), &a),
+            Err(TypeError::Unused("y".to_string()))
+        );
+        assert_eq!(
+            type_check(&context, &Term::Var("z".to_string()), &a),
+            Err(TypeError::Unbound("z".to_string()))
+        );
+
+        // λx. () drops x
+        let drop = Term::Abs("x".to_string(), Box::new(Term::Unit));
+        assert_eq!(
+            type_check(&[], &drop, &Formula::lolli(a, Formula::One)),
+            Err(TypeError::Unused("x".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_additive_branches() {
+        // x : A, y : B ⊕ C ⊢ case y of { inl b => (x, b) | inr c => ... } : A ⊗ B
+        let context = ctx(&[("x", atom("A")), ("y", Formula::plus(atom("B"), atom("B")))]);
+        let goal = Formula::tensor(atom("A"), atom("B"));
+        let case = |right: Term| {
+            Term::Case(
+                var("y"),
+                "b".to_string(),
+                Box::new(Term::Pair(var("x"), var("b"))),
+                "c".to_string(),
+                Box::new(right),
+            )
+        };
+        assert_eq!(
+            type_check(&context, &case(Term::Pair(var("x"), var("c"))), &goal),
+            Ok(())
+        );
+        assert_eq!(
+            type_check(
+                &context,
+                &case(Term::Pair(Box::new(Term::Trivial), var("c"))),
+                &goal
+            ),
+            Err(TypeError::Intro {
+                term: "⟨⟩".to_string(),
+                expected: "A".to_string(),
+            })
+        );
+
+        // x : A ⊢ (x, ⟨⟩) : A & ⊤, but not (x, ()) : A & 1
+        let context = ctx(&[("x", atom("A"))]);
+        let with = |b: Formula| Formula::with(atom("A"), b);
+        assert_eq!(
+            type_check(
+                &context,
+                &Term::Pair(var("x"), Box::new(Term::Trivial)),
+                &with(Formula::Top)
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            type_check(
+                &context,
+                &Term::Pair(var("x"), Box::new(Term::Unit)),
+                &with(Formula::One)
+            ),
+            Err(TypeError::UnusedInBranch {
+                var: "x".to_string(),
+                branch: "second component",
+            })
+        );
+    }
+
+    #[test]
+    fn test_unused_in_case_branch() {
+        let pair = Formula::tensor(atom("A"), atom("A"));
+        let context = ctx(&[
+            ("x", atom("A")),
+            ("y", Formula::plus(atom("A"), pair.clone())),
+        ]);
+        let case = Term::Case(
+            var("y"),
+            "a".to_string(),
+            Box::new(Term::Pair(var("x"), var("a"))),
+            "b".to_string(),
+            Box::new(Term::Var("b".to_string())),
+        );
+        let err = type_check(&context, &case, &pair).unwrap_err();
+        assert_eq!(err.to_string(), "x unused in inr branch");
+    }
+
+    #[test]
+    fn test_exponentials() {
+        let bang = Formula::of_course(atom("A"));
+        let context = ctx(&[("x", bang.clone())]);
+
+        // copy x as (y, z) in (derelict y, derelict z) : A ⊗ A
+        let copy = Term::Copy(
+            var("x"),
+            "y".to_string(),
+            "z".to_string(),
+            Box::new(Term::Pair(
+                Box::new(Term::Derelict(var("y"))),
+                Box::new(Term::Derelict(var("z"))),
+            )),
+        );
+        let pair = Formula::tensor(atom("A"), atom("A"));
+        assert_eq!(type_check(&context, &copy, &pair), Ok(()));
+
+        // discard x in () : 1
+        let discard = Term::Discard(var("x"), Box::new(Term::Unit));
+        assert_eq!(type_check(&context, &discard, &Formula::One), Ok(()));
+
+        // !x : !!A, but a linear y cannot be promoted
+        let promote = Term::Promote(var("x"));
+        assert_eq!(
+            type_check(&context, &promote, &Formula::of_course(bang)),
+            Ok(())
+        );
+        let linear = ctx(&[("y", atom("A"))]);
+        assert_eq!(
+            type_check(
+                &linear,
+                &Term::Promote(var("y")),
+                &Formula::of_course(atom("A"))
+            ),
+            Err(TypeError::Promotion("y".to_string()))
+        );
+
+        // A linear variable cannot be derelicted
+        let err = type_check(&linear, &Term::Derelict(var("y")), &atom("A")).unwrap_err();
+        assert_eq!(err.to_string(), "y has type A, expected a !");
+    }
+
+    #[test]
+    fn test_application() {
+        // f : A ⊸ B, x : A ⊢ f x : B, also with f : A⊥ ⅋ B
+        for f in [
+            Formula::lolli(atom("A"), atom("B")),
+            Formula::par(atom("A").negate(), atom("B")),
+        ] {
+            let context = ctx(&[("f", f), ("x", atom("A"))]);
+            let app = Term::App(var("f"), var("x"));
+            assert_eq!(type_check(&context, &app, &atom("B")), Ok(()));
+        }
+
+        // A redex binds its variable to the argument's type
+        let redex = Term::App(
+            Box::new(Term::Abs("y".to_string(), var("y"))),
+            Box::new(Term::Unit),
+        );
+        assert_eq!(type_check(&[], &redex, &Formula::One), Ok(()));
+        assert_eq!(
+            type_check(&[], &Term::Abs("y".to_string(), var("y")), &atom("A")),
+            Err(TypeError::Intro {
+                term: "λy. y".to_string(),
+                expected: "A".to_string(),
+            })
+        );
+    }
+
+    fn abs(x: &str, body: Term) -> Term {
+        Term::Abs(x.to_string(), Box::new(body))
+    }
+
+    fn infer(term: &Term) -> String {
+        infer_type(term).unwrap().pretty()
+    }
+
+    #[test]
+    fn test_infer_combinators() {
+        // λx. λy. (x, y) : A ⊸ B ⊸ A ⊗ B
+        let pair = abs("x", abs("y", Term::Pair(var("x"), var("y"))));
+        assert_eq!(infer(&pair), "⊢ (A ⊸ (B ⊸ (A ⊗ B)))");
+
+        // λf. λx. f x : (A ⊸ B) ⊸ A ⊸ B
+        let apply = abs("f", abs("x", Term::App(var("f"), var("x"))));
+        assert_eq!(infer(&apply), "⊢ ((A ⊸ B) ⊸ (A ⊸ B))");
+
+        // λp. let (a, b) = p in (b, a) : A ⊗ B ⊸ B ⊗ A
+        let swap = abs(
+            "p",
+            Term::LetPair(
+                "a".to_string(),
+                "b".to_string(),
+                var("p"),
+                Box::new(Term::Pair(var("b"), var("a"))),
+            ),
+        );
+        assert_eq!(infer(&swap), "⊢ ((A ⊗ B) ⊸ (B ⊗ A))");
+
+        // λx. case x of { inl a => inr a | inr b => inl b } : A ⊕ B ⊸ B ⊕ A
+        let mirror = abs(
+            "x",
+            Term::Case(
+                var("x"),
+                "a".to_string(),
+                Box::new(Term::Inr(var("a"))),
+                "b".to_string(),
+                Box::new(Term::Inl(var("b"))),
+            ),
+        );
+        assert_eq!(infer(&mirror), "⊢ ((A ⊕ B) ⊸ (B ⊕ A))");
+    }
+
+    #[test]
+    fn test_infer_with_pair() {
+        // A pair whose components share x is a with pair
+        let diagonal = abs("x", Term::Pair(var("x"), var("x")));
+        assert_eq!(infer(&diagonal), "⊢ (A ⊸ (A & A))");
+
+        let first = abs("p", Term::Fst(var("p")));
+        assert_eq!(infer(&first), "⊢ ((A & B) ⊸ A)");
+    }
+
+    #[test]
+    fn test_infer_exponentials() {
+        // λx. copy x as (y, z) in (derelict y, derelict z) : !A ⊸ A ⊗ A
+        let duplicate = abs(
+            "x",
+            Term::Copy(
+                var("x"),
+                "y".to_string(),
+                "z".to_string(),
+                Box::new(Term::Pair(
+                    Box::new(Term::Derelict(var("y"))),
+                    Box::new(Term::Derelict(var("z"))),
+                )),
+            ),
+        );
+        assert_eq!(infer(&duplicate), "⊢ (!A ⊸ (A ⊗ A))");
+
+        // Promotion needs x : !A
+        let promote = abs("x", Term::Promote(var("x")));
+        assert_eq!(infer(&promote), "⊢ (!A ⊸ !!A)");
+
+        let erase = abs("x", Term::Discard(var("x"), Box::new(Term::Unit)));
+        assert_eq!(infer(&erase), "⊢ (!A ⊸ 1)");
+
+        // A discarded variable may still turn out to be a unit
+        let erase = abs("x", Term::Discard(var("x"), Box::new(Term::Trivial)));
+        let unit = Term::App(Box::new(erase.clone()), Box::new(Term::Unit));
+        assert_eq!(infer(&unit), "⊢ ⊤");
+        let top = Term::App(Box::new(erase), Box::new(Term::Trivial));
+        assert_eq!(
+            infer_type(&top).unwrap_err().to_string(),
+            "x has type ⊤, expected a ! or 1"
+        );
+    }
+
+    #[test]
+    fn test_infer_open_term() {
+        let app = Term::App(var("f"), Box::new(Term::Pair(var("x"), var("y"))));
+        assert_eq!(infer(&app), "f : ((A ⊗ B) ⊸ C), x : A, y : B ⊢ C");
+    }
+
+    #[test]
+    fn test_infer_errors() {
+        // λx. x x applies x to itself
+        let omega = abs("x", Term::App(var("x"), var("x")));
+        assert_eq!(
+            infer_type(&omega),
+            Err(TypeError::UsedTwice("x".to_string()))
+        );
+
+        let drop = abs("x", Term::Unit);
+        assert_eq!(infer_type(&drop), Err(TypeError::Unused("x".to_string())));
+
+        // A unit cannot be applied
+        let apply_unit = Term::App(Box::new(Term::Unit), Box::new(Term::Unit));
+        assert_eq!(
+            infer_type(&apply_unit).unwrap_err().to_string(),
+            "() has type 1, expected a function"
+        );
+    }
+}
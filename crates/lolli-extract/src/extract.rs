@@ -1,9 +1,98 @@
 //! Term extraction from proofs.
 //!
 //! This module implements the Curry-Howard correspondence for linear logic,
-//! extracting computational terms from cut-free proofs.
+//! extracting computational terms from proofs.
+//!
+//! A proof of `⊢ Γ, C` is read as a term of type `C`. The other formulas
+//! of the conclusion are its hypotheses: the variable `arg{i}` stands for
+//! the formula at index `i`, and has the type `A⊥` of a formula `A`, or
+//! `!A⊥` of an unrestricted formula `A`. [`signature`] gives these types.
+//!
+//! Only intuitionistic proofs have such a reading. A step that would need
+//! a term of type `⊥` or `?A`, or a tensor of hypotheses whose left premise
+//! proves `C`, has no term.
+
+use lolli_core::{Formula, Proof, Rule, Sequent, Term};
+
+/// Error type for term extraction.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ExtractError {
+    /// The proof concludes the empty sequent
+    #[error("The empty sequent has no term")]
+    EmptySequent,
+
+    /// A proof step does not apply its rule to its conclusion
+    #[error("Invalid rule {rule:?} for conclusion {conclusion}")]
+    InvalidStep {
+        /// The rule that was applied
+        rule: Rule,
+        /// The conclusion of the proof step
+        conclusion: String,
+    },
+
+    /// A proof step with no intuitionistic reading
+    #[error("Rule {rule:?} for conclusion {conclusion} has no term")]
+    NoTerm {
+        /// The rule that was applied
+        rule: Rule,
+        /// The conclusion of the proof step
+        conclusion: String,
+    },
+}
 
-use lolli_core::{Formula, Proof, Rule, Term};
+/// The typing of the terms extracted from proofs of `seq`: the type of each
+/// free variable, and the type of the term.
+///
+/// Returns `None` for the empty sequent.
+///
+/// # Example
+///
+/// ```
+/// use lolli_extract::{signature, Extractor, Proof, Rule, Sequent};
+/// use lolli_core::{type_check, Formula};
+///
+/// let proof = Proof {
+///     conclusion: Sequent::new(vec![Formula::neg_atom("A"), Formula::atom("A")]),
+///     rule: Rule::Axiom,
+///     premises: vec![],
+/// };
+/// let term = Extractor::new().extract(&proof).unwrap();
+///
+/// let (context, ty) = signature(&proof.conclusion).unwrap();
+/// assert_eq!(context, vec![("arg0".to_string(), Formula::atom("A"))]);
+/// assert_eq!(type_check(&context, &term, &ty), Ok(()));
+/// ```
+pub fn signature(seq: &Sequent) -> Option<(Vec<(String, Formula)>, Formula)> {
+    let mut formulas: Vec<&Formula> = seq.linear.iter().chain(&seq.focus).collect();
+    let output = formulas.pop()?.clone();
+    let context = formulas
+        .iter()
+        .map(|f| f.negate())
+        .chain(
+            seq.unrestricted
+                .iter()
+                .map(|f| Formula::of_course(f.negate())),
+        )
+        .enumerate()
+        .map(|(i, ty)| (format!("arg{}", i), ty))
+        .collect();
+    Some((context, output))
+}
+
+/// What a formula of a sequent stands for in the term being extracted.
+#[derive(Clone, Debug)]
+enum Role {
+    /// A hypothesis of type `A⊥`, for the formula `A`
+    Input(Term),
+    /// The type of the term
+    Output,
+}
+
+/// The linear zone of a sequent with the role of each formula.
+type Zone = Vec<(Formula, Role)>;
+
+/// The variable of type `!A⊥` for each unrestricted formula `A`.
+type Env = Vec<(Formula, String)>;
 
 /// Term extractor using Curry-Howard correspondence.
 ///
@@ -22,9 +111,7 @@ impl Default for Extractor {
 impl Extractor {
     /// Create a new extractor.
     pub fn new() -> Self {
-        Self {
-            var_counter: 0,
-        }
+        Self { var_counter: 0 }
     }
 
     /// Generate a fresh variable name.
@@ -49,472 +136,1028 @@ impl Extractor {
 
     /// Extract a term from a proof.
     ///
-    /// The proof should be cut-free for best results. The extracted term
-    /// represents the computational content of the proof via Curry-Howard.
-    pub fn extract(&mut self, proof: &Proof) -> Term {
-        self.extract_with_env(proof, &mut Vec::new())
+    /// The term has the type of the last formula of the conclusion, with
+    /// the free variables given by [`signature`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ExtractError`] if a step of the proof is invalid or has
+    /// no term.
+    pub fn extract(&mut self, proof: &Proof) -> Result<Term, ExtractError> {
+        let formulas = proof.conclusion.linear_zone();
+        let output = formulas
+            .len()
+            .checked_sub(1)
+            .ok_or(ExtractError::EmptySequent)?;
+        let zone = formulas
+            .into_iter()
+            .enumerate()
+            .map(|(i, f)| {
+                let role = if i == output {
+                    Role::Output
+                } else {
+                    Role::Input(Term::Var(format!("arg{}", i)))
+                };
+                (f, role)
+            })
+            .collect();
+        let env = proof
+            .conclusion
+            .unrestricted_zone()
+            .into_iter()
+            .enumerate()
+            .map(|(i, f)| (f, format!("arg{}", output + i)))
+            .collect();
+        let term = self.extract_step(proof, zone, env)?;
+        Ok(simplify(term))
     }
 
-    /// Extract with an environment mapping formula indices to variables.
-    fn extract_with_env(&mut self, proof: &Proof, vars: &mut Vec<(Formula, String)>) -> Term {
+    /// Extract a premise, discarding the unrestricted variables it does not
+    /// have.
+    fn extract_premise(
+        &mut self,
+        proof: &Proof,
+        zone: Zone,
+        env: &[(Formula, String)],
+    ) -> Result<Term, ExtractError> {
+        let mut wanted = proof.conclusion.unrestricted_zone();
+        let mut kept = Vec::new();
+        let mut dropped = Vec::new();
+        for (f, u) in env.iter().rev() {
+            match wanted.iter().position(|g| g == f) {
+                Some(pos) => {
+                    wanted.swap_remove(pos);
+                    kept.push((f.clone(), u.clone()));
+                }
+                None => dropped.push(u.clone()),
+            }
+        }
+        if !wanted.is_empty() {
+            return Err(invalid_step(proof));
+        }
+        kept.reverse();
+
+        // Hypotheses of type !A are bound to variables, which promotions
+        // may use
+        let mut bound = Vec::new();
+        let mut items = Vec::new();
+        for (f, role) in zone {
+            match role {
+                Role::Input(t) if matches!(f, Formula::WhyNot(_)) && !matches!(t, Term::Var(_)) => {
+                    let x = self.fresh_var();
+                    items.push((f, Role::Input(Term::Var(x.clone()))));
+                    bound.push((x, t));
+                }
+                role => items.push((f, role)),
+            }
+        }
+
+        let body = self.extract_step(proof, items, kept)?;
+        let body = bound.into_iter().rev().fold(body, |body, (x, t)| {
+            Term::App(Box::new(Term::Abs(x, Box::new(body))), Box::new(t))
+        });
+        Ok(discard(dropped, body))
+    }
+
+    /// Extract a proof step, given the roles of its linear formulas and the
+    /// variables of its unrestricted formulas.
+    fn extract_step(&mut self, proof: &Proof, zone: Zone, env: Env) -> Result<Term, ExtractError> {
+        let expected = match proof.rule {
+            Rule::Axiom | Rule::OneIntro | Rule::TopIntro => 0,
+            Rule::TensorIntro | Rule::WithIntro | Rule::Cut(_) => 2,
+            _ => 1,
+        };
+        if proof.premises.len() != expected {
+            return Err(invalid_step(proof));
+        }
+        let premises: Vec<Vec<Formula>> = proof
+            .premises
+            .iter()
+            .map(|p| p.conclusion.linear_zone())
+            .collect();
+        let no_term = || ExtractError::NoTerm {
+            rule: proof.rule.clone(),
+            conclusion: proof.conclusion.pretty(),
+        };
+
         match &proof.rule {
             Rule::Axiom => {
-                // Axiom: ⊢ A⊥, A
-                // This represents identity: λx. x
-                // Find the positive formula and create an identity
-                self.extract_axiom(proof, vars)
+                // ⊢ A⊥, A is the hypothesis itself
+                if let [(x @ (Formula::Atom(_) | Formula::NegAtom(_)), a), (y, b)] = zone.as_slice()
+                {
+                    if *y == x.negate() {
+                        return match (a, b) {
+                            (Role::Input(t), Role::Output) | (Role::Output, Role::Input(t)) => {
+                                Ok(discard(env.into_iter().map(|(_, u)| u), t.clone()))
+                            }
+                            _ => Err(no_term()),
+                        };
+                    }
+                }
             }
 
-            Rule::OneIntro => {
-                // ⊢ 1 corresponds to ()
-                Term::Unit
-            }
+            Rule::OneIntro => match zone.as_slice() {
+                [(Formula::One, Role::Output)] => {
+                    return Ok(discard(env.into_iter().map(|(_, u)| u), Term::Unit));
+                }
+                [(Formula::One, _)] => return Err(no_term()),
+                _ => {}
+            },
 
             Rule::TopIntro => {
-                // ⊢ Γ, ⊤ corresponds to ⟨⟩ (trivial)
-                Term::Trivial
+                // ⊤ consumes every hypothesis, as ⟨⟩ or as absurd of a
+                // hypothesis of type 0
+                let mut tops = zone.iter().filter(|(f, _)| *f == Formula::Top);
+                if tops.clone().any(|(_, role)| matches!(role, Role::Output)) {
+                    return Ok(Term::Trivial);
+                }
+                if let Some((_, Role::Input(t))) = tops.next() {
+                    return Ok(Term::Abort(Box::new(t.clone())));
+                }
             }
 
             Rule::BottomIntro => {
-                // From ⊢ Γ derive ⊢ Γ, ⊥
-                // Bottom is the unit for par, it doesn't contribute a term
-                if !proof.premises.is_empty() {
-                    self.extract_with_env(&proof.premises[0], vars)
-                } else {
-                    Term::Unit
+                for (i, (f, role)) in zone.iter().enumerate() {
+                    let rest = without(&zone, i);
+                    if *f != Formula::Bottom || !fits(&rest, &[], &premises[0]) {
+                        continue;
+                    }
+                    let Role::Input(t) = role else {
+                        return Err(no_term());
+                    };
+                    let body = self.extract_premise(&proof.premises[0], rest, &env)?;
+                    return Ok(Term::Discard(Box::new(t.clone()), Box::new(body)));
                 }
             }
 
             Rule::TensorIntro => {
-                // From ⊢ Γ, A and ⊢ Δ, B derive ⊢ Γ, Δ, A ⊗ B
-                // Tensor corresponds to pairing: (a, b)
-                self.extract_tensor(proof, vars)
+                for (i, (f, role)) in zone.iter().enumerate() {
+                    let Formula::Tensor(a, b) = f else {
+                        continue;
+                    };
+                    let Some((left, right)) = split(without(&zone, i), a, b, &premises) else {
+                        continue;
+                    };
+                    let (env0, env1, copies) = self.share(env, &proof.premises);
+                    let term = match role {
+                        Role::Output => {
+                            let l = self.extract_premise(
+                                &proof.premises[0],
+                                extend(left, [(*a.clone(), Role::Output)]),
+                                &env0,
+                            )?;
+                            let r = self.extract_premise(
+                                &proof.premises[1],
+                                extend(right, [(*b.clone(), Role::Output)]),
+                                &env1,
+                            )?;
+                            Term::Pair(Box::new(l), Box::new(r))
+                        }
+                        Role::Input(h) => {
+                            // h : A ⊸ B⊥ is applied to the proof of A
+                            if left.iter().any(|(_, r)| matches!(r, Role::Output)) {
+                                return Err(no_term());
+                            }
+                            let arg = self.extract_premise(
+                                &proof.premises[0],
+                                extend(left, [(*a.clone(), Role::Output)]),
+                                &env0,
+                            )?;
+                            let applied = Term::App(Box::new(h.clone()), Box::new(arg));
+                            self.extract_premise(
+                                &proof.premises[1],
+                                extend(right, [(*b.clone(), Role::Input(applied))]),
+                                &env1,
+                            )?
+                        }
+                    };
+                    return Ok(copy(copies, term));
+                }
             }
 
             Rule::ParIntro => {
-                // From ⊢ Γ, A, B derive ⊢ Γ, A ⅋ B
-                // Par is the dual of tensor, corresponds to curried function
-                if !proof.premises.is_empty() {
-                    self.extract_with_env(&proof.premises[0], vars)
-                } else {
-                    Term::Unit
+                for (i, (f, role)) in zone.iter().enumerate() {
+                    let rest = without(&zone, i);
+                    let Formula::Par(a, b) = f else {
+                        continue;
+                    };
+                    if !fits(&rest, &[a, b], &premises[0]) {
+                        continue;
+                    }
+                    let x = self.var_for_formula(a);
+                    return Ok(match role {
+                        Role::Output => {
+                            let zone = extend(
+                                rest,
+                                [
+                                    (*a.clone(), Role::Input(Term::Var(x.clone()))),
+                                    (*b.clone(), Role::Output),
+                                ],
+                            );
+                            let body = self.extract_premise(&proof.premises[0], zone, &env)?;
+                            Term::Abs(x, Box::new(body))
+                        }
+                        Role::Input(h) => {
+                            let y = self.var_for_formula(b);
+                            let zone = extend(
+                                rest,
+                                [
+                                    (*a.clone(), Role::Input(Term::Var(x.clone()))),
+                                    (*b.clone(), Role::Input(Term::Var(y.clone()))),
+                                ],
+                            );
+                            let body = self.extract_premise(&proof.premises[0], zone, &env)?;
+                            Term::LetPair(x, y, Box::new(h.clone()), Box::new(body))
+                        }
+                    });
                 }
             }
 
             Rule::WithIntro => {
-                // From ⊢ Γ, A and ⊢ Γ, B derive ⊢ Γ, A & B
-                // With corresponds to lazy pair: ⟨fst, snd⟩
-                self.extract_with(proof, vars)
-            }
-
-            Rule::PlusIntroLeft => {
-                // From ⊢ Γ, A derive ⊢ Γ, A ⊕ B
-                // Plus left corresponds to: inl a
-                if !proof.premises.is_empty() {
-                    let inner = self.extract_with_env(&proof.premises[0], vars);
-                    Term::Inl(Box::new(inner))
-                } else {
-                    Term::Inl(Box::new(Term::Unit))
+                for (i, (f, role)) in zone.iter().enumerate() {
+                    let rest = without(&zone, i);
+                    let Formula::With(a, b) = f else {
+                        continue;
+                    };
+                    if !fits(&rest, &[a], &premises[0]) || !fits(&rest, &[b], &premises[1]) {
+                        continue;
+                    }
+                    return Ok(match role {
+                        Role::Output => {
+                            let l = self.extract_premise(
+                                &proof.premises[0],
+                                extend(rest.clone(), [(*a.clone(), Role::Output)]),
+                                &env,
+                            )?;
+                            let r = self.extract_premise(
+                                &proof.premises[1],
+                                extend(rest, [(*b.clone(), Role::Output)]),
+                                &env,
+                            )?;
+                            Term::Pair(Box::new(l), Box::new(r))
+                        }
+                        Role::Input(h) => {
+                            let x = self.var_for_formula(a);
+                            let y = self.var_for_formula(b);
+                            let l = self.extract_premise(
+                                &proof.premises[0],
+                                extend(
+                                    rest.clone(),
+                                    [(*a.clone(), Role::Input(Term::Var(x.clone())))],
+                                ),
+                                &env,
+                            )?;
+                            let r = self.extract_premise(
+                                &proof.premises[1],
+                                extend(rest, [(*b.clone(), Role::Input(Term::Var(y.clone())))]),
+                                &env,
+                            )?;
+                            Term::Case(Box::new(h.clone()), x, Box::new(l), y, Box::new(r))
+                        }
+                    });
                 }
             }
 
-            Rule::PlusIntroRight => {
-                // From ⊢ Γ, B derive ⊢ Γ, A ⊕ B
-                // Plus right corresponds to: inr b
-                if !proof.premises.is_empty() {
-                    let inner = self.extract_with_env(&proof.premises[0], vars);
-                    Term::Inr(Box::new(inner))
-                } else {
-                    Term::Inr(Box::new(Term::Unit))
+            Rule::PlusIntroLeft | Rule::PlusIntroRight => {
+                let left = proof.rule == Rule::PlusIntroLeft;
+                for (i, (f, role)) in zone.iter().enumerate() {
+                    let rest = without(&zone, i);
+                    let Formula::Plus(a, b) = f else {
+                        continue;
+                    };
+                    let part = if left { a } else { b };
+                    if !fits(&rest, &[part], &premises[0]) {
+                        continue;
+                    }
+                    return Ok(match role {
+                        Role::Output => {
+                            let zone = extend(rest, [(*part.clone(), Role::Output)]);
+                            let body = self.extract_premise(&proof.premises[0], zone, &env)?;
+                            if left {
+                                Term::Inl(Box::new(body))
+                            } else {
+                                Term::Inr(Box::new(body))
+                            }
+                        }
+                        Role::Input(h) => {
+                            let h = Box::new(h.clone());
+                            let projected = if left { Term::Fst(h) } else { Term::Snd(h) };
+                            let zone = extend(rest, [(*part.clone(), Role::Input(projected))]);
+                            self.extract_premise(&proof.premises[0], zone, &env)?
+                        }
+                    });
                 }
             }
 
             Rule::OfCourseIntro => {
-                // From ⊢ ?Γ, A derive ⊢ ?Γ, !A
-                // Promote the term to be copyable
-                if !proof.premises.is_empty() {
-                    let inner = self.extract_with_env(&proof.premises[0], vars);
-                    Term::Promote(Box::new(inner))
-                } else {
-                    Term::Promote(Box::new(Term::Unit))
+                for (i, (f, role)) in zone.iter().enumerate() {
+                    let rest = without(&zone, i);
+                    let Formula::OfCourse(a) = f else {
+                        continue;
+                    };
+                    if !fits(&rest, &[a], &premises[0])
+                        || rest.iter().any(|(g, _)| !matches!(g, Formula::WhyNot(_)))
+                    {
+                        continue;
+                    }
+                    let Role::Output = role else {
+                        return Err(no_term());
+                    };
+                    let zone = extend(rest, [(*a.clone(), Role::Output)]);
+                    let body = self.extract_premise(&proof.premises[0], zone, &env)?;
+                    return Ok(Term::Promote(Box::new(body)));
                 }
             }
 
             Rule::WhyNotIntro => {
-                // From ⊢ Γ, A derive ⊢ Γ, ?A
-                if !proof.premises.is_empty() {
-                    self.extract_with_env(&proof.premises[0], vars)
-                } else {
-                    Term::Unit
+                let unrestricted = proof.premises[0].conclusion.unrestricted_zone();
+                for (i, (f, role)) in zone.iter().enumerate() {
+                    let rest = without(&zone, i);
+                    let Formula::WhyNot(a) = f else {
+                        continue;
+                    };
+                    let dyadic = fits(&rest, &[], &premises[0]) && unrestricted.contains(a);
+                    if !dyadic && !fits(&rest, &[a], &premises[0]) {
+                        continue;
+                    }
+                    let Role::Input(t) = role else {
+                        return Err(no_term());
+                    };
+                    if !dyadic {
+                        let zone = extend(rest, [(*a.clone(), derelict(t.clone()))]);
+                        return self.extract_premise(&proof.premises[0], zone, &env);
+                    }
+
+                    // The hypothesis of type !A⊥ becomes unrestricted
+                    let (u, arg) = match t {
+                        Term::Var(u) => (u.clone(), None),
+                        _ => (self.fresh_var(), Some(t.clone())),
+                    };
+                    let env = extend(env, [(*a.clone(), u.clone())]);
+                    let body = self.extract_premise(&proof.premises[0], rest, &env)?;
+                    return Ok(match arg {
+                        Some(arg) => {
+                            Term::App(Box::new(Term::Abs(u, Box::new(body))), Box::new(arg))
+                        }
+                        None => body,
+                    });
                 }
             }
 
             Rule::Weakening => {
-                // From ⊢ Γ derive ⊢ Γ, ?A
-                // Discard a resource
-                if !proof.premises.is_empty() {
-                    let body = self.extract_with_env(&proof.premises[0], vars);
-                    Term::Discard(Box::new(Term::Unit), Box::new(body))
-                } else {
-                    Term::Unit
+                if fits(&zone, &[], &premises[0]) {
+                    return self.extract_premise(&proof.premises[0], zone, &env);
+                }
+                for (i, (f, role)) in zone.iter().enumerate() {
+                    let rest = without(&zone, i);
+                    if !matches!(f, Formula::WhyNot(_)) || !fits(&rest, &[], &premises[0]) {
+                        continue;
+                    }
+                    let Role::Input(t) = role else {
+                        return Err(no_term());
+                    };
+                    let body = self.extract_premise(&proof.premises[0], rest, &env)?;
+                    return Ok(Term::Discard(Box::new(t.clone()), Box::new(body)));
                 }
             }
 
             Rule::Contraction => {
-                // From ⊢ Γ, ?A, ?A derive ⊢ Γ, ?A
-                // Copy a resource
-                if !proof.premises.is_empty() {
-                    let x = self.fresh_var();
-                    let y = self.fresh_var();
-                    let src = Term::Var(self.fresh_var());
-                    let body = self.extract_with_env(&proof.premises[0], vars);
-                    Term::Copy(Box::new(src), x, y, Box::new(body))
-                } else {
-                    Term::Unit
+                for (k, (a, u)) in env.iter().enumerate() {
+                    if !fits(&zone, &[a, a], &premises[0]) {
+                        continue;
+                    }
+                    let (kept, copied) = (self.fresh_var(), self.fresh_var());
+                    let (x, y) = (self.fresh_var(), self.fresh_var());
+                    let zone = extend(
+                        zone.clone(),
+                        [
+                            (a.clone(), derelict(Term::Var(x.clone()))),
+                            (a.clone(), derelict(Term::Var(y.clone()))),
+                        ],
+                    );
+                    let mut env = env.clone();
+                    env[k].1 = kept.clone();
+                    let body = self.extract_premise(&proof.premises[0], zone, &env)?;
+                    let body =
+                        Term::Copy(Box::new(Term::Var(copied.clone())), x, y, Box::new(body));
+                    return Ok(copy([(u.clone(), kept, copied)], body));
+                }
+                for (i, (f, role)) in zone.iter().enumerate() {
+                    let rest = without(&zone, i);
+                    if !matches!(f, Formula::WhyNot(_)) || !fits(&rest, &[f, f], &premises[0]) {
+                        continue;
+                    }
+                    let Role::Input(t) = role else {
+                        return Err(no_term());
+                    };
+                    let (x, y) = (self.fresh_var(), self.fresh_var());
+                    let zone = extend(
+                        rest,
+                        [
+                            (f.clone(), Role::Input(Term::Var(x.clone()))),
+                            (f.clone(), Role::Input(Term::Var(y.clone()))),
+                        ],
+                    );
+                    let body = self.extract_premise(&proof.premises[0], zone, &env)?;
+                    return Ok(Term::Copy(Box::new(t.clone()), x, y, Box::new(body)));
                 }
             }
 
             Rule::Dereliction => {
-                // Use !A as A
-                if !proof.premises.is_empty() {
-                    let inner = self.extract_with_env(&proof.premises[0], vars);
-                    Term::Derelict(Box::new(inner))
-                } else {
-                    Term::Derelict(Box::new(Term::Unit))
+                for (k, (a, u)) in env.iter().enumerate() {
+                    if !fits(&zone, &[a], &premises[0]) {
+                        continue;
+                    }
+                    let (kept, used) = (self.fresh_var(), self.fresh_var());
+                    let zone = extend(
+                        zone.clone(),
+                        [(a.clone(), derelict(Term::Var(used.clone())))],
+                    );
+                    let mut env = env.clone();
+                    env[k].1 = kept.clone();
+                    let body = self.extract_premise(&proof.premises[0], zone, &env)?;
+                    return Ok(copy([(u.clone(), kept, used)], body));
+                }
+                for (i, (f, role)) in zone.iter().enumerate() {
+                    let rest = without(&zone, i);
+                    let Formula::WhyNot(a) = f else {
+                        continue;
+                    };
+                    if !fits(&rest, &[a], &premises[0]) {
+                        continue;
+                    }
+                    let Role::Input(t) = role else {
+                        return Err(no_term());
+                    };
+                    let zone = extend(rest, [(*a.clone(), derelict(t.clone()))]);
+                    return self.extract_premise(&proof.premises[0], zone, &env);
                 }
             }
 
-            Rule::Cut(formula) => {
-                // Cut: from ⊢ Γ, A and ⊢ Δ, A⊥ derive ⊢ Γ, Δ
-                // This corresponds to let-binding or application
-                self.extract_cut(proof, formula, vars)
+            Rule::Cut(a) => {
+                // The premise without the output proves the cut formula,
+                // which the other one uses as a hypothesis
+                let a = a.desugar();
+                let not_a = a.negate();
+                if let Some((left, right)) = split(zone, &a, &not_a, &premises) {
+                    let (env0, env1, copies) = self.share(env, &proof.premises);
+                    let (p0, p1) = (&proof.premises[0], &proof.premises[1]);
+                    let term = if right.iter().any(|(_, r)| matches!(r, Role::Output)) {
+                        let producer =
+                            self.extract_premise(p0, extend(left, [(a, Role::Output)]), &env0)?;
+                        let zone = extend(right, [(not_a, Role::Input(producer))]);
+                        self.extract_premise(p1, zone, &env1)?
+                    } else {
+                        let producer = self.extract_premise(
+                            p1,
+                            extend(right, [(not_a, Role::Output)]),
+                            &env1,
+                        )?;
+                        let zone = extend(left, [(a, Role::Input(producer))]);
+                        self.extract_premise(p0, zone, &env0)?
+                    };
+                    return Ok(copy(copies, term));
+                }
             }
 
             Rule::FocusPositive(_) | Rule::FocusNegative(_) | Rule::Blur => {
-                // Focus rules don't change the term, just pass through
-                if !proof.premises.is_empty() {
-                    self.extract_with_env(&proof.premises[0], vars)
-                } else {
-                    Term::Unit
+                // Focusing moves formulas without changing the term
+                if fits(&zone, &[], &premises[0]) {
+                    return self.extract_premise(&proof.premises[0], zone, &env);
                 }
             }
         }
+
+        Err(invalid_step(proof))
     }
 
-    /// Extract term for axiom rule.
-    fn extract_axiom(&mut self, proof: &Proof, vars: &[(Formula, String)]) -> Term {
-        // The axiom ⊢ A⊥, A represents identity
-        // Look for matching formulas in the conclusion
-        let formulas = &proof.conclusion.linear;
-
-        // Find atoms and their negations
-        for formula in formulas {
-            match formula {
-                Formula::Atom(name) => {
-                    // Check if we have a variable for this in the environment
-                    for (f, v) in vars {
-                        if let Formula::NegAtom(neg_name) = f {
-                            if neg_name == name {
-                                return Term::Var(v.clone());
-                            }
-                        }
-                    }
-                    // Otherwise create a fresh variable representing identity
-                    return Term::Var(name.to_lowercase());
-                }
-                Formula::NegAtom(name) => {
-                    // Check if we have a variable for the positive in the environment
-                    for (f, v) in vars {
-                        if let Formula::Atom(pos_name) = f {
-                            if pos_name == name {
-                                return Term::Var(v.clone());
-                            }
-                        }
-                    }
+    /// Share the unrestricted variables between the two premises of a
+    /// step, copying those both of them have.
+    fn share(&mut self, env: Env, premises: &[Proof]) -> (Env, Env, Vec<(String, String, String)>) {
+        let mut left_wanted = premises[0].conclusion.unrestricted_zone();
+        let mut right_wanted = premises[1].conclusion.unrestricted_zone();
+        let (mut left, mut right, mut copies) = (Vec::new(), Vec::new(), Vec::new());
+        for (f, u) in env {
+            match (take(&mut left_wanted, &f), take(&mut right_wanted, &f)) {
+                (true, true) => {
+                    let (x, y) = (self.fresh_var(), self.fresh_var());
+                    left.push((f.clone(), x.clone()));
+                    right.push((f, y.clone()));
+                    copies.push((u, x, y));
                 }
-                _ => {}
+                (false, true) => right.push((f, u)),
+                // Discarded by the left premise if it does not have it
+                _ => left.push((f, u)),
             }
         }
+        (left, right, copies)
+    }
+}
 
-        // Default: create identity function
-        let var = self.fresh_var();
-        Term::Abs(var.clone(), Box::new(Term::Var(var)))
+fn invalid_step(proof: &Proof) -> ExtractError {
+    ExtractError::InvalidStep {
+        rule: proof.rule.clone(),
+        conclusion: proof.conclusion.pretty(),
     }
+}
 
-    /// Extract term for tensor introduction.
-    fn extract_tensor(&mut self, proof: &Proof, vars: &mut Vec<(Formula, String)>) -> Term {
-        if proof.premises.len() == 2 {
-            let left = self.extract_with_env(&proof.premises[0], vars);
-            let right = self.extract_with_env(&proof.premises[1], vars);
-            Term::Pair(Box::new(left), Box::new(right))
-        } else if proof.premises.len() == 1 {
-            self.extract_with_env(&proof.premises[0], vars)
-        } else {
-            Term::Unit
+fn derelict(term: Term) -> Role {
+    Role::Input(Term::Derelict(Box::new(term)))
+}
+
+/// `body` with each of `vars` discarded.
+fn discard(vars: impl IntoIterator<Item = String>, body: Term) -> Term {
+    let vars: Vec<String> = vars.into_iter().collect();
+    vars.into_iter().rev().fold(body, |body, u| {
+        Term::Discard(Box::new(Term::Var(u)), Box::new(body))
+    })
+}
+
+/// `body` in the scope of `copy u as (x, y)` for each `(u, x, y)`.
+fn copy(copies: impl IntoIterator<Item = (String, String, String)>, body: Term) -> Term {
+    let copies: Vec<_> = copies.into_iter().collect();
+    copies.into_iter().rev().fold(body, |body, (u, x, y)| {
+        Term::Copy(Box::new(Term::Var(u)), x, y, Box::new(body))
+    })
+}
+
+/// `zone` without its formula at `i`.
+fn without(zone: &Zone, i: usize) -> Zone {
+    let mut zone = zone.clone();
+    zone.remove(i);
+    zone
+}
+
+fn extend<T>(mut items: Vec<T>, extra: impl IntoIterator<Item = T>) -> Vec<T> {
+    items.extend(extra);
+    items
+}
+
+/// Remove one copy of `formula` from `items`, if there is one.
+fn take(items: &mut Vec<Formula>, formula: &Formula) -> bool {
+    match items.iter().position(|f| f == formula) {
+        Some(pos) => {
+            items.swap_remove(pos);
+            true
         }
+        None => false,
     }
+}
+
+/// Whether `zone` and `extra` are the formulas of `premise`, as multisets.
+fn fits(zone: &Zone, extra: &[&Formula], premise: &[Formula]) -> bool {
+    let mut rest = premise.to_vec();
+    zone.len() + extra.len() == premise.len()
+        && zone
+            .iter()
+            .map(|(f, _)| f)
+            .chain(extra.iter().copied())
+            .all(|f| take(&mut rest, f))
+}
 
-    /// Extract term for with introduction.
-    fn extract_with(&mut self, proof: &Proof, vars: &mut Vec<(Formula, String)>) -> Term {
-        if proof.premises.len() == 2 {
-            let left = self.extract_with_env(&proof.premises[0], vars);
-            let right = self.extract_with_env(&proof.premises[1], vars);
-            // With creates a pair that can be projected
-            Term::Pair(Box::new(left), Box::new(right))
-        } else if proof.premises.len() == 1 {
-            self.extract_with_env(&proof.premises[0], vars)
+/// Split `zone` between two premises proving `a` and `b`. Hypotheses go
+/// left when they can, and the output right.
+fn split(zone: Zone, a: &Formula, b: &Formula, premises: &[Vec<Formula>]) -> Option<(Zone, Zone)> {
+    let mut left_wanted = premises[0].clone();
+    let mut right_wanted = premises[1].clone();
+    if !take(&mut left_wanted, a) || !take(&mut right_wanted, b) {
+        return None;
+    }
+    let (mut left, mut right) = (Vec::new(), Vec::new());
+    let (outputs, inputs): (Zone, Zone) = zone
+        .into_iter()
+        .partition(|(_, role)| matches!(role, Role::Output));
+    for (f, role) in inputs {
+        if take(&mut left_wanted, &f) {
+            left.push((f, role));
+        } else if take(&mut right_wanted, &f) {
+            right.push((f, role));
+        } else {
+            return None;
+        }
+    }
+    for (f, role) in outputs {
+        if take(&mut right_wanted, &f) {
+            right.push((f, role));
+        } else if take(&mut left_wanted, &f) {
+            left.push((f, role));
         } else {
-            Term::Trivial
+            return None;
         }
     }
+    (left_wanted.is_empty() && right_wanted.is_empty()).then_some((left, right))
+}
 
-    /// Extract term for cut rule.
-    fn extract_cut(
-        &mut self,
-        proof: &Proof,
-        cut_formula: &Formula,
-        vars: &mut Vec<(Formula, String)>,
-    ) -> Term {
-        if proof.premises.len() != 2 {
-            return Term::Unit;
+/// Drop the copies of unrestricted variables that are only discarded.
+fn simplify(term: Term) -> Term {
+    let term = map_subterms(term, &mut simplify);
+    let Term::Copy(src, x, y, body) = term else {
+        return term;
+    };
+    let Term::Var(u) = src.as_ref() else {
+        return Term::Copy(src, x, y, body);
+    };
+    let u = Term::Var(u.clone());
+    match (only_discarded(&body, &x), only_discarded(&body, &y)) {
+        (true, true) => {
+            let body = undiscard(undiscard(*body, &x), &y);
+            Term::Discard(src, Box::new(body))
         }
+        (true, false) => undiscard(*body, &x).substitute(&y, &u),
+        (false, true) => undiscard(*body, &y).substitute(&x, &u),
+        (false, false) => Term::Copy(src, x, y, body),
+    }
+}
 
-        // Generate a variable for the cut formula
-        let cut_var = self.var_for_formula(cut_formula);
+/// Whether `x` occurs in `term` only as `discard x in e`.
+fn only_discarded(term: &Term, x: &str) -> bool {
+    match term {
+        Term::Var(v) => v != x,
+        Term::Discard(e, body) if matches!(e.as_ref(), Term::Var(v) if v == x) => {
+            only_discarded(body, x)
+        }
+        _ => subterms(term).into_iter().all(|t| only_discarded(t, x)),
+    }
+}
 
-        // Extract the term that produces the cut formula
-        let producer = self.extract_with_env(&proof.premises[0], vars);
+/// `term` without the discards of `x`.
+fn undiscard(term: Term, x: &str) -> Term {
+    match term {
+        Term::Discard(e, body) if matches!(e.as_ref(), Term::Var(v) if v == x) => {
+            undiscard(*body, x)
+        }
+        term => map_subterms(term, &mut |t| undiscard(t, x)),
+    }
+}
 
-        // Add the cut variable to the environment for the consumer
-        vars.push((cut_formula.clone(), cut_var.clone()));
-        let consumer = self.extract_with_env(&proof.premises[1], vars);
-        vars.pop();
+fn subterms(term: &Term) -> Vec<&Term> {
+    match term {
+        Term::Var(_) | Term::Unit | Term::Trivial => vec![],
+        Term::Abs(_, e)
+        | Term::Inl(e)
+        | Term::Inr(e)
+        | Term::Fst(e)
+        | Term::Snd(e)
+        | Term::Abort(e)
+        | Term::Promote(e)
+        | Term::Derelict(e) => vec![e],
+        Term::Pair(a, b)
+        | Term::App(a, b)
+        | Term::LetPair(_, _, a, b)
+        | Term::Discard(a, b)
+        | Term::Copy(a, _, _, b) => vec![a, b],
+        Term::Case(scrut, _, left, _, right) => vec![scrut, left, right],
+    }
+}
 
-        // Cut corresponds to application or let-binding depending on the formula
-        match cut_formula {
-            Formula::Tensor(_, _) => {
-                // Tensor cut becomes let-pair
-                let x = self.fresh_var();
-                let y = self.fresh_var();
-                Term::LetPair(x, y, Box::new(producer), Box::new(consumer))
-            }
-            Formula::Plus(_, _) => {
-                // Plus cut becomes case
-                let x = self.fresh_var();
-                let y = self.fresh_var();
-                Term::Case(
-                    Box::new(producer),
-                    x,
-                    Box::new(consumer.clone()),
-                    y,
-                    Box::new(consumer),
-                )
-            }
-            _ => {
-                // Default: application
-                Term::App(Box::new(consumer), Box::new(producer))
-            }
-        }
+fn map_subterms(term: Term, f: &mut impl FnMut(Term) -> Term) -> Term {
+    let mut map = |t: Box<Term>| Box::new(f(*t));
+    match term {
+        Term::Var(_) | Term::Unit | Term::Trivial => term,
+        Term::Pair(a, b) => Term::Pair(map(a), map(b)),
+        Term::LetPair(x, y, pair, body) => Term::LetPair(x, y, map(pair), map(body)),
+        Term::Abs(x, body) => Term::Abs(x, map(body)),
+        Term::App(fun, arg) => Term::App(map(fun), map(arg)),
+        Term::Inl(e) => Term::Inl(map(e)),
+        Term::Inr(e) => Term::Inr(map(e)),
+        Term::Case(scrut, x, left, y, right) => Term::Case(map(scrut), x, map(left), y, map(right)),
+        Term::Fst(e) => Term::Fst(map(e)),
+        Term::Snd(e) => Term::Snd(map(e)),
+        Term::Abort(e) => Term::Abort(map(e)),
+        Term::Promote(e) => Term::Promote(map(e)),
+        Term::Derelict(e) => Term::Derelict(map(e)),
+        Term::Discard(e, body) => Term::Discard(map(e), map(body)),
+        Term::Copy(src, x, y, body) => Term::Copy(map(src), x, y, map(body)),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use lolli_core::Sequent;
+    use lolli_core::type_check;
 
-    #[test]
-    fn test_extract_axiom() {
-        let proof = Proof {
-            conclusion: Sequent::new(vec![Formula::neg_atom("A"), Formula::atom("A")]),
-            rule: Rule::Axiom,
-            premises: vec![],
-        };
+    fn axiom(a: &str) -> Proof {
+        step(
+            vec![Formula::neg_atom(a), Formula::atom(a)],
+            Rule::Axiom,
+            vec![],
+        )
+    }
 
-        let mut extractor = Extractor::new();
-        let term = extractor.extract(&proof);
+    fn step(linear: Vec<Formula>, rule: Rule, premises: Vec<Proof>) -> Proof {
+        Proof {
+            conclusion: Sequent::new(linear),
+            rule,
+            premises,
+        }
+    }
 
-        // Axiom gives us a variable (identity)
-        assert!(matches!(term, Term::Var(_) | Term::Abs(_, _)));
+    /// Extract a term from `proof`, check it against its conclusion and
+    /// compare it with `expected` up to the names of bound variables.
+    fn assert_extracts(proof: &Proof, expected: &str) {
+        let term = Extractor::new().extract(proof).unwrap();
+        let (context, ty) = signature(&proof.conclusion).unwrap();
+        assert_eq!(
+            type_check(&context, &term, &ty),
+            Ok(()),
+            "{}",
+            term.pretty()
+        );
+        let expected = lolli_parse::parse_term(expected).unwrap();
+        assert!(
+            term.alpha_eq(&expected),
+            "extracted {}, expected {}",
+            term.pretty(),
+            expected.pretty()
+        );
     }
 
     #[test]
-    fn test_extract_one() {
-        let proof = Proof {
-            conclusion: Sequent::new(vec![Formula::One]),
-            rule: Rule::OneIntro,
-            premises: vec![],
-        };
-
-        let mut extractor = Extractor::new();
-        let term = extractor.extract(&proof);
+    fn test_extract_axiom() {
+        assert_extracts(&axiom("A"), "arg0");
+    }
 
-        assert_eq!(term, Term::Unit);
+    #[test]
+    fn test_extract_one() {
+        let proof = step(vec![Formula::One], Rule::OneIntro, vec![]);
+        assert_extracts(&proof, "()");
     }
 
     #[test]
     fn test_extract_top() {
-        let proof = Proof {
-            conclusion: Sequent::new(vec![Formula::atom("A"), Formula::Top]),
-            rule: Rule::TopIntro,
-            premises: vec![],
-        };
+        let proof = step(
+            vec![Formula::neg_atom("A"), Formula::Top],
+            Rule::TopIntro,
+            vec![],
+        );
+        assert_extracts(&proof, "⟨⟩");
 
-        let mut extractor = Extractor::new();
-        let term = extractor.extract(&proof);
-
-        assert_eq!(term, Term::Trivial);
+        // A hypothesis of type 0 proves anything
+        let proof = step(
+            vec![Formula::Top, Formula::atom("A")],
+            Rule::TopIntro,
+            vec![],
+        );
+        assert_extracts(&proof, "absurd arg0");
     }
 
     #[test]
     fn test_extract_tensor() {
-        // Proof of ⊢ A⊥, B⊥, A ⊗ B
-        let left = Proof {
-            conclusion: Sequent::new(vec![Formula::neg_atom("A"), Formula::atom("A")]),
-            rule: Rule::Axiom,
-            premises: vec![],
-        };
-        let right = Proof {
-            conclusion: Sequent::new(vec![Formula::neg_atom("B"), Formula::atom("B")]),
-            rule: Rule::Axiom,
-            premises: vec![],
-        };
-        let proof = Proof {
-            conclusion: Sequent::new(vec![
+        // A, B ⊢ A ⊗ B
+        let proof = step(
+            vec![
                 Formula::neg_atom("A"),
                 Formula::neg_atom("B"),
                 Formula::tensor(Formula::atom("A"), Formula::atom("B")),
-            ]),
-            rule: Rule::TensorIntro,
-            premises: vec![left, right],
-        };
-
-        let mut extractor = Extractor::new();
-        let term = extractor.extract(&proof);
-
-        // Should be a pair
-        assert!(matches!(term, Term::Pair(_, _)));
+            ],
+            Rule::TensorIntro,
+            vec![axiom("A"), axiom("B")],
+        );
+        assert_extracts(&proof, "(arg0, arg1)");
     }
 
     #[test]
-    fn test_extract_plus_left() {
-        let inner = Proof {
-            conclusion: Sequent::new(vec![Formula::neg_atom("A"), Formula::atom("A")]),
-            rule: Rule::Axiom,
-            premises: vec![],
-        };
-        let proof = Proof {
-            conclusion: Sequent::new(vec![
+    fn test_extract_application() {
+        // A ⊸ B, A ⊢ B is ⊢ A ⊗ B⊥, A⊥, B
+        let proof = step(
+            vec![
+                Formula::tensor(Formula::atom("A"), Formula::neg_atom("B")),
                 Formula::neg_atom("A"),
-                Formula::plus(Formula::atom("A"), Formula::atom("B")),
-            ]),
-            rule: Rule::PlusIntroLeft,
-            premises: vec![inner],
-        };
-
-        let mut extractor = Extractor::new();
-        let term = extractor.extract(&proof);
-
-        // Should be inl
-        assert!(matches!(term, Term::Inl(_)));
+                Formula::atom("B"),
+            ],
+            Rule::TensorIntro,
+            vec![axiom("A"), axiom("B")],
+        );
+        assert_extracts(&proof, "(arg0 arg1)");
     }
 
     #[test]
-    fn test_extract_plus_right() {
-        let inner = Proof {
-            conclusion: Sequent::new(vec![Formula::neg_atom("B"), Formula::atom("B")]),
-            rule: Rule::Axiom,
-            premises: vec![],
-        };
-        let proof = Proof {
-            conclusion: Sequent::new(vec![
+    fn test_extract_par() {
+        // ⊢ A ⊗ B ⊸ B ⊗ A is λx. let (a, b) = x in (b, a)
+        let (a, b) = (Formula::atom("A"), Formula::atom("B"));
+        let swap = step(
+            vec![
+                Formula::neg_atom("A"),
                 Formula::neg_atom("B"),
-                Formula::plus(Formula::atom("A"), Formula::atom("B")),
-            ]),
-            rule: Rule::PlusIntroRight,
-            premises: vec![inner],
-        };
+                Formula::tensor(b.clone(), a.clone()),
+            ],
+            Rule::TensorIntro,
+            vec![axiom("B"), axiom("A")],
+        );
+        let unpaired = step(
+            vec![
+                Formula::par(a.negate(), b.negate()),
+                Formula::tensor(b.clone(), a.clone()),
+            ],
+            Rule::ParIntro,
+            vec![swap],
+        );
+        let proof = step(
+            vec![Formula::lolli(
+                Formula::tensor(a.clone(), b.clone()),
+                Formula::tensor(b, a),
+            )],
+            Rule::ParIntro,
+            vec![unpaired],
+        );
+        assert_extracts(&proof, "λp. let (a, b) = p in (b, a)");
+    }
 
-        let mut extractor = Extractor::new();
-        let term = extractor.extract(&proof);
+    #[test]
+    fn test_extract_plus() {
+        let plus = Formula::plus(Formula::atom("A"), Formula::atom("B"));
+        let left = step(
+            vec![Formula::neg_atom("A"), plus.clone()],
+            Rule::PlusIntroLeft,
+            vec![axiom("A")],
+        );
+        assert_extracts(&left, "inl arg0");
 
-        // Should be inr
-        assert!(matches!(term, Term::Inr(_)));
+        let right = step(
+            vec![Formula::neg_atom("B"), plus],
+            Rule::PlusIntroRight,
+            vec![axiom("B")],
+        );
+        assert_extracts(&right, "inr arg0");
     }
 
     #[test]
     fn test_extract_with() {
-        let left = Proof {
-            conclusion: Sequent::new(vec![Formula::atom("A")]),
-            rule: Rule::Axiom,
-            premises: vec![],
-        };
-        let right = Proof {
-            conclusion: Sequent::new(vec![Formula::atom("B")]),
-            rule: Rule::Axiom,
-            premises: vec![],
-        };
-        let proof = Proof {
-            conclusion: Sequent::new(vec![Formula::with(Formula::atom("A"), Formula::atom("B"))]),
-            rule: Rule::WithIntro,
-            premises: vec![left, right],
-        };
+        // A ⊢ A & A shares the hypothesis between the components
+        let proof = step(
+            vec![
+                Formula::neg_atom("A"),
+                Formula::with(Formula::atom("A"), Formula::atom("A")),
+            ],
+            Rule::WithIntro,
+            vec![axiom("A"), axiom("A")],
+        );
+        assert_extracts(&proof, "(arg0, arg0)");
 
-        let mut extractor = Extractor::new();
-        let term = extractor.extract(&proof);
+        // A & B ⊢ B projects
+        let proof = step(
+            vec![
+                Formula::plus(Formula::neg_atom("A"), Formula::neg_atom("B")),
+                Formula::atom("B"),
+            ],
+            Rule::PlusIntroRight,
+            vec![axiom("B")],
+        );
+        assert_extracts(&proof, "snd arg0");
+    }
 
-        // Should be a pair
-        assert!(matches!(term, Term::Pair(_, _)));
+    #[test]
+    fn test_extract_case() {
+        // A ⊕ A ⊢ A
+        let proof = step(
+            vec![
+                Formula::with(Formula::neg_atom("A"), Formula::neg_atom("A")),
+                Formula::atom("A"),
+            ],
+            Rule::WithIntro,
+            vec![axiom("A"), axiom("A")],
+        );
+        assert_extracts(&proof, "case arg0 of { inl a => a | inr b => b }");
     }
 
     #[test]
     fn test_extract_promote() {
-        let inner = Proof {
-            conclusion: Sequent::new(vec![Formula::atom("A")]),
-            rule: Rule::Axiom,
-            premises: vec![],
-        };
-        let proof = Proof {
-            conclusion: Sequent::new(vec![Formula::of_course(Formula::atom("A"))]),
-            rule: Rule::OfCourseIntro,
-            premises: vec![inner],
+        // !A ⊢ !A derelicts under the promotion
+        let bang = Formula::of_course(Formula::atom("A"));
+        let derelicted = step(
+            vec![Formula::why_not(Formula::neg_atom("A")), Formula::atom("A")],
+            Rule::Dereliction,
+            vec![axiom("A")],
+        );
+        let proof = step(
+            vec![Formula::why_not(Formula::neg_atom("A")), bang],
+            Rule::OfCourseIntro,
+            vec![derelicted],
+        );
+        assert_extracts(&proof, "!derelict arg0");
+    }
+
+    #[test]
+    fn test_extract_unrestricted() {
+        // !A ⊢ A ⊗ A moves A to the unrestricted zone and uses it twice
+        let a = Formula::atom("A");
+        let unrestricted = |linear: Vec<Formula>, rule, premises| Proof {
+            conclusion: Sequent {
+                linear,
+                unrestricted: vec![a.negate()],
+                focus: None,
+            },
+            rule,
+            premises,
         };
+        let used = |_| unrestricted(vec![Formula::neg_atom("A"), a.clone()], Rule::Axiom, vec![]);
+        let derelicted = |i| unrestricted(vec![a.clone()], Rule::Dereliction, vec![used(i)]);
+        let pair = unrestricted(
+            vec![Formula::tensor(a.clone(), a.clone())],
+            Rule::TensorIntro,
+            vec![derelicted(0), derelicted(1)],
+        );
+        let proof = step(
+            vec![
+                Formula::why_not(a.negate()),
+                Formula::tensor(a.clone(), a.clone()),
+            ],
+            Rule::WhyNotIntro,
+            vec![pair],
+        );
+        assert_extracts(&proof, "copy arg0 as (x, y) in (derelict x, derelict y)");
+    }
 
-        let mut extractor = Extractor::new();
-        let term = extractor.extract(&proof);
+    #[test]
+    fn test_no_term() {
+        // ⊢ 1, ⊥ would need a term of type ⊥
+        let one = step(vec![Formula::One], Rule::OneIntro, vec![]);
+        let proof = step(
+            vec![Formula::One, Formula::Bottom],
+            Rule::BottomIntro,
+            vec![one],
+        );
+        assert!(matches!(
+            Extractor::new().extract(&proof),
+            Err(ExtractError::NoTerm {
+                rule: Rule::BottomIntro,
+                ..
+            })
+        ));
 
-        // Should be promote
-        assert!(matches!(term, Term::Promote(_)));
+        let empty = step(vec![], Rule::Axiom, vec![]);
+        assert_eq!(
+            Extractor::new().extract(&empty),
+            Err(ExtractError::EmptySequent)
+        );
     }
 
     #[test]
-    fn test_pretty_print() {
-        let proof = Proof {
-            conclusion: Sequent::new(vec![
-                Formula::neg_atom("A"),
-                Formula::neg_atom("B"),
-                Formula::tensor(Formula::atom("A"), Formula::atom("B")),
-            ]),
-            rule: Rule::TensorIntro,
-            premises: vec![
-                Proof {
-                    conclusion: Sequent::new(vec![Formula::neg_atom("A"), Formula::atom("A")]),
-                    rule: Rule::Axiom,
-                    premises: vec![],
-                },
-                Proof {
-                    conclusion: Sequent::new(vec![Formula::neg_atom("B"), Formula::atom("B")]),
-                    rule: Rule::Axiom,
-                    premises: vec![],
-                },
+    fn test_invalid_step() {
+        // ⊢ A is not an axiom
+        let proof = step(
+            vec![Formula::with(Formula::atom("A"), Formula::atom("B"))],
+            Rule::WithIntro,
+            vec![
+                step(vec![Formula::atom("A")], Rule::Axiom, vec![]),
+                step(vec![Formula::atom("B")], Rule::Axiom, vec![]),
             ],
-        };
-
-        let mut extractor = Extractor::new();
-        let term = extractor.extract(&proof);
-        let pretty = term.pretty();
+        );
+        assert!(matches!(
+            Extractor::new().extract(&proof),
+            Err(ExtractError::InvalidStep {
+                rule: Rule::Axiom,
+                ..
+            })
+        ));
+    }
 
-        // Should contain pair syntax
-        assert!(pretty.contains(',') || pretty.contains('('));
+    #[test]
+    fn test_extracted_terms_are_well_typed() {
+        let sequents = [
+            "A |- A",
+            "A, B |- A * B",
+            "A * B |- B * A",
+            "A -o B, A |- B",
+            "A -o B, B -o C |- A -o C",
+            "|- (A -o B) -o (B -o C) -o A -o C",
+            "A & B |- A",
+            "A & B |- B & A",
+            "A |- A + B",
+            "A + B |- B + A",
+            "A * (B + C) |- (A * B) + (A * C)",
+            "(A -o C) & (B -o C) |- (A + B) -o C",
+            "A, 0 |- B",
+            "A |- top",
+            "1, A |- A",
+            "!A |- A",
+            "!A |- A * A",
+            "!A |- 1",
+            "!A, !B |- !(A * B)",
+            "!(A -o B), !A |- B * B",
+            "!(A & B) |- !A * !B",
+            "A & A |- A",
+            "A -o B & C |- (A -o B) & (A -o C)",
+        ];
+        let mut prover = lolli_prove::Prover::new(20);
+        for input in sequents {
+            let seq = lolli_parse::parse_sequent(input).unwrap().to_one_sided();
+            let (context, ty) = signature(&seq).unwrap();
+            let mut count = 0;
+            for proof in prover.proofs(&seq).up_to_permutation().take(8) {
+                let term = Extractor::new()
+                    .extract(&proof)
+                    .unwrap_or_else(|e| panic!("{}: {}", input, e));
+                if let Err(e) = type_check(&context, &term, &ty) {
+                    panic!("{}: {} is ill-typed: {}", input, term.pretty(), e);
+                }
+                let printed = term.pretty();
+                let reparsed = lolli_parse::parse_term(&printed)
+                    .unwrap_or_else(|e| panic!("{}: {} does not parse: {}", input, printed, e));
+                assert_eq!(reparsed, term, "{}: {} does not round-trip", input, printed);
+                count += 1;
+            }
+            assert!(count > 0, "{} has no proof", input);
+        }
     }
 }
@@ -34,9 +34,14 @@
 //! };
 //!
 //! let mut extractor = Extractor::new();
-//! let term = extractor.extract(&proof);
-//! // The extracted term is λa. a (identity function)
+//! let term = extractor.extract(&proof).unwrap();
+//! // The extracted term is the hypothesis A, named arg0
+//! assert_eq!(term, Term::Var("arg0".to_string()));
 //! ```
+//!
+//! Extracted terms are well typed: [`signature`] gives the types of their
+//! free variables and their own, to check them with
+//! [`lolli_core::type_check`].
 
 #![warn(missing_docs)]
 #![warn(clippy::all)]
@@ -46,8 +51,11 @@ pub use lolli_core::{Formula, Proof, Rule, Sequent, Term};
 mod extract;
 mod normalize;
 
-pub use extract::Extractor;
-pub use normalize::{is_normal, normalize, normalize_bounded, step};
+pub use extract::{signature, ExtractError, Extractor};
+pub use normalize::{
+    is_normal, normalize, normalize_bounded, normalize_with, step, step_with, trace, Reduction,
+    ReductionStep, Strategy, Trace,
+};
 
 /// Extract a term from a proof (convenience function).
 ///
@@ -63,10 +71,15 @@ pub use normalize::{is_normal, normalize, normalize_bounded, step};
 ///     premises: vec![],
 /// };
 ///
-/// let term = extract_term(&proof);
+/// let term = extract_term(&proof).unwrap();
 /// println!("{}", term.pretty());
 /// ```
-pub fn extract_term(proof: &Proof) -> Term {
+///
+/// # Errors
+///
+/// Returns an [`ExtractError`] if a step of the proof is invalid or has no
+/// term.
+pub fn extract_term(proof: &Proof) -> Result<Term, ExtractError> {
     let mut extractor = Extractor::new();
     extractor.extract(proof)
 }
@@ -2,10 +2,123 @@
 //!
 //! This module provides normalization (beta reduction) for linear lambda terms.
 //! Since terms are linear, reduction is strongly normalizing.
+//!
+//! A [`Strategy`] picks the redex to contract next, and [`trace`] records
+//! every step of a reduction sequence with the rule that fired.
 
+use lolli_core::term::fresh_var;
 use lolli_core::Term;
 
-/// Perform one step of reduction, if possible.
+/// The order in which redexes are contracted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Strategy {
+    /// The leftmost-outermost redex, anywhere in the term, including under
+    /// binders. Reaches the normal form.
+    #[default]
+    NormalOrder,
+    /// The leftmost-outermost redex, substituting arguments unevaluated.
+    /// Never reduces under a binder or inside a pair, an injection or a
+    /// promotion, so it stops at a weak head normal form.
+    CallByName,
+    /// Evaluates functions, arguments and pair components left to right
+    /// before a redex that uses them is contracted. Never reduces under a
+    /// binder or inside a promotion.
+    CallByValue,
+}
+
+impl Strategy {
+    /// Every strategy, in the order they are documented.
+    pub const ALL: [Strategy; 3] = [
+        Strategy::NormalOrder,
+        Strategy::CallByName,
+        Strategy::CallByValue,
+    ];
+
+    /// The name of the strategy, as accepted by [`Strategy::from_name`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Strategy::NormalOrder => "normal-order",
+            Strategy::CallByName => "call-by-name",
+            Strategy::CallByValue => "call-by-value",
+        }
+    }
+
+    /// Look up a strategy by name.
+    pub fn from_name(name: &str) -> Option<Strategy> {
+        Strategy::ALL.into_iter().find(|s| s.name() == name)
+    }
+}
+
+/// The rule that contracted a redex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reduction {
+    /// `(λx. e) v → e[v/x]`
+    Beta,
+    /// `let (x, y) = (a, b) in e → e[a/x, b/y]`
+    LetPair,
+    /// `case inl v of { inl x => e1 | inr y => e2 } → e1[v/x]`, and
+    /// likewise for `inr`
+    Case,
+    /// `fst (a, b) → a`
+    Fst,
+    /// `snd (a, b) → b`
+    Snd,
+    /// `derelict !v → v`
+    Derelict,
+    /// `copy !v as (x, y) in e → e[!v/x, !v/y]`, copying each free
+    /// variable of `v` for the two copies
+    Copy,
+    /// `discard !v in e → e`, discarding each free variable of `v`, and
+    /// `discard () in e → e`
+    Discard,
+}
+
+impl std::fmt::Display for Reduction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Reduction::Beta => "β",
+            Reduction::LetPair => "let-pair",
+            Reduction::Case => "case",
+            Reduction::Fst => "fst",
+            Reduction::Snd => "snd",
+            Reduction::Derelict => "derelict",
+            Reduction::Copy => "copy",
+            Reduction::Discard => "discard",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// One step of a reduction sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReductionStep {
+    /// The rule that fired
+    pub rule: Reduction,
+    /// The subterm that was contracted
+    pub redex: Term,
+    /// What the redex was replaced with
+    pub contractum: Term,
+    /// The whole term after the step
+    pub term: Term,
+}
+
+/// A reduction sequence, as recorded by [`trace`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    /// The term the sequence starts from
+    pub start: Term,
+    /// The steps in the order they were taken
+    pub steps: Vec<ReductionStep>,
+}
+
+impl Trace {
+    /// The term the sequence ends with.
+    pub fn result(&self) -> &Term {
+        self.steps.last().map_or(&self.start, |step| &step.term)
+    }
+}
+
+/// Perform one step of normal-order reduction, if possible.
 ///
 /// Returns `Some(reduced)` if a reduction was performed, `None` if the term is normal.
 ///
@@ -24,159 +137,200 @@ use lolli_core::Term;
 /// assert_eq!(reduced, Some(Term::Unit));
 /// ```
 pub fn step(term: &Term) -> Option<Term> {
-    match term {
-        // Beta reduction: (λx. e) v → e[v/x]
-        Term::App(f, arg) => {
-            if let Term::Abs(x, body) = f.as_ref() {
-                Some(body.substitute(x, arg))
-            } else {
-                // Try to reduce the function
-                if let Some(f_reduced) = step(f) {
-                    Some(Term::App(Box::new(f_reduced), arg.clone()))
-                } else if let Some(arg_reduced) = step(arg) {
-                    Some(Term::App(f.clone(), Box::new(arg_reduced)))
-                } else {
-                    None
-                }
-            }
-        }
-
-        // Let-pair reduction: let (x, y) = (a, b) in e → e[a/x][b/y]
-        Term::LetPair(x, y, pair, body) => {
-            if let Term::Pair(a, b) = pair.as_ref() {
-                let substituted = body.substitute(x, a).substitute(y, b);
-                Some(substituted)
-            } else if let Some(pair_reduced) = step(pair) {
-                Some(Term::LetPair(
-                    x.clone(),
-                    y.clone(),
-                    Box::new(pair_reduced),
-                    body.clone(),
-                ))
-            } else if let Some(body_reduced) = step(body) {
-                Some(Term::LetPair(
-                    x.clone(),
-                    y.clone(),
-                    pair.clone(),
-                    Box::new(body_reduced),
-                ))
-            } else {
-                None
-            }
-        }
-
-        // Case reduction: case inl v of { inl x => e1 | inr y => e2 } → e1[v/x]
-        Term::Case(scrut, x, left, y, right) => {
-            match scrut.as_ref() {
-                Term::Inl(v) => Some(left.substitute(x, v)),
-                Term::Inr(v) => Some(right.substitute(y, v)),
-                _ => {
-                    if let Some(scrut_reduced) = step(scrut) {
-                        Some(Term::Case(
-                            Box::new(scrut_reduced),
-                            x.clone(),
-                            left.clone(),
-                            y.clone(),
-                            right.clone(),
-                        ))
-                    } else {
-                        None
-                    }
-                }
-            }
-        }
-
-        // Fst reduction: fst (a, b) → a
-        Term::Fst(pair) => {
-            if let Term::Pair(a, _) = pair.as_ref() {
-                Some(a.as_ref().clone())
-            } else if let Some(pair_reduced) = step(pair) {
-                Some(Term::Fst(Box::new(pair_reduced)))
-            } else {
-                None
-            }
-        }
-
-        // Snd reduction: snd (a, b) → b
-        Term::Snd(pair) => {
-            if let Term::Pair(_, b) = pair.as_ref() {
-                Some(b.as_ref().clone())
-            } else if let Some(pair_reduced) = step(pair) {
-                Some(Term::Snd(Box::new(pair_reduced)))
-            } else {
-                None
-            }
-        }
+    step_with(term, Strategy::NormalOrder)
+}
 
-        // Dereliction: derelict (!v) → v
-        Term::Derelict(e) => {
-            if let Term::Promote(v) = e.as_ref() {
-                Some(v.as_ref().clone())
-            } else if let Some(e_reduced) = step(e) {
-                Some(Term::Derelict(Box::new(e_reduced)))
-            } else {
-                None
-            }
-        }
+/// Perform one step of reduction under `strategy`, if possible.
+///
+/// Returns `None` if `strategy` finds no redex to contract.
+pub fn step_with(term: &Term, strategy: Strategy) -> Option<Term> {
+    reduce(term, strategy).map(|(reduced, _)| reduced)
+}
 
-        // Copy reduction: copy !v as (x, y) in e → e[!v/x][!v/y]
-        Term::Copy(src, x, y, body) => {
-            if let Term::Promote(v) = src.as_ref() {
-                let promoted = Term::Promote(v.clone());
-                let substituted = body.substitute(x, &promoted).substitute(y, &promoted);
-                Some(substituted)
-            } else if let Some(src_reduced) = step(src) {
-                Some(Term::Copy(
-                    Box::new(src_reduced),
-                    x.clone(),
-                    y.clone(),
-                    body.clone(),
-                ))
-            } else {
-                None
-            }
-        }
+/// A contracted redex: the rule, the redex and its contractum.
+type Contraction = (Reduction, Term, Term);
+
+/// Contract the redex `strategy` picks in `term`, returning the new term.
+fn reduce(term: &Term, strategy: Strategy) -> Option<(Term, Contraction)> {
+    let here = || {
+        contract(term)
+            .map(|(rule, contractum)| (contractum.clone(), (rule, term.clone(), contractum)))
+    };
+    let inside = || {
+        let children = subterms(term);
+        positions(term, strategy).iter().find_map(|&i| {
+            let (reduced, contraction) = reduce(children[i], strategy)?;
+            Some((replace(term, i, reduced), contraction))
+        })
+    };
+    match strategy {
+        // Values first, then the redex they make up
+        Strategy::CallByValue => inside().or_else(here),
+        Strategy::NormalOrder | Strategy::CallByName => here().or_else(inside),
+    }
+}
 
-        // Discard reduction: discard !v in e → e
-        Term::Discard(discarded, body) => {
-            if matches!(discarded.as_ref(), Term::Promote(_)) {
-                Some(body.as_ref().clone())
-            } else if let Some(discarded_reduced) = step(discarded) {
-                Some(Term::Discard(Box::new(discarded_reduced), body.clone()))
-            } else if let Some(body_reduced) = step(body) {
-                Some(Term::Discard(discarded.clone(), Box::new(body_reduced)))
-            } else {
-                None
+/// Contract `term` itself if it is a redex.
+fn contract(term: &Term) -> Option<(Reduction, Term)> {
+    match term {
+        Term::App(f, arg) => match f.as_ref() {
+            Term::Abs(x, body) => Some((Reduction::Beta, body.substitute(x, arg))),
+            _ => None,
+        },
+        Term::LetPair(x, y, pair, body) => match pair.as_ref() {
+            Term::Pair(a, b) => Some((Reduction::LetPair, body.substitute_many(&[(x, a), (y, b)]))),
+            _ => None,
+        },
+        Term::Case(scrut, x, left, y, right) => match scrut.as_ref() {
+            Term::Inl(v) => Some((Reduction::Case, left.substitute(x, v))),
+            Term::Inr(v) => Some((Reduction::Case, right.substitute(y, v))),
+            _ => None,
+        },
+        Term::Fst(pair) => match pair.as_ref() {
+            Term::Pair(a, _) => Some((Reduction::Fst, a.as_ref().clone())),
+            _ => None,
+        },
+        Term::Snd(pair) => match pair.as_ref() {
+            Term::Pair(_, b) => Some((Reduction::Snd, b.as_ref().clone())),
+            _ => None,
+        },
+        Term::Derelict(e) => match e.as_ref() {
+            Term::Promote(v) => Some((Reduction::Derelict, v.as_ref().clone())),
+            _ => None,
+        },
+        // The free variables of a promotion are themselves of type `!A`, so
+        // they are copied and discarded along with it
+        Term::Copy(src, x, y, body) => match src.as_ref() {
+            Term::Promote(v) => Some((Reduction::Copy, copy_promotion(v, x, y, body))),
+            _ => None,
+        },
+        Term::Discard(discarded, body) => match discarded.as_ref() {
+            Term::Promote(v) => {
+                let contractum = sorted_free_vars(v)
+                    .into_iter()
+                    .rev()
+                    .fold(body.as_ref().clone(), |e, z| {
+                        Term::Discard(Box::new(Term::Var(z)), Box::new(e))
+                    });
+                Some((Reduction::Discard, contractum))
             }
-        }
-
-        // Reduce inside abstractions
-        Term::Abs(x, body) => {
-            step(body).map(|reduced| Term::Abs(x.clone(), Box::new(reduced)))
-        }
+            Term::Unit => Some((Reduction::Discard, body.as_ref().clone())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
 
-        // Reduce inside pairs
-        Term::Pair(a, b) => {
-            if let Some(a_reduced) = step(a) {
-                Some(Term::Pair(Box::new(a_reduced), b.clone()))
-            } else {
-                step(b).map(|b_reduced| Term::Pair(a.clone(), Box::new(b_reduced)))
-            }
-        }
+/// The contractum of `copy !v as (x, y) in body`: each free variable `z` of
+/// `v` is copied into two fresh variables, one for each copy of `!v`.
+fn copy_promotion(v: &Term, x: &str, y: &str, body: &Term) -> Term {
+    let shared = sorted_free_vars(v);
+    let mut avoid = body.free_vars();
+    avoid.extend(shared.iter().cloned());
+
+    let mut copy = || {
+        let names: Vec<String> = shared
+            .iter()
+            .map(|z| {
+                let name = fresh_var(z, &avoid);
+                avoid.insert(name.clone());
+                name
+            })
+            .collect();
+        let vars: Vec<Term> = names.iter().cloned().map(Term::Var).collect();
+        let renaming: Vec<(&str, &Term)> = shared.iter().map(String::as_str).zip(&vars).collect();
+        (names, Term::Promote(Box::new(v.substitute_many(&renaming))))
+    };
+    let (lefts, left) = copy();
+    let (rights, right) = copy();
+    let copied = body.substitute_many(&[(x, &left), (y, &right)]);
+
+    shared
+        .into_iter()
+        .zip(lefts.into_iter().zip(rights))
+        .rev()
+        .fold(copied, |e, (z, (left, right))| {
+            Term::Copy(Box::new(Term::Var(z)), left, right, Box::new(e))
+        })
+}
 
-        // Reduce inside injections
-        Term::Inl(e) => step(e).map(|reduced| Term::Inl(Box::new(reduced))),
-        Term::Inr(e) => step(e).map(|reduced| Term::Inr(Box::new(reduced))),
+/// The free variables of `term`, sorted so that contracta do not depend on
+/// hashing.
+fn sorted_free_vars(term: &Term) -> Vec<String> {
+    let mut vars: Vec<String> = term.free_vars().into_iter().collect();
+    vars.sort();
+    vars
+}
 
-        // Reduce inside promote
-        Term::Promote(e) => step(e).map(|reduced| Term::Promote(Box::new(reduced))),
+/// The indices into [`subterms`] that `strategy` reduces inside `term`, in
+/// the order it tries them.
+fn positions(term: &Term, strategy: Strategy) -> &'static [usize] {
+    match (strategy, term) {
+        (_, Term::Var(_) | Term::Unit | Term::Trivial) => &[],
+        (Strategy::NormalOrder, Term::Case(..)) => &[0, 1, 2],
+        (
+            Strategy::NormalOrder,
+            Term::Pair(..) | Term::App(..) | Term::LetPair(..) | Term::Copy(..) | Term::Discard(..),
+        ) => &[0, 1],
+        (Strategy::NormalOrder, _) => &[0],
+        (Strategy::CallByValue, Term::App(..) | Term::Pair(..)) => &[0, 1],
+        (Strategy::CallByValue, Term::Inl(_) | Term::Inr(_)) => &[0],
+        // The term an eliminator takes apart
+        (
+            _,
+            Term::App(..)
+            | Term::LetPair(..)
+            | Term::Case(..)
+            | Term::Fst(_)
+            | Term::Snd(_)
+            | Term::Derelict(_)
+            | Term::Copy(..)
+            | Term::Discard(..),
+        ) => &[0],
+        _ => &[],
+    }
+}
 
-        // Abort and values are already normal
-        Term::Var(_) | Term::Unit | Term::Trivial | Term::Abort(_) => None,
+/// The immediate subterms of `term`, eliminated terms before bodies.
+fn subterms(term: &Term) -> Vec<&Term> {
+    match term {
+        Term::Var(_) | Term::Unit | Term::Trivial => vec![],
+        Term::Pair(a, b) | Term::App(a, b) | Term::Discard(a, b) => vec![a, b],
+        Term::LetPair(_, _, e, body) | Term::Copy(e, _, _, body) => vec![e, body],
+        Term::Case(scrut, _, left, _, right) => vec![scrut, left, right],
+        Term::Abs(_, e)
+        | Term::Inl(e)
+        | Term::Inr(e)
+        | Term::Fst(e)
+        | Term::Snd(e)
+        | Term::Abort(e)
+        | Term::Promote(e)
+        | Term::Derelict(e) => vec![e],
     }
 }
 
-/// Fully normalize a term to its normal form.
+/// `term` with its `i`th subterm, as numbered by [`subterms`], replaced.
+fn replace(term: &Term, i: usize, new: Term) -> Term {
+    let mut term = term.clone();
+    let slot = match &mut term {
+        Term::Pair(a, b) | Term::App(a, b) | Term::Discard(a, b) => [a, b].into_iter().nth(i),
+        Term::LetPair(_, _, e, body) | Term::Copy(e, _, _, body) => [e, body].into_iter().nth(i),
+        Term::Case(scrut, _, left, _, right) => [scrut, left, right].into_iter().nth(i),
+        Term::Abs(_, e)
+        | Term::Inl(e)
+        | Term::Inr(e)
+        | Term::Fst(e)
+        | Term::Snd(e)
+        | Term::Abort(e)
+        | Term::Promote(e)
+        | Term::Derelict(e) => [e].into_iter().nth(i),
+        Term::Var(_) | Term::Unit | Term::Trivial => None,
+    };
+    **slot.expect("subterm index in range") = new;
+    term
+}
+
+/// Fully normalize a term to its normal form, in normal order.
 ///
 /// Since linear lambda calculus is strongly normalizing, this always terminates.
 ///
@@ -195,13 +349,64 @@ pub fn step(term: &Term) -> Option<Term> {
 /// assert_eq!(normal, Term::Unit);
 /// ```
 pub fn normalize(term: &Term) -> Term {
+    normalize_with(term, Strategy::NormalOrder)
+}
+
+/// Reduce a term under `strategy` until it finds no more redexes.
+///
+/// Under [`Strategy::NormalOrder`] this is the normal form; the other
+/// strategies may stop earlier, such as at a λ with a redex in its body.
+pub fn normalize_with(term: &Term, strategy: Strategy) -> Term {
     let mut current = term.clone();
-    while let Some(reduced) = step(&current) {
+    while let Some(reduced) = step_with(&current, strategy) {
         current = reduced;
     }
     current
 }
 
+/// Reduce a term under `strategy`, recording each step.
+///
+/// # Example
+///
+/// ```
+/// use lolli_extract::{trace, Reduction, Strategy, Term};
+///
+/// // fst ((λx. x) (), ⟨⟩) projects first under call-by-name, but reduces
+/// // the pair's components first under call-by-value
+/// let id = Term::Abs("x".to_string(), Box::new(Term::Var("x".to_string())));
+/// let pair = Term::Pair(
+///     Box::new(Term::App(Box::new(id), Box::new(Term::Unit))),
+///     Box::new(Term::Trivial),
+/// );
+/// let t = Term::Fst(Box::new(pair));
+///
+/// let by_name = trace(&t, Strategy::CallByName);
+/// let rules: Vec<_> = by_name.steps.iter().map(|s| s.rule).collect();
+/// assert_eq!(rules, [Reduction::Fst, Reduction::Beta]);
+///
+/// let by_value = trace(&t, Strategy::CallByValue);
+/// let rules: Vec<_> = by_value.steps.iter().map(|s| s.rule).collect();
+/// assert_eq!(rules, [Reduction::Beta, Reduction::Fst]);
+/// assert_eq!(by_value.result(), &Term::Unit);
+/// ```
+pub fn trace(term: &Term, strategy: Strategy) -> Trace {
+    let mut steps = Vec::new();
+    let mut current = term.clone();
+    while let Some((reduced, (rule, redex, contractum))) = reduce(&current, strategy) {
+        steps.push(ReductionStep {
+            rule,
+            redex,
+            contractum,
+            term: reduced.clone(),
+        });
+        current = reduced;
+    }
+    Trace {
+        start: term.clone(),
+        steps,
+    }
+}
+
 /// Normalize with a maximum step count to prevent infinite loops.
 ///
 /// Returns the term after at most `max_steps` reduction steps.
@@ -353,6 +558,222 @@ mod tests {
         assert_eq!(result, Term::Trivial);
     }
 
+    fn var(x: &str) -> Term {
+        Term::Var(x.to_string())
+    }
+
+    #[test]
+    fn test_beta_avoids_capture() {
+        // (λx. λy. (x, y)) y → λy0. (y, y0), not λy. (y, y)
+        let t = Term::App(
+            Box::new(Term::Abs(
+                "x".to_string(),
+                Box::new(Term::Abs(
+                    "y".to_string(),
+                    Box::new(Term::Pair(Box::new(var("x")), Box::new(var("y")))),
+                )),
+            )),
+            Box::new(var("y")),
+        );
+
+        let result = normalize(&t);
+        let expected = Term::Abs(
+            "z".to_string(),
+            Box::new(Term::Pair(Box::new(var("y")), Box::new(var("z")))),
+        );
+        assert!(result.alpha_eq(&expected), "{}", result.pretty());
+    }
+
+    #[test]
+    fn test_let_pair_substitutes_simultaneously() {
+        // let (x, y) = (y, x) in (x, y) → (y, x)
+        let t = Term::LetPair(
+            "x".to_string(),
+            "y".to_string(),
+            Box::new(Term::Pair(Box::new(var("y")), Box::new(var("x")))),
+            Box::new(Term::Pair(Box::new(var("x")), Box::new(var("y")))),
+        );
+
+        let result = normalize(&t);
+        assert_eq!(result, Term::Pair(Box::new(var("y")), Box::new(var("x"))));
+    }
+
+    fn id(x: &str) -> Term {
+        Term::Abs(x.to_string(), Box::new(var(x)))
+    }
+
+    fn app(f: Term, a: Term) -> Term {
+        Term::App(Box::new(f), Box::new(a))
+    }
+
+    fn rules(t: &Term, strategy: Strategy) -> Vec<Reduction> {
+        trace(t, strategy).steps.iter().map(|s| s.rule).collect()
+    }
+
+    #[test]
+    fn test_strategies_order_redexes() {
+        // (λx. x) ((λy. y) a): the argument is reduced first only by value
+        let t = app(id("x"), app(id("y"), var("a")));
+        let by_name = trace(&t, Strategy::CallByName);
+        assert_eq!(by_name.steps[0].redex, t);
+        assert_eq!(by_name.result(), &var("a"));
+
+        let by_value = trace(&t, Strategy::CallByValue);
+        assert_eq!(by_value.steps[0].redex, app(id("y"), var("a")));
+        assert_eq!(by_value.steps[0].contractum, var("a"));
+        assert_eq!(by_value.steps[0].term, app(id("x"), var("a")));
+        assert_eq!(by_value.result(), &var("a"));
+
+        assert_eq!(trace(&t, Strategy::NormalOrder), by_name);
+    }
+
+    #[test]
+    fn test_weak_strategies_stop_at_values() {
+        // λz. (λx. x) z only reduces under the binder in normal order
+        let t = Term::Abs("z".to_string(), Box::new(app(id("x"), var("z"))));
+        assert_eq!(normalize_with(&t, Strategy::NormalOrder), id("z"));
+        assert_eq!(normalize_with(&t, Strategy::CallByName), t);
+        assert_eq!(normalize_with(&t, Strategy::CallByValue), t);
+
+        // ((λx. x) a, b) is a value for call-by-name only
+        let pair = Term::Pair(Box::new(app(id("x"), var("a"))), Box::new(var("b")));
+        assert!(rules(&pair, Strategy::CallByName).is_empty());
+        assert_eq!(rules(&pair, Strategy::CallByValue), [Reduction::Beta]);
+
+        // Promotions are never evaluated early by the weak strategies
+        let promoted = Term::Promote(Box::new(app(id("x"), var("a"))));
+        assert!(rules(&promoted, Strategy::CallByValue).is_empty());
+        assert_eq!(rules(&promoted, Strategy::NormalOrder), [Reduction::Beta]);
+    }
+
+    #[test]
+    fn test_normal_order_reduces_everywhere() {
+        // Case branches and copy bodies are reduced even when stuck on a variable
+        let case = Term::Case(
+            Box::new(var("s")),
+            "x".to_string(),
+            Box::new(app(id("y"), var("x"))),
+            "z".to_string(),
+            Box::new(Term::Snd(Box::new(Term::Pair(
+                Box::new(Term::Unit),
+                Box::new(var("z")),
+            )))),
+        );
+        assert_eq!(
+            rules(&case, Strategy::NormalOrder),
+            [Reduction::Beta, Reduction::Snd]
+        );
+        assert!(rules(&case, Strategy::CallByName).is_empty());
+
+        let copy = Term::Copy(
+            Box::new(var("c")),
+            "x".to_string(),
+            "y".to_string(),
+            Box::new(Term::Discard(
+                Box::new(var("x")),
+                Box::new(Term::Derelict(Box::new(Term::Promote(Box::new(var("y")))))),
+            )),
+        );
+        assert_eq!(rules(&copy, Strategy::NormalOrder), [Reduction::Derelict]);
+    }
+
+    #[test]
+    fn test_trace_exponentials() {
+        // copy !() as (x, y) in discard x in derelict y
+        let t = Term::Copy(
+            Box::new(Term::Promote(Box::new(Term::Unit))),
+            "x".to_string(),
+            "y".to_string(),
+            Box::new(Term::Discard(
+                Box::new(var("x")),
+                Box::new(Term::Derelict(Box::new(var("y")))),
+            )),
+        );
+        for strategy in Strategy::ALL {
+            let trace = trace(&t, strategy);
+            let rules: Vec<_> = trace.steps.iter().map(|s| s.rule).collect();
+            assert_eq!(
+                rules,
+                [Reduction::Copy, Reduction::Discard, Reduction::Derelict],
+                "{}",
+                strategy.name()
+            );
+            assert_eq!(trace.result(), &Term::Unit);
+            assert_eq!(trace.start, t);
+        }
+        assert_eq!(
+            Strategy::from_name("call-by-value"),
+            Some(Strategy::CallByValue)
+        );
+        assert_eq!(Strategy::from_name("lazy"), None);
+        assert_eq!(Reduction::Beta.to_string(), "β");
+    }
+
+    #[test]
+    fn test_exponentials_keep_free_variables() {
+        let parse = |t: &str| lolli_parse::parse_term(t).unwrap();
+
+        // The variables of a discarded promotion are discarded in turn
+        let t = parse("discard !(derelict a, derelict b) in c");
+        assert_eq!(normalize(&t), parse("discard a in discard b in c"));
+        assert_eq!(normalize(&parse("discard () in c")), var("c"));
+
+        // and those of a copied promotion are copied
+        let t = parse("copy !(derelict f) as (g, h) in derelict g (derelict h x)");
+        let copied = parse("copy f as (f0, f1) in derelict f0 (derelict f1 x)");
+        assert!(normalize(&t).alpha_eq(&copied));
+    }
+
+    #[test]
+    fn test_steps_preserve_types() {
+        let cases = [
+            (&[("z", "!A"), ("y", "B")][..], "discard !z in y", "B"),
+            (&[("y", "B")], "discard () in y", "B"),
+            (&[("y", "B")], r"(\u. discard u in y) ()", "B"),
+            (
+                &[("z", "!A")],
+                "copy !z as (x, y) in (derelict x, derelict y)",
+                "!A * !A",
+            ),
+            (
+                &[("f", "!(A -o A)"), ("a", "A")],
+                "copy !(derelict f) as (g, h) in derelict g (derelict h a)",
+                "A",
+            ),
+            (
+                &[("z", "!A"), ("w", "!B")],
+                "copy !(derelict z, derelict w) as (p, q) in \
+                 let (a, b) = derelict p in discard q in (b, a)",
+                "B * A",
+            ),
+            (
+                &[],
+                r"(\x. copy x as (a, b) in (derelict a, derelict b)) !()",
+                "1 * 1",
+            ),
+        ];
+        for (context, term, ty) in cases {
+            let context: Vec<(String, lolli_core::Formula)> = context
+                .iter()
+                .map(|(x, ty)| (x.to_string(), lolli_parse::parse_formula(ty).unwrap()))
+                .collect();
+            let term = lolli_parse::parse_term(term).unwrap();
+            let ty = lolli_parse::parse_formula(ty).unwrap();
+            lolli_core::type_check(&context, &term, &ty).unwrap();
+            for strategy in Strategy::ALL {
+                for step in trace(&term, strategy).steps {
+                    assert_eq!(
+                        lolli_core::type_check(&context, &step.term, &ty),
+                        Ok(()),
+                        "{} under {}",
+                        step.term.pretty(),
+                        strategy.name()
+                    );
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_is_normal() {
         assert!(is_normal(&Term::Unit));
@@ -0,0 +1,365 @@
+//! Spec file parsing.
+//!
+//! A spec file collects atom declarations, named definitions and `prove`
+//! obligations in one document:
+//!
+//! ```text
+//! // Connection pool
+//! atom Pool
+//! atom Available
+//! atom InUse
+//! def Acquire = Pool * Available -o Pool * InUse
+//!
+//! prove Pool, Available, Acquire |- Pool * InUse
+//! ```
+//!
+//! Definitions are expanded as the file is read, so every [`Obligation`]
+//! holds a plain sequent that can be handed straight to the prover. A file
+//! that declares atoms must declare every atom it uses; one without atom
+//! declarations may use any.
+
+use std::collections::HashMap;
+
+use lolli_core::{Formula, TwoSidedSequent};
+use pest::iterators::Pair;
+use pest::Parser;
+
+use crate::{build_formula, build_sequent, LolliParser, ParseError, Rule};
+
+/// A location in the source text (1-based).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    /// Line number
+    pub line: usize,
+    /// Column number
+    pub column: usize,
+}
+
+impl Position {
+    fn of(pair: &Pair<Rule>) -> Self {
+        let (line, column) = pair.line_col();
+        Position { line, column }
+    }
+}
+
+/// A named definition: `def Name = formula`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Definition {
+    /// Name of the definition
+    pub name: String,
+    /// The defined formula, with earlier definitions expanded
+    pub formula: Formula,
+    /// Where the definition appears
+    pub position: Position,
+}
+
+/// A proof obligation: `prove Γ |- Δ`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Obligation {
+    /// The sequent to prove, with definitions expanded
+    pub sequent: TwoSidedSequent,
+    /// The sequent as written in the file
+    pub source: String,
+    /// Where the obligation appears
+    pub position: Position,
+}
+
+/// A parsed spec file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpecFile {
+    /// Declared atoms, in order of declaration
+    pub atoms: Vec<String>,
+    /// Named definitions, in order of declaration
+    pub definitions: Vec<Definition>,
+    /// Proof obligations, in order of appearance
+    pub obligations: Vec<Obligation>,
+}
+
+impl SpecFile {
+    /// Look up a definition by name.
+    pub fn definition(&self, name: &str) -> Option<&Definition> {
+        self.definitions.iter().find(|d| d.name == name)
+    }
+}
+
+/// Parse a spec file.
+///
+/// # Examples
+///
+/// ```
+/// use lolli_parse::parse_file;
+///
+/// let spec = parse_file(
+///     "atom A\n\
+///      atom B\n\
+///      def Swap = A * B -o B * A\n\
+///      prove |- Swap",
+/// )
+/// .unwrap();
+///
+/// assert_eq!(spec.atoms, vec!["A", "B"]);
+/// assert_eq!(spec.obligations.len(), 1);
+/// assert_eq!(spec.obligations[0].position.line, 4);
+/// ```
+///
+/// # Errors
+///
+/// Returns a `ParseError` if the input is not a valid spec file, if a name
+/// is declared twice, or if the file declares atoms and uses one it does
+/// not declare.
+pub fn parse_file(input: &str) -> Result<SpecFile, ParseError> {
+    let file = LolliParser::parse(Rule::file, input)?
+        .next()
+        .ok_or(ParseError::EmptyInput)?;
+
+    let mut spec = SpecFile::default();
+    let mut defs: HashMap<String, Formula> = HashMap::new();
+    // Atoms used by each definition and obligation, with where they appear
+    let mut used: Vec<(String, Position)> = Vec::new();
+
+    for decl in file.into_inner() {
+        let decl = match decl.as_rule() {
+            Rule::declaration => decl.into_inner().next().ok_or(ParseError::EmptyInput)?,
+            Rule::EOI => continue,
+            _ => decl,
+        };
+        let position = Position::of(&decl);
+
+        match decl.as_rule() {
+            Rule::atom_decl => {
+                let name = single_ident(decl)?;
+                check_fresh(&spec, &name, position)?;
+                spec.atoms.push(name);
+            }
+            Rule::def_decl => {
+                let mut inner = decl.into_inner();
+                let name = inner.next().ok_or(ParseError::EmptyInput)?.as_str().to_string();
+                check_fresh(&spec, &name, position)?;
+                let body = inner.next().ok_or(ParseError::EmptyInput)?;
+                let formula = expand(&build_formula(body)?, &defs);
+                used.extend(atoms(&formula).into_iter().map(|a| (a, position)));
+                defs.insert(name.clone(), formula.clone());
+                spec.definitions.push(Definition {
+                    name,
+                    formula,
+                    position,
+                });
+            }
+            Rule::sequent_decl => {
+                let sequent = decl.into_inner().next().ok_or(ParseError::EmptyInput)?;
+                let source = sequent.as_str().trim().to_string();
+                let parsed = build_sequent(sequent)?;
+                let sequent = TwoSidedSequent::new(
+                    parsed.antecedent.iter().map(|f| expand(f, &defs)).collect(),
+                    parsed.succedent.iter().map(|f| expand(f, &defs)).collect(),
+                );
+                for formula in sequent.antecedent.iter().chain(&sequent.succedent) {
+                    used.extend(atoms(formula).into_iter().map(|a| (a, position)));
+                }
+                spec.obligations.push(Obligation {
+                    sequent,
+                    source,
+                    position,
+                });
+            }
+            other => return Err(ParseError::UnexpectedRule(format!("{:?}", other))),
+        }
+    }
+
+    if !spec.atoms.is_empty() {
+        if let Some((name, position)) = used.into_iter().find(|(a, _)| !spec.atoms.contains(a)) {
+            return Err(ParseError::UndeclaredAtom {
+                name,
+                line: position.line,
+            });
+        }
+    }
+
+    Ok(spec)
+}
+
+fn single_ident(decl: Pair<Rule>) -> Result<String, ParseError> {
+    decl.into_inner()
+        .next()
+        .map(|p| p.as_str().to_string())
+        .ok_or(ParseError::EmptyInput)
+}
+
+fn check_fresh(spec: &SpecFile, name: &str, position: Position) -> Result<(), ParseError> {
+    if spec.atoms.iter().any(|a| a == name) || spec.definition(name).is_some() {
+        return Err(ParseError::DuplicateDeclaration {
+            name: name.to_string(),
+            line: position.line,
+        });
+    }
+    Ok(())
+}
+
+/// The atoms of `formula`, in order of first occurrence.
+fn atoms(formula: &Formula) -> Vec<String> {
+    fn go(formula: &Formula, out: &mut Vec<String>) {
+        match formula {
+            Formula::Atom(name) | Formula::NegAtom(name) => {
+                if !out.contains(name) {
+                    out.push(name.clone());
+                }
+            }
+            Formula::Tensor(a, b)
+            | Formula::Par(a, b)
+            | Formula::With(a, b)
+            | Formula::Plus(a, b)
+            | Formula::Lolli(a, b) => {
+                go(a, out);
+                go(b, out);
+            }
+            Formula::OfCourse(a) | Formula::WhyNot(a) => go(a, out),
+            Formula::One | Formula::Bottom | Formula::Top | Formula::Zero => {}
+        }
+    }
+    let mut out = Vec::new();
+    go(formula, &mut out);
+    out
+}
+
+/// Replace every defined name in `formula` by its definition.
+fn expand(formula: &Formula, defs: &HashMap<String, Formula>) -> Formula {
+    let go = |f: &Formula| Box::new(expand(f, defs));
+    match formula {
+        Formula::Atom(name) => defs.get(name).cloned().unwrap_or_else(|| formula.clone()),
+        Formula::NegAtom(name) => defs
+            .get(name)
+            .map(Formula::negate)
+            .unwrap_or_else(|| formula.clone()),
+        Formula::Tensor(a, b) => Formula::Tensor(go(a), go(b)),
+        Formula::Par(a, b) => Formula::Par(go(a), go(b)),
+        Formula::With(a, b) => Formula::With(go(a), go(b)),
+        Formula::Plus(a, b) => Formula::Plus(go(a), go(b)),
+        Formula::Lolli(a, b) => Formula::Lolli(go(a), go(b)),
+        Formula::OfCourse(a) => Formula::OfCourse(go(a)),
+        Formula::WhyNot(a) => Formula::WhyNot(go(a)),
+        Formula::One | Formula::Bottom | Formula::Top | Formula::Zero => formula.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_empty_file() {
+        let spec = parse_file("").unwrap();
+        assert!(spec.atoms.is_empty());
+        assert!(spec.definitions.is_empty());
+        assert!(spec.obligations.is_empty());
+    }
+
+    #[test]
+    fn test_parse_declarations() {
+        let spec = parse_file(
+            "// pool spec\n\
+             atom Pool\n\
+             atom Available\n\
+             prove Pool, Available |- Pool * Available\n\
+             prove |- Pool -o Pool",
+        )
+        .unwrap();
+
+        assert_eq!(spec.atoms, vec!["Pool", "Available"]);
+        assert_eq!(spec.obligations.len(), 2);
+        assert_eq!(spec.obligations[0].source, "Pool, Available |- Pool * Available");
+        assert_eq!(spec.obligations[0].position, Position { line: 4, column: 1 });
+        assert_eq!(spec.obligations[1].position.line, 5);
+        assert_eq!(spec.obligations[1].sequent.antecedent.len(), 0);
+    }
+
+    #[test]
+    fn test_definitions_are_expanded() {
+        let spec = parse_file(
+            "def Pair = A * B\n\
+             def Swap = Pair -o B * A\n\
+             prove Pair |- B * A\n\
+             prove |- Swap",
+        )
+        .unwrap();
+
+        let pair = Formula::tensor(Formula::atom("A"), Formula::atom("B"));
+        assert_eq!(spec.definition("Pair").unwrap().formula, pair);
+        assert_eq!(
+            spec.definition("Swap").unwrap().formula,
+            Formula::lolli(
+                pair.clone(),
+                Formula::tensor(Formula::atom("B"), Formula::atom("A"))
+            )
+        );
+        assert_eq!(spec.obligations[0].sequent.antecedent, vec![pair]);
+        assert_eq!(
+            spec.obligations[1].sequent.succedent[0],
+            spec.definition("Swap").unwrap().formula
+        );
+    }
+
+    #[test]
+    fn test_negated_definition() {
+        let spec = parse_file("def P = A * B\nprove |- P^, A * B").unwrap();
+        assert_eq!(
+            spec.obligations[0].sequent.succedent[0],
+            Formula::par(Formula::neg_atom("A"), Formula::neg_atom("B"))
+        );
+    }
+
+    #[test]
+    fn test_duplicate_declaration() {
+        let err = parse_file("atom A\ndef A = B").unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::DuplicateDeclaration { ref name, line: 2 } if name == "A"
+        ));
+
+        assert!(parse_file("def X = A\ndef X = B").is_err());
+    }
+
+    #[test]
+    fn test_undeclared_atom() {
+        let err = parse_file("atom A\nprove A |- A\nprove A |- B").unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::UndeclaredAtom { ref name, line: 3 } if name == "B"
+        ));
+        assert!(parse_file("atom A\ndef F = A * C").is_err());
+
+        // Definitions are not atoms, and a file without atom declarations
+        // is not checked
+        assert!(parse_file("atom A\ndef F = A * A\nprove F |- A * A").is_ok());
+        assert!(parse_file("prove A |- A").is_ok());
+    }
+
+    #[test]
+    fn test_keywords_start_declarations() {
+        // An empty succedent must not swallow the next declaration
+        let spec = parse_file("prove A |-\nprove A |- A").unwrap();
+        assert_eq!(spec.obligations.len(), 2);
+        assert!(spec.obligations[0].sequent.succedent.is_empty());
+
+        // Anywhere else the keywords are ordinary atoms
+        let spec = parse_file(
+            "atom atom\n\
+             atom def\n\
+             atom prove\n\
+             prove atom, def |- def, atom\n\
+             prove prove |- prove",
+        )
+        .unwrap();
+        assert_eq!(spec.atoms, vec!["atom", "def", "prove"]);
+        assert_eq!(spec.obligations.len(), 2);
+        assert_eq!(
+            spec.obligations[1].sequent.succedent,
+            vec![Formula::atom("prove")]
+        );
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        assert!(parse_file("prove A |- A *").is_err());
+        assert!(parse_file("atom").is_err());
+    }
+}
@@ -2,7 +2,10 @@
 //!
 //! Parser for the Lolli linear logic workbench.
 //!
-//! This crate provides parsing functionality for linear logic formulas and sequents.
+//! This crate provides parsing functionality for linear logic formulas and sequents,
+//! for spec files that bundle declarations with `prove` obligations
+//! (see [`parse_file`]), for proof scripts that write out a proof
+//! rule by rule (see [`parse_proof`]), and for λ-terms (see [`parse_term`]).
 //!
 //! ## Supported Syntax
 //!
@@ -34,13 +37,30 @@
 #![warn(clippy::all)]
 
 use pest::Parser;
-use pest_derive::Parser;
 
 pub use lolli_core::{Formula, Sequent, TwoSidedSequent};
 
-#[derive(Parser)]
-#[grammar = "grammar.pest"]
-struct LolliParser;
+mod file;
+mod script;
+mod term;
+
+pub use file::{parse_file, Definition, Obligation, Position, SpecFile};
+pub use script::{parse_proof, ProofScript, SCRIPT_RULES};
+pub use term::parse_term;
+pub use grammar::Rule;
+
+use grammar::LolliParser;
+
+mod grammar {
+    // The derive generates an undocumented public `Rule` enum.
+    #![allow(missing_docs)]
+
+    use pest_derive::Parser;
+
+    #[derive(Parser)]
+    #[grammar = "grammar.pest"]
+    pub struct LolliParser;
+}
 
 /// Parse error type.
 #[derive(Debug, thiserror::Error)]
@@ -59,11 +79,46 @@ pub enum ParseError {
 
     /// Pest parsing error
     #[error("Parse error: {0}")]
-    PestError(#[from] pest::error::Error<Rule>),
+    PestError(Box<pest::error::Error<Rule>>),
 
     /// Empty input
     #[error("Empty input")]
     EmptyInput,
+
+    /// A name declared more than once in a spec file
+    #[error("Duplicate declaration of `{name}` at line {line}")]
+    DuplicateDeclaration {
+        /// The name being redeclared
+        name: String,
+        /// Line of the second declaration
+        line: usize,
+    },
+
+    /// An atom a spec file uses without declaring it
+    #[error("Undeclared atom `{name}` at line {line}")]
+    UndeclaredAtom {
+        /// The undeclared atom
+        name: String,
+        /// Line of the first declaration that uses it
+        line: usize,
+    },
+
+    /// A malformed proof script
+    #[error("Line {line}, column {column}: {message}")]
+    Script {
+        /// Line of the error
+        line: usize,
+        /// Column of the error
+        column: usize,
+        /// What is wrong
+        message: String,
+    },
+}
+
+impl From<pest::error::Error<Rule>> for ParseError {
+    fn from(err: pest::error::Error<Rule>) -> Self {
+        ParseError::PestError(Box::new(err))
+    }
 }
 
 /// Parse a formula from a string.
@@ -173,7 +228,7 @@ where
     let first = inner.next().ok_or(ParseError::EmptyInput)?;
     let mut result = build_formula(first)?;
 
-    while let Some(next) = inner.next() {
+    for next in inner {
         // Skip operator tokens
         if next.as_rule() == _op_rule {
             continue;
@@ -0,0 +1,364 @@
+//! Proof script parsing.
+//!
+//! A proof script writes a proof as a tree of `rule: sequent` lines, the
+//! conclusion first and each premise indented below the step it belongs to:
+//!
+//! ```text
+//! // Tensor is commutative
+//! par: |- (A * B) -o B * A
+//!   par: A * B |- B * A
+//!     tensor: A, B |- B * A
+//!       axiom: B |- B
+//!       axiom: A |- A
+//! ```
+//!
+//! Sequents may be two-sided; they are read as the one-sided sequent
+//! ⊢ Γ⊥, Δ. A cut names its cut formula, as in `cut A * B: Γ |- Δ`.
+//! The exponential rules are the textbook ones on `?` formulas:
+//! `promotion`, `dereliction`, `weakening` and `contraction`. Script
+//! sequents carry no focus, so the focusing rules have no script names.
+
+use lolli_core::{Proof, ProofPath, Rule, Sequent};
+use pest::error::LineColLocation;
+use pest::iterators::Pair;
+use pest::Parser;
+
+use crate::{build_formula, build_sequent, LolliParser, ParseError, Position, Rule as PestRule};
+
+/// A proof read from a proof script.
+#[derive(Clone, Debug)]
+pub struct ProofScript {
+    /// The proof
+    pub proof: Proof,
+    /// Where each step of the proof is written, from the root down
+    pub positions: Vec<(ProofPath, Position)>,
+}
+
+impl ProofScript {
+    /// Where the step at `path` is written.
+    pub fn position(&self, path: &ProofPath) -> Option<Position> {
+        self.positions
+            .iter()
+            .find(|(p, _)| p == path)
+            .map(|&(_, position)| position)
+    }
+}
+
+/// The rule names a proof script accepts, with the rule each one stands for.
+/// `cut` is followed by the cut formula and is not listed.
+pub const SCRIPT_RULES: [(&str, Rule); 14] = [
+    ("axiom", Rule::Axiom),
+    ("one", Rule::OneIntro),
+    ("bottom", Rule::BottomIntro),
+    ("tensor", Rule::TensorIntro),
+    ("par", Rule::ParIntro),
+    ("top", Rule::TopIntro),
+    ("with", Rule::WithIntro),
+    ("plus_left", Rule::PlusIntroLeft),
+    ("plus_right", Rule::PlusIntroRight),
+    ("promotion", Rule::OfCourseIntro),
+    ("why_not", Rule::WhyNotIntro),
+    ("weakening", Rule::Weakening),
+    ("contraction", Rule::Contraction),
+    ("dereliction", Rule::Dereliction),
+];
+
+/// One `rule: sequent` line.
+struct Step {
+    indent: usize,
+    position: Position,
+    rule: Rule,
+    conclusion: Sequent,
+}
+
+/// Parse a proof script.
+///
+/// # Examples
+///
+/// ```
+/// use lolli_parse::parse_proof;
+/// use lolli_core::Rule;
+///
+/// let script = parse_proof(
+///     "with: A & B |- B & A\n\
+///      \x20 plus_right: A & B |- B\n\
+///      \x20   axiom: B |- B\n\
+///      \x20 plus_left: A & B |- A\n\
+///      \x20   axiom: A |- A",
+/// )
+/// .unwrap();
+///
+/// assert_eq!(script.proof.rule, Rule::WithIntro);
+/// assert_eq!(script.proof.premises.len(), 2);
+/// ```
+///
+/// # Errors
+///
+/// Returns a `ParseError` if a line is not a valid step or the
+/// indentation does not form a single tree.
+pub fn parse_proof(input: &str) -> Result<ProofScript, ParseError> {
+    let mut steps = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let text = line.split("//").next().unwrap_or_default();
+        if text.trim().is_empty() {
+            continue;
+        }
+        let indent = text.len() - text.trim_start().len();
+        steps.push(parse_step(text, i + 1, indent)?);
+    }
+    if steps.is_empty() {
+        return Err(ParseError::EmptyInput);
+    }
+
+    let mut positions = Vec::new();
+    let mut next = 0;
+    let proof = build(&steps, &mut next, ProofPath::root(), &mut positions)?;
+    if let Some(extra) = steps.get(next) {
+        return Err(script_error(
+            extra.position,
+            "a second conclusion; premises must be indented below their step",
+        ));
+    }
+
+    Ok(ProofScript { proof, positions })
+}
+
+fn parse_step(text: &str, line: usize, indent: usize) -> Result<Step, ParseError> {
+    let position = Position {
+        line,
+        column: indent + 1,
+    };
+    let Some((name, sequent)) = text.split_once(':') else {
+        return Err(script_error(position, "expected `rule: sequent`"));
+    };
+
+    let name = name.trim();
+    let rule = match name.split_once(char::is_whitespace) {
+        Some(("cut", formula)) => {
+            let column = column_of(text, formula);
+            let formula = parse_piece(PestRule::formula, formula, line, column)?;
+            Rule::Cut(build_formula(formula)?)
+        }
+        None if name == "cut" => {
+            return Err(script_error(position, "`cut` needs its cut formula"));
+        }
+        _ => SCRIPT_RULES
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, rule)| rule.clone())
+            .ok_or_else(|| script_error(position, &format!("unknown rule `{}`", name)))?,
+    };
+
+    let column = column_of(text, sequent);
+    let sequent = parse_piece(PestRule::sequent, sequent, line, column)?;
+
+    Ok(Step {
+        indent,
+        position,
+        rule,
+        conclusion: build_sequent(sequent)?.to_one_sided(),
+    })
+}
+
+/// The 1-based column at which the trimmed `piece` starts within `text`.
+fn column_of(text: &str, piece: &str) -> usize {
+    let start = piece.as_ptr() as usize - text.as_ptr() as usize;
+    let start = start + piece.len() - piece.trim_start().len();
+    text[..start].chars().count() + 1
+}
+
+/// Parse all of `piece` as `rule`, reporting errors at their place in the script.
+fn parse_piece(
+    rule: PestRule,
+    piece: &str,
+    line: usize,
+    column: usize,
+) -> Result<Pair<'_, PestRule>, ParseError> {
+    let piece = piece.trim();
+    let what = match rule {
+        PestRule::formula => "cut formula",
+        _ => "sequent",
+    };
+    let pair = LolliParser::parse(rule, piece)
+        .map_err(|e| {
+            let offset = match e.line_col {
+                LineColLocation::Pos((_, c)) | LineColLocation::Span((_, c), _) => c - 1,
+            };
+            let position = Position {
+                line,
+                column: column + offset,
+            };
+            script_error(
+                position,
+                &format!("invalid {}: {}", what, e.variant.message()),
+            )
+        })?
+        .next()
+        .ok_or(ParseError::EmptyInput)?;
+
+    let end = pair.as_span().end();
+    if end < piece.len() {
+        let position = Position {
+            line,
+            column: column + piece[..end].chars().count(),
+        };
+        return Err(script_error(
+            position,
+            &format!("unexpected `{}` after the {}", piece[end..].trim(), what),
+        ));
+    }
+    Ok(pair)
+}
+
+/// Build the step at `next` and, recursively, the steps indented below it.
+fn build(
+    steps: &[Step],
+    next: &mut usize,
+    path: ProofPath,
+    positions: &mut Vec<(ProofPath, Position)>,
+) -> Result<Proof, ParseError> {
+    let step = &steps[*next];
+    *next += 1;
+    positions.push((path.clone(), step.position));
+
+    let mut premises = Vec::new();
+    if let Some(indent) = steps.get(*next).map(|s| s.indent) {
+        while let Some(premise) = steps.get(*next) {
+            if premise.indent <= step.indent {
+                break;
+            }
+            if premise.indent != indent {
+                return Err(script_error(
+                    premise.position,
+                    "indentation does not match the premises above",
+                ));
+            }
+            premises.push(build(steps, next, path.child(premises.len()), positions)?);
+        }
+    }
+
+    Ok(Proof {
+        conclusion: step.conclusion.clone(),
+        rule: step.rule.clone(),
+        premises,
+    })
+}
+
+fn script_error(position: Position, message: &str) -> ParseError {
+    ParseError::Script {
+        line: position.line,
+        column: position.column,
+        message: message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lolli_core::Formula;
+
+    const SWAP: &str = "\
+// Tensor is commutative
+par: |- (A * B) -o B * A
+  par: A * B |- B * A
+
+    tensor: A, B |- B * A
+      axiom: B |- B   // right premise first
+      axiom: A |- A
+";
+
+    fn error_at(input: &str) -> (usize, usize, String) {
+        match parse_proof(input) {
+            Err(ParseError::Script {
+                line,
+                column,
+                message,
+            }) => (line, column, message),
+            other => panic!("expected a script error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_tree() {
+        let script = parse_proof(SWAP).unwrap();
+        let proof = &script.proof;
+        assert_eq!(proof.rule, Rule::ParIntro);
+        assert_eq!(proof.depth(), 4);
+
+        let tensor = &proof.premises[0].premises[0];
+        assert_eq!(tensor.rule, Rule::TensorIntro);
+        assert_eq!(tensor.premises.len(), 2);
+        assert_eq!(
+            tensor.conclusion,
+            Sequent::new(vec![
+                Formula::neg_atom("A"),
+                Formula::neg_atom("B"),
+                Formula::tensor(Formula::atom("B"), Formula::atom("A")),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_positions() {
+        let script = parse_proof(SWAP).unwrap();
+        assert_eq!(script.positions.len(), 5);
+        assert_eq!(
+            script.position(&ProofPath::root()),
+            Some(Position { line: 2, column: 1 })
+        );
+        let axiom = ProofPath::root().child(0).child(0).child(1);
+        assert_eq!(
+            script.position(&axiom),
+            Some(Position { line: 7, column: 7 })
+        );
+        assert_eq!(script.position(&ProofPath::root().child(1)), None);
+    }
+
+    #[test]
+    fn test_cut() {
+        let script = parse_proof("cut A: |- A\n  axiom: A |- A\n  axiom: |- A^, A").unwrap();
+        assert_eq!(script.proof.rule, Rule::Cut(Formula::atom("A")));
+
+        let (line, _, message) = error_at("cut: |- A");
+        assert_eq!(line, 1);
+        assert!(message.contains("cut formula"));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(
+            parse_proof("// nothing\n\n"),
+            Err(ParseError::EmptyInput)
+        ));
+
+        let (line, column, message) = error_at("axiom: A |- A\n  foo: A |- A");
+        assert_eq!((line, column), (2, 3));
+        assert!(message.contains("unknown rule `foo`"));
+        let (_, _, message) = error_at("blur: A |- A");
+        assert!(message.contains("unknown rule `blur`"));
+
+        let (_, _, message) = error_at("axiom A |- A");
+        assert!(message.contains("rule: sequent"));
+
+        // The sequent is cut short at `)`, reported where it stops
+        let (line, column, message) = error_at("top: |- A, Top)");
+        assert_eq!((line, column), (1, 15));
+        assert!(message.contains("unexpected `)`"));
+
+        let (line, column, message) = error_at("one: |- 1\n  top: |- *");
+        assert_eq!(line, 2);
+        assert_eq!(column, 11);
+        assert!(message.contains("invalid sequent"));
+    }
+
+    #[test]
+    fn test_indentation() {
+        let (line, _, message) = error_at("with: |- A & B\n    top: |- A\n  top: |- B");
+        assert_eq!(line, 3);
+        assert!(message.contains("indentation"));
+
+        let (line, _, message) = error_at("one: |- 1\none: |- 1");
+        assert_eq!(line, 2);
+        assert!(message.contains("second conclusion"));
+    }
+}
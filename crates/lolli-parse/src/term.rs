@@ -0,0 +1,297 @@
+//! Term parsing.
+//!
+//! Terms are written the way [`Term::pretty`] prints them:
+//!
+//! ```text
+//! λp. let (a, b) = p in (b, a)
+//! ```
+//!
+//! `λx. e` may also be written `\x. e`, and `⟨⟩` may be written `<>`.
+//! Abstractions, `let`, `copy` and `discard` extend as far right as
+//! possible, and application is juxtaposition, so `f x y` is `(f x) y`.
+//! The prefix forms `inl`, `inr`, `fst`, `snd`, `absurd`, `derelict` and
+//! `!` bind tighter than application, so `inl f x` is `(inl f) x`.
+
+use lolli_core::Term;
+use pest::iterators::Pair;
+use pest::Parser;
+
+use crate::{LolliParser, ParseError, Rule};
+
+/// Parse a term from a string.
+///
+/// # Example
+///
+/// ```
+/// use lolli_parse::parse_term;
+///
+/// let swap = parse_term(r"\p. let (a, b) = p in (b, a)").unwrap();
+/// assert_eq!(swap.pretty(), "λp. let (a, b) = p in (b, a)");
+/// ```
+///
+/// # Errors
+///
+/// Returns a `ParseError` if the input is not a valid term.
+pub fn parse_term(input: &str) -> Result<Term, ParseError> {
+    let mut pairs = LolliParser::parse(Rule::term_input, input)?;
+    let input = pairs.next().ok_or(ParseError::EmptyInput)?;
+    let term = input.into_inner().next().ok_or(ParseError::EmptyInput)?;
+    build_term(term)
+}
+
+fn build_term(pair: Pair<Rule>) -> Result<Term, ParseError> {
+    match pair.as_rule() {
+        Rule::term | Rule::term_atom => {
+            let inner = pair.into_inner().next().ok_or(ParseError::EmptyInput)?;
+            build_term(inner)
+        }
+        Rule::term_var => Ok(Term::Var(pair.as_str().to_string())),
+        Rule::unit => Ok(Term::Unit),
+        Rule::trivial => Ok(Term::Trivial),
+        Rule::abs => {
+            let mut inner = pair.into_inner();
+            let x = next_var(&mut inner)?;
+            let body = next_term(&mut inner)?;
+            Ok(Term::Abs(x, Box::new(body)))
+        }
+        Rule::let_pair => {
+            let mut inner = pair.into_inner();
+            let x = next_var(&mut inner)?;
+            let y = next_var(&mut inner)?;
+            let bound = next_term(&mut inner)?;
+            let body = next_term(&mut inner)?;
+            Ok(Term::LetPair(x, y, Box::new(bound), Box::new(body)))
+        }
+        Rule::copy => {
+            let mut inner = pair.into_inner();
+            let source = next_term(&mut inner)?;
+            let x = next_var(&mut inner)?;
+            let y = next_var(&mut inner)?;
+            let body = next_term(&mut inner)?;
+            Ok(Term::Copy(Box::new(source), x, y, Box::new(body)))
+        }
+        Rule::discard => {
+            let mut inner = pair.into_inner();
+            let discarded = next_term(&mut inner)?;
+            let body = next_term(&mut inner)?;
+            Ok(Term::Discard(Box::new(discarded), Box::new(body)))
+        }
+        Rule::case => {
+            let mut inner = pair.into_inner();
+            let scrutinee = next_term(&mut inner)?;
+            let x = next_var(&mut inner)?;
+            let left = next_term(&mut inner)?;
+            let y = next_var(&mut inner)?;
+            let right = next_term(&mut inner)?;
+            Ok(Term::Case(
+                Box::new(scrutinee),
+                x,
+                Box::new(left),
+                y,
+                Box::new(right),
+            ))
+        }
+        Rule::prefixed => {
+            let mut inner = pair.into_inner();
+            let op = next_var(&mut inner)?;
+            let e = Box::new(next_term(&mut inner)?);
+            match op.as_str() {
+                "inl" => Ok(Term::Inl(e)),
+                "inr" => Ok(Term::Inr(e)),
+                "fst" => Ok(Term::Fst(e)),
+                "snd" => Ok(Term::Snd(e)),
+                "absurd" => Ok(Term::Abort(e)),
+                "derelict" => Ok(Term::Derelict(e)),
+                "!" => Ok(Term::Promote(e)),
+                op => Err(ParseError::UnknownOperator(op.to_string())),
+            }
+        }
+        Rule::pair => {
+            let mut inner = pair.into_inner();
+            let first = next_term(&mut inner)?;
+            let second = next_term(&mut inner)?;
+            Ok(Term::Pair(Box::new(first), Box::new(second)))
+        }
+        Rule::app => {
+            let mut inner = pair.into_inner();
+            let mut term = next_term(&mut inner)?;
+            for arg in inner {
+                term = Term::App(Box::new(term), Box::new(build_term(arg)?));
+            }
+            Ok(term)
+        }
+        rule => Err(ParseError::UnexpectedRule(format!("{:?}", rule))),
+    }
+}
+
+fn next_var(pairs: &mut pest::iterators::Pairs<Rule>) -> Result<String, ParseError> {
+    let pair = pairs.next().ok_or(ParseError::EmptyInput)?;
+    Ok(pair.as_str().to_string())
+}
+
+fn next_term(pairs: &mut pest::iterators::Pairs<Rule>) -> Result<Term, ParseError> {
+    build_term(pairs.next().ok_or(ParseError::EmptyInput)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var(x: &str) -> Box<Term> {
+        Box::new(Term::Var(x.to_string()))
+    }
+
+    #[test]
+    fn test_parse_abstraction() {
+        let expected = Term::Abs("x".to_string(), var("x"));
+        assert_eq!(parse_term("λx. x").unwrap(), expected);
+        assert_eq!(parse_term(r"\x. x").unwrap(), expected);
+    }
+
+    #[test]
+    fn test_parse_application() {
+        // Application associates to the left
+        let expected = Term::App(Box::new(Term::App(var("f"), var("x"))), var("y"));
+        assert_eq!(parse_term("f x y").unwrap(), expected);
+        assert_eq!(parse_term("((f x) y)").unwrap(), expected);
+
+        let nested = Term::App(var("f"), Box::new(Term::App(var("g"), var("x"))));
+        assert_eq!(parse_term("f (g x)").unwrap(), nested);
+    }
+
+    #[test]
+    fn test_parse_pairs() {
+        assert_eq!(parse_term("()").unwrap(), Term::Unit);
+        assert_eq!(
+            parse_term("(x, ())").unwrap(),
+            Term::Pair(var("x"), Box::new(Term::Unit))
+        );
+
+        let swap = parse_term("λp. let (a, b) = p in (b, a)").unwrap();
+        assert_eq!(
+            swap,
+            Term::Abs(
+                "p".to_string(),
+                Box::new(Term::LetPair(
+                    "a".to_string(),
+                    "b".to_string(),
+                    var("p"),
+                    Box::new(Term::Pair(var("b"), var("a"))),
+                )),
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_additives() {
+        let case = parse_term("case s of { inl a => inr a | inr b => inl b }").unwrap();
+        assert_eq!(
+            case,
+            Term::Case(
+                var("s"),
+                "a".to_string(),
+                Box::new(Term::Inr(var("a"))),
+                "b".to_string(),
+                Box::new(Term::Inl(var("b"))),
+            )
+        );
+        assert_eq!(parse_term("<>").unwrap(), Term::Trivial);
+        assert_eq!(parse_term("fst p").unwrap(), Term::Fst(var("p")));
+        assert_eq!(parse_term("absurd z").unwrap(), Term::Abort(var("z")));
+
+        // Prefix forms bind tighter than application
+        assert_eq!(
+            parse_term("snd f x").unwrap(),
+            Term::App(Box::new(Term::Snd(var("f"))), var("x"))
+        );
+        assert_eq!(
+            parse_term("inl inr x").unwrap(),
+            Term::Inl(Box::new(Term::Inr(var("x"))))
+        );
+    }
+
+    #[test]
+    fn test_parse_exponentials() {
+        let dup = parse_term("λx. copy x as (a, b) in (derelict a, derelict b)").unwrap();
+        assert_eq!(
+            dup,
+            Term::Abs(
+                "x".to_string(),
+                Box::new(Term::Copy(
+                    var("x"),
+                    "a".to_string(),
+                    "b".to_string(),
+                    Box::new(Term::Pair(
+                        Box::new(Term::Derelict(var("a"))),
+                        Box::new(Term::Derelict(var("b"))),
+                    )),
+                )),
+            )
+        );
+        assert_eq!(
+            parse_term("discard x in ()").unwrap(),
+            Term::Discard(var("x"), Box::new(Term::Unit))
+        );
+        assert_eq!(
+            parse_term("!(f x)").unwrap(),
+            Term::Promote(Box::new(Term::App(var("f"), var("x"))))
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let abs = |x: &str, body: Term| Term::Abs(x.to_string(), Box::new(body));
+        let terms = [
+            Term::Trivial,
+            abs("x", Term::Inl(var("x"))),
+            // Binders as operands need parentheses
+            Term::App(Box::new(abs("x", Term::Var("x".to_string()))), var("y")),
+            Term::App(var("f"), Box::new(abs("x", Term::Var("x".to_string())))),
+            Term::Inr(Box::new(abs("x", Term::Var("x".to_string())))),
+            Term::Promote(Box::new(Term::Discard(var("x"), Box::new(Term::Unit)))),
+            Term::App(
+                Box::new(Term::Derelict(var("f"))),
+                Box::new(Term::Derelict(var("x"))),
+            ),
+            Term::LetPair(
+                "a".to_string(),
+                "b".to_string(),
+                Box::new(Term::LetPair(
+                    "c".to_string(),
+                    "d".to_string(),
+                    var("p"),
+                    var("c"),
+                )),
+                Box::new(Term::Pair(var("a"), var("b"))),
+            ),
+            Term::Case(
+                Box::new(Term::Fst(var("p"))),
+                "x".to_string(),
+                Box::new(Term::Copy(
+                    var("x"),
+                    "y".to_string(),
+                    "z".to_string(),
+                    Box::new(Term::Pair(var("y"), var("z"))),
+                )),
+                "w".to_string(),
+                Box::new(Term::Abort(var("w"))),
+            ),
+            Term::Snd(Box::new(Term::Pair(var("a"), Box::new(Term::Trivial)))),
+        ];
+        for term in terms {
+            let printed = term.pretty();
+            assert_eq!(parse_term(&printed).unwrap(), term, "{}", printed);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_term("").is_err());
+        assert!(parse_term("λ. x").is_err());
+        assert!(parse_term("let (x, y) = p").is_err());
+        assert!(parse_term("(x, y").is_err());
+        assert!(parse_term("case s of { inl a => a }").is_err());
+        assert!(parse_term("copy x as (a, b)").is_err());
+        assert!(parse_term("λin. in").is_err());
+    }
+}
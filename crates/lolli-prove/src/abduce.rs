@@ -0,0 +1,307 @@
+//! Resource abduction.
+//!
+//! [`Prover::abduce`] turns a failed proof into a fix: it looks for the
+//! fewest formulas to add to either side of a sequent so that it becomes
+//! provable. When the candidates are atoms, the balance check settles most
+//! of the answer up front, since every unbalanced atom has to be added on
+//! the side that lacks it; only what else is needed is searched for.
+//!
+//! Atoms fix missing resources, not leftover ones: without the mix rule,
+//! `A ⊢ A, B` stays unprovable after adding `B` to the left. A leftover `B`
+//! needs a consumer such as `B ⊸ 1` among the candidates.
+
+use crate::balance::check_balance;
+use crate::explain::{counted, grouped};
+use crate::{ProofOutcome, Prover, UnknownReason};
+use lolli_core::{Formula, Proof, TwoSidedSequent};
+
+/// Formulas whose addition makes a sequent provable.
+#[derive(Debug, Clone)]
+pub struct Abduction {
+    /// Formulas to add to the antecedent
+    pub left: Vec<Formula>,
+    /// Formulas to add to the succedent
+    pub right: Vec<Formula>,
+    /// A proof of the extended sequent
+    pub proof: Proof,
+}
+
+impl Abduction {
+    /// The number of formulas added.
+    pub fn len(&self) -> usize {
+        self.left.len() + self.right.len()
+    }
+
+    /// Returns true if nothing had to be added.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Extend `seq` with the additions.
+    pub fn apply(&self, seq: &TwoSidedSequent) -> TwoSidedSequent {
+        let mut extended = seq.clone();
+        extended.antecedent.extend(self.left.iter().cloned());
+        extended.succedent.extend(self.right.iter().cloned());
+        extended
+    }
+
+    /// Describe the additions, e.g. "add Available to the left".
+    pub fn suggestions(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (formulas, side) in [(&self.left, "left"), (&self.right, "right")] {
+            for (formula, count) in grouped(formulas) {
+                let what = if count == 1 {
+                    formula.pretty()
+                } else {
+                    counted(&formula, count)
+                };
+                lines.push(format!("add {} to the {}", what, side));
+            }
+        }
+        lines
+    }
+}
+
+impl Prover {
+    /// Find a smallest multiset of `candidates` to add to `seq` that makes it
+    /// provable.
+    ///
+    /// Each candidate may be added to either side, any number of times, up
+    /// to `max_additions` formulas in total. An empty `candidates` list
+    /// stands for the atoms of `seq`. Returns `None` if no such addition
+    /// yields a proof within `max_depth`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lolli_prove::Prover;
+    /// use lolli_core::{Formula, TwoSidedSequent};
+    ///
+    /// // Pool ⊢ Pool ⊗ Available
+    /// let seq = TwoSidedSequent::new(
+    ///     vec![Formula::atom("Pool")],
+    ///     vec![Formula::tensor(Formula::atom("Pool"), Formula::atom("Available"))],
+    /// );
+    /// let fix = Prover::new(100).abduce(&seq, &[], 4).unwrap();
+    /// assert_eq!(fix.suggestions(), vec!["add Available to the left"]);
+    /// ```
+    pub fn abduce(
+        &mut self,
+        seq: &TwoSidedSequent,
+        candidates: &[Formula],
+        max_additions: usize,
+    ) -> Option<Abduction> {
+        let candidates = if candidates.is_empty() {
+            atoms_of(seq)
+        } else {
+            candidates.to_vec()
+        };
+
+        // An unbalanced atom must be added on the side that lacks it, as
+        // often as it is lacking, unless other formulas can make up for it
+        let mut forced_left = Vec::new();
+        let mut forced_right = Vec::new();
+        let only_atoms = candidates.iter().all(|f| matches!(f, Formula::Atom(_)));
+        if only_atoms {
+            if let Err(unbalanced) = check_balance(&seq.to_one_sided()) {
+                for imbalance in unbalanced {
+                    let atom = Formula::atom(imbalance.atom);
+                    if !candidates.contains(&atom) {
+                        return None;
+                    }
+                    let (side, count) = if imbalance.positive > imbalance.negative {
+                        (&mut forced_left, imbalance.positive - imbalance.negative)
+                    } else {
+                        (&mut forced_right, imbalance.negative - imbalance.positive)
+                    };
+                    side.extend(std::iter::repeat_n(atom, count));
+                }
+            }
+        }
+        // Each option adds one candidate to one side
+        let options: Vec<(bool, Formula)> = candidates
+            .iter()
+            .flat_map(|f| [(true, f.clone()), (false, f.clone())])
+            .collect();
+
+        let forced: Vec<(bool, Formula)> = forced_left
+            .into_iter()
+            .map(|f| (true, f))
+            .chain(forced_right.into_iter().map(|f| (false, f)))
+            .collect();
+        let (additions, proof) =
+            self.smallest_extension(forced, &options, max_additions, |additions| {
+                let mut extended = seq.clone();
+                for (left, formula) in additions {
+                    let side = if *left {
+                        &mut extended.antecedent
+                    } else {
+                        &mut extended.succedent
+                    };
+                    side.push(formula.clone());
+                }
+                extended
+            })?;
+
+        let (left, right): (Vec<_>, Vec<_>) = additions.into_iter().partition(|(left, _)| *left);
+        Some(Abduction {
+            left: left.into_iter().map(|(_, f)| f).collect(),
+            right: right.into_iter().map(|(_, f)| f).collect(),
+            proof,
+        })
+    }
+
+    /// Find the smallest multiset of `options` that, on top of `forced` and
+    /// with at most `max` items in total, makes `build` return a provable
+    /// sequent. Returns the items with the proof, or `None` if there are
+    /// none or a [`crate::SearchLimits`] bound or cancellation stops a search.
+    pub(crate) fn smallest_extension<T: Clone>(
+        &mut self,
+        forced: Vec<T>,
+        options: &[T],
+        max: usize,
+        mut build: impl FnMut(&[T]) -> TwoSidedSequent,
+    ) -> Option<(Vec<T>, Proof)> {
+        if forced.len() > max {
+            return None;
+        }
+
+        for extra in 0..=max - forced.len() {
+            if options.is_empty() && extra > 0 {
+                break;
+            }
+
+            let mut choice = vec![0; extra];
+            loop {
+                let mut items = forced.clone();
+                items.extend(choice.iter().map(|&i| options[i].clone()));
+                match self.decide_two_sided(&build(&items)) {
+                    ProofOutcome::Provable(proof) => return Some((items, proof)),
+                    // Running into a search limit ends the whole search
+                    ProofOutcome::Unknown(
+                        UnknownReason::SequentLimit
+                        | UnknownReason::Timeout
+                        | UnknownReason::CacheLimit
+                        | UnknownReason::Cancelled,
+                    ) => return None,
+                    _ => {}
+                }
+
+                // Next multiset: choices are kept in non-decreasing order
+                let Some(pos) = choice.iter().rposition(|&i| i + 1 < options.len()) else {
+                    break;
+                };
+                let next = choice[pos] + 1;
+                for i in &mut choice[pos..] {
+                    *i = next;
+                }
+            }
+        }
+        None
+    }
+}
+
+/// The distinct atoms of `seq`, in order of first occurrence.
+fn atoms_of(seq: &TwoSidedSequent) -> Vec<Formula> {
+    fn collect(formula: &Formula, atoms: &mut Vec<Formula>) {
+        match formula {
+            Formula::Atom(name) | Formula::NegAtom(name) => {
+                let atom = Formula::atom(name.clone());
+                if !atoms.contains(&atom) {
+                    atoms.push(atom);
+                }
+            }
+            Formula::Tensor(a, b)
+            | Formula::Par(a, b)
+            | Formula::With(a, b)
+            | Formula::Plus(a, b)
+            | Formula::Lolli(a, b) => {
+                collect(a, atoms);
+                collect(b, atoms);
+            }
+            Formula::OfCourse(a) | Formula::WhyNot(a) => collect(a, atoms),
+            _ => {}
+        }
+    }
+
+    let mut atoms = Vec::new();
+    for formula in seq.antecedent.iter().chain(&seq.succedent) {
+        collect(formula, &mut atoms);
+    }
+    atoms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atom(name: &str) -> Formula {
+        Formula::atom(name)
+    }
+
+    #[test]
+    fn test_abduce_leftover() {
+        // Pool, Available, Available ⊢ Pool ⊗ Available: no atom can take
+        // up the extra Available, but a consumer can
+        let seq = TwoSidedSequent::new(
+            vec![atom("Pool"), atom("Available"), atom("Available")],
+            vec![Formula::tensor(atom("Pool"), atom("Available"))],
+        );
+        assert!(Prover::new(100).abduce(&seq, &[], 3).is_none());
+
+        let release = Formula::lolli(atom("Available"), Formula::One);
+        let fix = Prover::new(100)
+            .abduce(&seq, std::slice::from_ref(&release), 3)
+            .unwrap();
+        assert_eq!(fix.left, vec![release]);
+        assert!(fix.right.is_empty());
+    }
+
+    #[test]
+    fn test_abduce_provable_needs_nothing() {
+        let seq = TwoSidedSequent::new(vec![atom("A")], vec![atom("A")]);
+        let fix = Prover::new(100).abduce(&seq, &[], 3).unwrap();
+        assert!(fix.is_empty());
+    }
+
+    #[test]
+    fn test_abduce_beyond_balance() {
+        // A & B ⊢ A ⊗ B is balanced, but needs a second resource
+        let seq = TwoSidedSequent::new(
+            vec![Formula::with(atom("A"), atom("B"))],
+            vec![Formula::tensor(atom("A"), atom("B"))],
+        );
+        let fix = Prover::new(100).abduce(&seq, &[], 3).unwrap();
+        assert_eq!(fix.len(), 1);
+        assert!(Prover::new(100)
+            .decide_two_sided(&fix.apply(&seq))
+            .is_provable());
+    }
+
+    #[test]
+    fn test_abduce_from_candidates() {
+        // Request ⊢ Response, fixed by a handler rather than by atoms
+        let seq = TwoSidedSequent::new(vec![atom("Request")], vec![atom("Response")]);
+        let handler = Formula::lolli(atom("Request"), atom("Response"));
+        let fix = Prover::new(100)
+            .abduce(&seq, std::slice::from_ref(&handler), 2)
+            .unwrap();
+        assert_eq!(fix.left, vec![handler]);
+        assert!(fix.right.is_empty());
+    }
+
+    #[test]
+    fn test_abduce_respects_limit() {
+        // Pool ⊢ Pool ⊗ InUse ⊗ InUse ⊗ InUse lacks three InUse
+        let seq = TwoSidedSequent::new(
+            vec![atom("Pool")],
+            vec![Formula::tensor(
+                Formula::tensor(atom("Pool"), atom("InUse")),
+                Formula::tensor(atom("InUse"), atom("InUse")),
+            )],
+        );
+        assert!(Prover::new(100).abduce(&seq, &[], 2).is_none());
+        let fix = Prover::new(100).abduce(&seq, &[], 3).unwrap();
+        assert_eq!(fix.suggestions(), vec!["add three InUse to the left"]);
+    }
+}
@@ -0,0 +1,365 @@
+//! Atom-count balance check.
+//!
+//! Every axiom closes one `A` against one `A⊥`, and the multiplicative rules
+//! neither copy nor discard formulas. So in a provable sequent without `⊤`,
+//! an atom that only occurs in the multiplicative part of the sequent occurs
+//! as often positively as negatively. Atoms under an additive or an
+//! exponential, or in the unrestricted zone, may end up used any number of
+//! times and are left out of the count.
+//!
+//! Counting is linear in the size of the sequent, so the prover runs it
+//! before search and at each tensor split to reject hopeless sequents.
+
+use lolli_core::{Formula, Sequent};
+use std::collections::{BTreeMap, HashSet};
+
+/// An atom whose positive and negative occurrences cannot be paired up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Imbalance {
+    /// The atom name
+    pub atom: String,
+    /// Occurrences of `A`
+    pub positive: usize,
+    /// Occurrences of `A⊥`
+    pub negative: usize,
+}
+
+impl std::fmt::Display for Imbalance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} positive, {} negative",
+            self.atom, self.positive, self.negative
+        )
+    }
+}
+
+/// Check the atom-count invariant of a one-sided sequent.
+///
+/// Returns the unbalanced atoms, sorted by name. An `Err` means the sequent
+/// has no proof; `Ok` means nothing, since balance is necessary but not
+/// sufficient.
+///
+/// # Example
+///
+/// ```
+/// use lolli_prove::check_balance;
+/// use lolli_core::{Formula, TwoSidedSequent};
+///
+/// // A, A ⊢ A leaves an A over
+/// let seq = TwoSidedSequent::new(
+///     vec![Formula::atom("A"), Formula::atom("A")],
+///     vec![Formula::atom("A")],
+/// );
+/// let unbalanced = check_balance(&seq.to_one_sided()).unwrap_err();
+/// assert_eq!(unbalanced[0].to_string(), "A: 1 positive, 2 negative");
+/// ```
+pub fn check_balance(seq: &Sequent) -> Result<(), Vec<Imbalance>> {
+    let tally = Tally::of_sequent(seq);
+    if tally.top {
+        return Ok(());
+    }
+
+    let unbalanced: Vec<Imbalance> = tally
+        .counts
+        .into_iter()
+        .filter(|(atom, (pos, neg))| pos != neg && !tally.flexible.contains_key(atom))
+        .map(|(atom, (positive, negative))| Imbalance {
+            atom,
+            positive,
+            negative,
+        })
+        .collect();
+
+    if unbalanced.is_empty() {
+        Ok(())
+    } else {
+        Err(unbalanced)
+    }
+}
+
+/// Returns false if no choice of `pool` formulas added to `seq` can balance it.
+///
+/// This is the check for the resource-passing search, where a premise
+/// consumes some unknown part of its pool. Each pool formula may or may not
+/// be used, so an atom passes if its count in `seq` can be cancelled by
+/// some selection of the pool.
+pub(crate) fn can_balance(seq: &Sequent, pool: &[Formula]) -> bool {
+    let tally = Tally::of_sequent(seq);
+    let mut flexible = tally.flexible;
+    let mut items = Vec::with_capacity(pool.len());
+    for formula in pool {
+        let mut item = Tally::default();
+        item.add(formula, true, true);
+        if item.top {
+            return true;
+        }
+        flexible.extend(item.flexible);
+        items.push(item.counts);
+    }
+    if tally.top {
+        return true;
+    }
+
+    // Every atom of the pool must be looked at, not just those of `seq`
+    let atoms: HashSet<&String> = tally
+        .counts
+        .keys()
+        .chain(items.iter().flat_map(|c| c.keys()))
+        .collect();
+    atoms
+        .into_iter()
+        .filter(|atom| !flexible.contains_key(*atom))
+        .all(|atom| {
+            let net = |counts: &BTreeMap<String, (usize, usize)>| {
+                counts
+                    .get(atom)
+                    .map_or(0, |&(pos, neg)| pos as i64 - neg as i64)
+            };
+            let base = net(&tally.counts);
+            let low: i64 = items.iter().map(|c| net(c).min(0)).sum();
+            let high: i64 = items.iter().map(|c| net(c).max(0)).sum();
+            base + low <= 0 && 0 <= base + high
+        })
+}
+
+/// The atoms of `seq` that the balance check leaves out, with the number of
+/// their occurrences, sorted by name.
+pub(crate) fn flexible_atoms(seq: &Sequent) -> Vec<(String, usize)> {
+    Tally::of_sequent(seq).flexible.into_iter().collect()
+}
+
+/// Returns false if some atom occurrence that an axiom must close has too
+/// few possible partners.
+///
+/// Unlike [`check_balance`], this still says something when the unrestricted
+/// zone is not empty: an `A` in the multiplicative part of the linear zone
+/// needs an `A⊥` somewhere in the linear zone, or any number of them if
+/// `A⊥` occurs under an exponential.
+pub(crate) fn can_link(seq: &Sequent) -> bool {
+    let mut links = Links::default();
+    for formula in seq.linear.iter().chain(&seq.focus) {
+        links.add(formula, true, true, true);
+    }
+    for formula in &seq.unrestricted {
+        links.add(formula, true, false, false);
+    }
+    if links.top {
+        return true;
+    }
+
+    links.required.iter().all(|((atom, positive), &count)| {
+        let partner = (atom.clone(), !positive);
+        links.unbounded.contains(&partner)
+            || links.available.get(&partner).is_some_and(|&n| n >= count)
+    })
+}
+
+/// Atom occurrences of each polarity, for [`can_link`].
+#[derive(Default)]
+struct Links {
+    /// Occurrences that must be closed by an axiom
+    required: BTreeMap<(String, bool), usize>,
+    /// Occurrences that may be used once
+    available: BTreeMap<(String, bool), usize>,
+    /// Occurrences under an exponential, which may be used any number of times
+    unbounded: HashSet<(String, bool)>,
+    /// Whether `⊤` occurs anywhere
+    top: bool,
+}
+
+impl Links {
+    fn add(&mut self, formula: &Formula, positive: bool, required: bool, bounded: bool) {
+        match formula {
+            Formula::Atom(name) | Formula::NegAtom(name) => {
+                let key = (
+                    name.clone(),
+                    positive == matches!(formula, Formula::Atom(_)),
+                );
+                if !bounded {
+                    self.unbounded.insert(key);
+                    return;
+                }
+                if required {
+                    *self.required.entry(key.clone()).or_default() += 1;
+                }
+                *self.available.entry(key).or_default() += 1;
+            }
+            Formula::Tensor(a, b) | Formula::Par(a, b) => {
+                self.add(a, positive, required, bounded);
+                self.add(b, positive, required, bounded);
+            }
+            Formula::Lolli(a, b) => {
+                self.add(a, !positive, required, bounded);
+                self.add(b, positive, required, bounded);
+            }
+            Formula::With(a, b) | Formula::Plus(a, b) => {
+                self.add(a, positive, false, bounded);
+                self.add(b, positive, false, bounded);
+            }
+            Formula::OfCourse(a) | Formula::WhyNot(a) => self.add(a, positive, false, false),
+            Formula::Top | Formula::Zero => {
+                if positive == matches!(formula, Formula::Top) {
+                    self.top = true;
+                }
+            }
+            Formula::One | Formula::Bottom => {}
+        }
+    }
+}
+
+/// Atom occurrences in the multiplicative part of some formulas.
+#[derive(Default)]
+struct Tally {
+    /// Positive and negative occurrences per atom
+    counts: BTreeMap<String, (usize, usize)>,
+    /// Occurrences of atoms under an additive or exponential
+    flexible: BTreeMap<String, usize>,
+    /// Whether `⊤` occurs anywhere
+    top: bool,
+}
+
+impl Tally {
+    fn of_sequent(seq: &Sequent) -> Self {
+        let mut tally = Tally::default();
+        for formula in seq.linear.iter().chain(&seq.focus) {
+            tally.add(formula, true, true);
+        }
+        for formula in &seq.unrestricted {
+            tally.add(formula, true, false);
+        }
+        tally
+    }
+
+    /// Count `formula`, negated if `positive` is false. Occurrences below a
+    /// non-multiplicative connective are `flexible` rather than counted.
+    fn add(&mut self, formula: &Formula, positive: bool, counted: bool) {
+        match formula {
+            Formula::Atom(name) | Formula::NegAtom(name) => {
+                if !counted {
+                    *self.flexible.entry(name.clone()).or_default() += 1;
+                    return;
+                }
+                let entry = self.counts.entry(name.clone()).or_default();
+                if positive == matches!(formula, Formula::Atom(_)) {
+                    entry.0 += 1;
+                } else {
+                    entry.1 += 1;
+                }
+            }
+            Formula::Tensor(a, b) | Formula::Par(a, b) => {
+                self.add(a, positive, counted);
+                self.add(b, positive, counted);
+            }
+            Formula::Lolli(a, b) => {
+                self.add(a, !positive, counted);
+                self.add(b, positive, counted);
+            }
+            Formula::With(a, b) | Formula::Plus(a, b) => {
+                self.add(a, positive, false);
+                self.add(b, positive, false);
+            }
+            Formula::OfCourse(a) | Formula::WhyNot(a) => self.add(a, positive, false),
+            // A negated 0 is a ⊤
+            Formula::Top | Formula::Zero => {
+                if positive == matches!(formula, Formula::Top) {
+                    self.top = true;
+                }
+            }
+            Formula::One | Formula::Bottom => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lolli_core::TwoSidedSequent;
+
+    fn atom(name: &str) -> Formula {
+        Formula::atom(name)
+    }
+
+    fn balance(antecedent: Vec<Formula>, succedent: Vec<Formula>) -> Result<(), Vec<Imbalance>> {
+        check_balance(&TwoSidedSequent::new(antecedent, succedent).to_one_sided())
+    }
+
+    #[test]
+    fn test_leftover_atom() {
+        // Pool, Available, Available ⊢ Pool ⊗ Available
+        let result = balance(
+            vec![atom("Pool"), atom("Available"), atom("Available")],
+            vec![Formula::tensor(atom("Pool"), atom("Available"))],
+        );
+        assert_eq!(
+            result,
+            Err(vec![Imbalance {
+                atom: "Available".to_string(),
+                positive: 1,
+                negative: 2,
+            }])
+        );
+    }
+
+    #[test]
+    fn test_lolli_flips_polarity() {
+        // A, A ⊸ B ⊢ B
+        let result = balance(
+            vec![atom("A"), Formula::lolli(atom("A"), atom("B"))],
+            vec![atom("B")],
+        );
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn test_additives_and_exponentials_not_counted() {
+        // A & B ⊢ A and !A ⊢ A ⊗ A are balanced as far as counting can tell
+        assert_eq!(
+            balance(vec![Formula::with(atom("A"), atom("B"))], vec![atom("A")]),
+            Ok(())
+        );
+        assert_eq!(
+            balance(
+                vec![Formula::of_course(atom("A"))],
+                vec![Formula::tensor(atom("A"), atom("A"))],
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_top_disables_check() {
+        assert_eq!(
+            balance(vec![atom("A"), atom("A")], vec![Formula::Top]),
+            Ok(())
+        );
+        // 0 on the left is a ⊤ in one-sided form
+        assert_eq!(balance(vec![atom("A"), Formula::Zero], vec![]), Ok(()));
+    }
+
+    #[test]
+    fn test_can_balance_with_pool() {
+        // ⊢ A⊥ needs an A from the pool
+        let seq = Sequent::new(vec![atom("A").negate()]);
+        assert!(can_balance(&seq, &[atom("B"), atom("A")]));
+        assert!(!can_balance(&seq, &[atom("B"), atom("B").negate()]));
+        assert!(!can_balance(&seq, &[]));
+    }
+
+    #[test]
+    fn test_can_link_with_exponentials() {
+        // !A, !B ⊢ A ⊗ B after two derelictions of !A: one A for two A⊥
+        let mut seq = Sequent::new(vec![
+            Formula::tensor(atom("A"), atom("B")),
+            atom("A").negate(),
+            atom("A").negate(),
+        ]);
+        seq.unrestricted = vec![atom("A").negate(), atom("B").negate()];
+        assert!(check_balance(&seq).is_ok());
+        assert!(!can_link(&seq));
+
+        // An A under an exponential can be used any number of times
+        seq.unrestricted.push(Formula::why_not(atom("A")));
+        assert!(can_link(&seq));
+    }
+}
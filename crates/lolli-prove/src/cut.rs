@@ -0,0 +1,902 @@
+//! Cut elimination.
+//!
+//! Proofs are read in the dyadic calculus the provers produce: a sequent
+//! ⊢ Θ ; Γ has an unrestricted zone Θ, a set of formulas that may be used any
+//! number of times, and a linear zone Γ. `WhyNotIntro` moves the body of a
+//! `?A` into Θ, `Dereliction` and `Contraction` copy formulas of Θ into Γ, and
+//! leaves may ignore Θ. Formulas are compared modulo [`Formula::desugar`].
+//!
+//! Cuts are removed from the leaves down. A cut between cut-free premises is
+//! pushed up past the rules that do not introduce the cut formula until it
+//! meets an axiom or a rule introducing the formula on both sides, where it
+//! is replaced by cuts on the subformulas. A cut on `!A` against `?A⊥`
+//! becomes a cut on `A` against the formula in Θ, which is copied once for
+//! every dereliction of it.
+
+use crate::multiset::{same_multiset, without};
+use lolli_core::{Formula, Proof, Rule, Sequent};
+
+/// Error type for cut elimination.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum CutError {
+    /// A step that is not a valid rule application in the dyadic calculus
+    #[error("Invalid rule {rule:?} for conclusion {conclusion}")]
+    InvalidRule {
+        /// The rule that was applied
+        rule: Rule,
+        /// The conclusion of the proof step
+        conclusion: String,
+    },
+
+    /// The step limit was reached
+    #[error("Gave up after {0} reduction steps")]
+    StepLimit(usize),
+}
+
+/// One reduction step of cut elimination.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reduction {
+    /// A cut against an axiom was replaced by the other premise
+    Axiom {
+        /// The cut formula
+        cut: Formula,
+    },
+    /// A cut on a formula introduced on both sides was replaced by cuts on
+    /// its subformulas
+    Principal {
+        /// The cut formula
+        cut: Formula,
+    },
+    /// A cut was moved above the last rule of one premise
+    Commute {
+        /// The cut formula
+        cut: Formula,
+        /// The rule the cut was moved past
+        rule: Rule,
+    },
+    /// A cut on `!A` whose `?A⊥` side never uses `A` was dropped
+    Weakening {
+        /// The cut formula
+        cut: Formula,
+    },
+    /// A cut on `!A` met a dereliction of `A⊥`, which gets its own copy of
+    /// the proof of `!A`
+    Dereliction {
+        /// The cut formula
+        cut: Formula,
+    },
+}
+
+impl std::fmt::Display for Reduction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reduction::Axiom { cut } => write!(f, "axiom: cut on {}", cut.pretty()),
+            Reduction::Principal { cut } => write!(f, "principal: cut on {}", cut.pretty()),
+            Reduction::Commute { cut, rule } => {
+                write!(f, "commute: cut on {} past {:?}", cut.pretty(), rule)
+            }
+            Reduction::Weakening { cut } => write!(f, "weakening: cut on {}", cut.pretty()),
+            Reduction::Dereliction { cut } => {
+                write!(f, "dereliction: cut on {}", cut.pretty())
+            }
+        }
+    }
+}
+
+/// The result of cut elimination.
+#[derive(Debug, Clone)]
+pub struct Elimination {
+    /// A cut-free proof of the same conclusion
+    pub proof: Proof,
+    /// The number of reduction steps taken
+    pub steps: usize,
+    /// The reductions in the order they were taken, if tracing was on
+    pub trace: Vec<Reduction>,
+}
+
+/// Removes cuts from MALL and MELL proofs.
+///
+/// # Example
+///
+/// ```
+/// use lolli_prove::{verify_proof, CutEliminator, Prover};
+/// use lolli_core::{Formula, Proof, Rule, Sequent, TwoSidedSequent};
+///
+/// let (a, b) = (Formula::atom("A"), Formula::atom("B"));
+/// let mut prover = Prover::new(100);
+///
+/// // Compose A, B ⊢ A ⊗ B and A ⊗ B ⊢ B ⊗ A with a cut on A ⊗ B
+/// let ab = Formula::tensor(a.clone(), b.clone());
+/// let lemma = prover
+///     .prove_two_sided(&TwoSidedSequent::new(vec![a.clone(), b.clone()], vec![ab.clone()]))
+///     .unwrap();
+/// let swap = prover
+///     .prove_two_sided(&TwoSidedSequent::new(vec![ab.clone()], vec![Formula::tensor(b, a)]))
+///     .unwrap();
+/// let mut conclusion = lemma.conclusion.linear.clone();
+/// conclusion.retain(|f| *f != ab);
+/// conclusion.extend(swap.conclusion.linear.iter().filter(|f| **f != ab.negate()).cloned());
+/// let proof = Proof {
+///     conclusion: Sequent::new(conclusion),
+///     rule: Rule::Cut(ab),
+///     premises: vec![lemma, swap],
+/// };
+///
+/// let result = CutEliminator::new().eliminate(&proof).unwrap();
+/// assert!(result.proof.is_cut_free());
+/// assert!(verify_proof(&result.proof).is_ok());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CutEliminator {
+    /// Record every reduction in [`Elimination::trace`]
+    pub trace: bool,
+    /// Give up after this many reduction steps; `None` means unlimited.
+    /// Cut elimination terminates, but with exponentials the cut-free proof
+    /// can be vastly larger than the original.
+    pub max_steps: Option<usize>,
+}
+
+impl CutEliminator {
+    /// Create an eliminator without tracing or a step limit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Turn a proof into a cut-free proof of the same conclusion.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `CutError` if the proof is not valid in the dyadic calculus
+    /// or the step limit is reached.
+    pub fn eliminate(&self, proof: &Proof) -> Result<Elimination, CutError> {
+        let derivation = read(proof)?;
+        let mut run = Run {
+            eliminator: self,
+            steps: 0,
+            trace: Vec::new(),
+        };
+        let derivation = run.eliminate(derivation)?;
+        // The conclusion is unchanged up to desugaring and order
+        let mut cut_free = write(derivation);
+        cut_free.conclusion = proof.conclusion.clone();
+        Ok(Elimination {
+            proof: cut_free,
+            steps: run.steps,
+            trace: run.trace,
+        })
+    }
+}
+
+/// Turn a proof into a cut-free proof of the same conclusion, without a
+/// step limit.
+///
+/// # Errors
+///
+/// Returns a `CutError` if the proof is not valid in the dyadic calculus.
+pub fn eliminate_cuts(proof: &Proof) -> Result<Proof, CutError> {
+    CutEliminator::new()
+        .eliminate(proof)
+        .map(|result| result.proof)
+}
+
+/// A proof of ⊢ Θ ; Γ with the principal formula of its last step.
+#[derive(Debug, Clone)]
+struct Derivation {
+    unrestricted: Vec<Formula>,
+    linear: Vec<Formula>,
+    step: Step,
+    premises: Vec<Derivation>,
+}
+
+/// A rule of the dyadic calculus, with its principal formula.
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Axiom,
+    One,
+    Top,
+    Bottom,
+    Tensor(Formula),
+    Par(Formula),
+    With(Formula),
+    PlusLeft(Formula),
+    PlusRight(Formula),
+    OfCourse(Formula),
+    WhyNot(Formula),
+    /// Copy this formula of Θ into Γ
+    Copy(Formula),
+    Cut(Formula),
+}
+
+impl Step {
+    fn rule(&self) -> Rule {
+        match self {
+            Step::Axiom => Rule::Axiom,
+            Step::One => Rule::OneIntro,
+            Step::Top => Rule::TopIntro,
+            Step::Bottom => Rule::BottomIntro,
+            Step::Tensor(_) => Rule::TensorIntro,
+            Step::Par(_) => Rule::ParIntro,
+            Step::With(_) => Rule::WithIntro,
+            Step::PlusLeft(_) => Rule::PlusIntroLeft,
+            Step::PlusRight(_) => Rule::PlusIntroRight,
+            Step::OfCourse(_) => Rule::OfCourseIntro,
+            Step::WhyNot(_) => Rule::WhyNotIntro,
+            Step::Copy(_) => Rule::Dereliction,
+            Step::Cut(a) => Rule::Cut(a.clone()),
+        }
+    }
+
+    /// Whether this step introduces `formula` of the linear zone.
+    fn introduces(&self, formula: &Formula) -> bool {
+        match self {
+            Step::Axiom => true,
+            Step::One => *formula == Formula::One,
+            Step::Top => *formula == Formula::Top,
+            Step::Bottom => *formula == Formula::Bottom,
+            Step::Tensor(f)
+            | Step::Par(f)
+            | Step::With(f)
+            | Step::PlusLeft(f)
+            | Step::PlusRight(f)
+            | Step::OfCourse(f)
+            | Step::WhyNot(f) => f == formula,
+            Step::Copy(_) | Step::Cut(_) => false,
+        }
+    }
+
+    /// The formula the premises add to Θ, if any.
+    fn adds(&self) -> Option<&Formula> {
+        match self {
+            Step::WhyNot(Formula::WhyNot(a)) => Some(a),
+            _ => None,
+        }
+    }
+}
+
+impl Derivation {
+    /// A step without premises.
+    fn leaf(step: Step, unrestricted: Vec<Formula>, linear: Vec<Formula>) -> Self {
+        Derivation {
+            unrestricted,
+            linear,
+            step,
+            premises: Vec::new(),
+        }
+    }
+
+    /// A step with premises, whose linear zone follows from theirs. Returns
+    /// `None` if the premises do not fit the step.
+    fn infer(step: Step, unrestricted: Vec<Formula>, premises: Vec<Derivation>) -> Option<Self> {
+        let mut above = unrestricted.clone();
+        if let Some(a) = step.adds() {
+            add(&mut above, a);
+        }
+        if !premises.iter().all(|p| same_set(&p.unrestricted, &above)) {
+            return None;
+        }
+
+        let linear = match (&step, premises.as_slice()) {
+            (Step::Bottom, [p]) => extended(&p.linear, Formula::Bottom),
+            (Step::Par(f), [p]) => {
+                let Formula::Par(a, b) = f else { return None };
+                extended(&without(&without(&p.linear, a)?, b)?, f.clone())
+            }
+            (Step::Tensor(f), [p, q]) => {
+                let Formula::Tensor(a, b) = f else {
+                    return None;
+                };
+                let mut linear = without(&p.linear, a)?;
+                linear.extend(without(&q.linear, b)?);
+                extended(&linear, f.clone())
+            }
+            (Step::With(f), [p, q]) => {
+                let Formula::With(a, b) = f else { return None };
+                let linear = without(&p.linear, a)?;
+                if !same_multiset(&linear, &without(&q.linear, b)?) {
+                    return None;
+                }
+                extended(&linear, f.clone())
+            }
+            (Step::PlusLeft(f), [p]) => {
+                let Formula::Plus(a, _) = f else { return None };
+                extended(&without(&p.linear, a)?, f.clone())
+            }
+            (Step::PlusRight(f), [p]) => {
+                let Formula::Plus(_, b) = f else { return None };
+                extended(&without(&p.linear, b)?, f.clone())
+            }
+            (Step::OfCourse(f), [p]) => {
+                let Formula::OfCourse(a) = f else { return None };
+                if p.linear != [a.as_ref().clone()] {
+                    return None;
+                }
+                vec![f.clone()]
+            }
+            (Step::WhyNot(f), [p]) => extended(&p.linear, f.clone()),
+            (Step::Copy(a), [p]) => {
+                if !unrestricted.contains(a) {
+                    return None;
+                }
+                without(&p.linear, a)?
+            }
+            (Step::Cut(a), [p, q]) => {
+                let mut linear = without(&p.linear, a)?;
+                linear.extend(without(&q.linear, &a.negate())?);
+                linear
+            }
+            _ => return None,
+        };
+
+        Some(Derivation {
+            unrestricted,
+            linear,
+            step,
+            premises,
+        })
+    }
+
+    /// The same proof with `formula` added to Θ throughout.
+    fn weaken(mut self, formula: &Formula) -> Self {
+        if !self.unrestricted.contains(formula) {
+            self.unrestricted.push(formula.clone());
+            self.premises = self
+                .premises
+                .into_iter()
+                .map(|p| p.weaken(formula))
+                .collect();
+        }
+        self
+    }
+
+    /// The same proof with Θ extended to `unrestricted`, if it is a subset.
+    fn lift(self, unrestricted: &[Formula]) -> Option<Self> {
+        if !self.unrestricted.iter().all(|f| unrestricted.contains(f)) {
+            return None;
+        }
+        Some(unrestricted.iter().fold(self, |d, f| d.weaken(f)))
+    }
+}
+
+/// Read a proof as a derivation, checking every step.
+fn read(proof: &Proof) -> Result<Derivation, CutError> {
+    let seq = &proof.conclusion;
+    let linear: Vec<Formula> = seq
+        .linear
+        .iter()
+        .chain(&seq.focus)
+        .map(Formula::desugar)
+        .collect();
+    let mut unrestricted = Vec::new();
+    for formula in &seq.unrestricted {
+        add(&mut unrestricted, &formula.desugar());
+    }
+    let premises = proof
+        .premises
+        .iter()
+        .map(read)
+        .collect::<Result<Vec<_>, _>>()?;
+    let invalid = || CutError::InvalidRule {
+        rule: proof.rule.clone(),
+        conclusion: seq.pretty(),
+    };
+
+    // Leaves are checked directly
+    let leaf = match &proof.rule {
+        Rule::Axiom => Some(Step::Axiom),
+        Rule::OneIntro => Some(Step::One),
+        Rule::TopIntro => Some(Step::Top),
+        _ => None,
+    };
+    if let Some(step) = leaf {
+        let valid = premises.is_empty()
+            && match step {
+                Step::Axiom => matches!(
+                    linear.as_slice(),
+                    [x @ (Formula::Atom(_) | Formula::NegAtom(_)), y] if *y == x.negate()
+                ),
+                Step::One => linear == [Formula::One],
+                _ => linear.contains(&Formula::Top),
+            };
+        return if valid {
+            Ok(Derivation::leaf(step, unrestricted, linear))
+        } else {
+            Err(invalid())
+        };
+    }
+
+    // Otherwise try each formula that could be principal
+    let mut candidates: Vec<Step> = Vec::new();
+    for formula in linear.iter().chain(&unrestricted) {
+        let step = match (&proof.rule, formula) {
+            (Rule::BottomIntro, Formula::Bottom) => Step::Bottom,
+            (Rule::TensorIntro, Formula::Tensor(_, _)) => Step::Tensor(formula.clone()),
+            (Rule::ParIntro, Formula::Par(_, _)) => Step::Par(formula.clone()),
+            (Rule::WithIntro, Formula::With(_, _)) => Step::With(formula.clone()),
+            (Rule::PlusIntroLeft, Formula::Plus(_, _)) => Step::PlusLeft(formula.clone()),
+            (Rule::PlusIntroRight, Formula::Plus(_, _)) => Step::PlusRight(formula.clone()),
+            (Rule::OfCourseIntro, Formula::OfCourse(_)) => Step::OfCourse(formula.clone()),
+            (Rule::WhyNotIntro, Formula::WhyNot(_)) => Step::WhyNot(formula.clone()),
+            (Rule::Dereliction | Rule::Contraction, _) if unrestricted.contains(formula) => {
+                Step::Copy(formula.clone())
+            }
+            _ => continue,
+        };
+        if !candidates.contains(&step) {
+            candidates.push(step);
+        }
+    }
+    if let Rule::Cut(a) = &proof.rule {
+        candidates.push(Step::Cut(a.desugar()));
+    }
+
+    for step in candidates {
+        let mut above = unrestricted.clone();
+        if let Some(a) = step.adds() {
+            add(&mut above, a);
+        }
+        let Some(lifted) = premises
+            .iter()
+            .map(|p| p.clone().lift(&above))
+            .collect::<Option<Vec<_>>>()
+        else {
+            continue;
+        };
+        let derivation = if proof.rule == Rule::Contraction {
+            // Two copies of the same formula
+            Derivation::infer(step.clone(), unrestricted.clone(), lifted)
+                .and_then(|d| Derivation::infer(step, unrestricted.clone(), vec![d]))
+        } else {
+            Derivation::infer(step, unrestricted.clone(), lifted)
+        };
+        if let Some(derivation) = derivation {
+            if same_multiset(&derivation.linear, &linear) {
+                return Ok(derivation);
+            }
+        }
+    }
+
+    // Weakening and the focusing rules leave the linear zone as it is
+    if matches!(
+        proof.rule,
+        Rule::Weakening | Rule::FocusPositive(_) | Rule::FocusNegative(_) | Rule::Blur
+    ) {
+        if let [premise] = premises.as_slice() {
+            if let Some(premise) = premise.clone().lift(&unrestricted) {
+                if same_multiset(&premise.linear, &linear) {
+                    return Ok(premise);
+                }
+            }
+        }
+    }
+
+    Err(invalid())
+}
+
+/// Write a derivation back as a proof.
+fn write(derivation: Derivation) -> Proof {
+    Proof {
+        conclusion: Sequent {
+            linear: derivation.linear,
+            unrestricted: derivation.unrestricted,
+            focus: None,
+        },
+        rule: derivation.step.rule(),
+        premises: derivation.premises.into_iter().map(write).collect(),
+    }
+}
+
+/// The state of one elimination.
+struct Run<'a> {
+    eliminator: &'a CutEliminator,
+    steps: usize,
+    trace: Vec<Reduction>,
+}
+
+impl Run<'_> {
+    /// Count a reduction step, giving up past the limit.
+    fn record(&mut self, reduction: Reduction) -> Result<(), CutError> {
+        if self.eliminator.max_steps == Some(self.steps) {
+            return Err(CutError::StepLimit(self.steps));
+        }
+        self.steps += 1;
+        if self.eliminator.trace {
+            self.trace.push(reduction);
+        }
+        Ok(())
+    }
+
+    /// Remove the cuts of `derivation`, starting with the topmost ones.
+    fn eliminate(&mut self, derivation: Derivation) -> Result<Derivation, CutError> {
+        let Derivation {
+            unrestricted,
+            linear,
+            step,
+            premises,
+        } = derivation;
+        let mut premises = premises
+            .into_iter()
+            .map(|p| self.eliminate(p))
+            .collect::<Result<Vec<_>, _>>()?;
+        match step {
+            Step::Cut(a) => {
+                let q = premises.pop().expect("a cut has two premises");
+                let p = premises.pop().expect("a cut has two premises");
+                self.cut(&a, p, q)
+            }
+            step => Ok(Derivation {
+                unrestricted,
+                linear,
+                step,
+                premises,
+            }),
+        }
+    }
+
+    /// Cut cut-free proofs of ⊢ Θ ; Γ, a and ⊢ Θ ; Δ, a⊥ into a cut-free
+    /// proof of ⊢ Θ ; Γ, Δ.
+    fn cut(&mut self, a: &Formula, p: Derivation, q: Derivation) -> Result<Derivation, CutError> {
+        let dual = a.negate();
+        if p.step == Step::Axiom {
+            self.record(Reduction::Axiom { cut: a.clone() })?;
+            return Ok(q);
+        }
+        if q.step == Step::Axiom {
+            self.record(Reduction::Axiom { cut: a.clone() })?;
+            return Ok(p);
+        }
+
+        // A ⊤ in Γ or Δ absorbs the other side
+        let rest = |d: &Derivation, f: &Formula| without(&d.linear, f).expect("cut formula");
+        let (gamma, delta) = (rest(&p, a), rest(&q, &dual));
+        if (p.step == Step::Top && gamma.contains(&Formula::Top))
+            || (q.step == Step::Top && delta.contains(&Formula::Top))
+        {
+            self.record(Reduction::Commute {
+                cut: a.clone(),
+                rule: Rule::TopIntro,
+            })?;
+            let mut linear = gamma;
+            linear.extend(delta);
+            return Ok(Derivation::leaf(Step::Top, p.unrestricted, linear));
+        }
+
+        if !p.step.introduces(a) {
+            return self.commute(a, p, q);
+        }
+        if !q.step.introduces(&dual) {
+            return self.commute(&dual, q, p);
+        }
+        self.record(Reduction::Principal { cut: a.clone() })?;
+        self.principal(a, p, q)
+    }
+
+    /// The cut where both premises end with a rule introducing the cut
+    /// formula.
+    fn principal(
+        &mut self,
+        a: &Formula,
+        p: Derivation,
+        mut q: Derivation,
+    ) -> Result<Derivation, CutError> {
+        let mut ps = p.premises;
+        match (a, p.step) {
+            (Formula::Tensor(x, y), Step::Tensor(_)) => {
+                let q1 = q.premises.pop().expect("par has one premise");
+                let p2 = ps.pop().expect("tensor has two premises");
+                let p1 = ps.pop().expect("tensor has two premises");
+                let r = self.cut(x, p1, q1)?;
+                self.cut(y, p2, r)
+            }
+            (Formula::One, Step::One) => Ok(q.premises.pop().expect("⊥ has one premise")),
+            (Formula::With(x, y), Step::With(_)) => {
+                let q1 = q.premises.pop().expect("plus has one premise");
+                let p2 = ps.pop().expect("with has two premises");
+                let p1 = ps.pop().expect("with has two premises");
+                match q.step {
+                    Step::PlusLeft(_) => self.cut(x, p1, q1),
+                    _ => self.cut(y, p2, q1),
+                }
+            }
+            (Formula::OfCourse(x), Step::OfCourse(_)) => {
+                let q1 = q.premises.pop().expect("? has one premise");
+                let p1 = ps.pop().expect("! has one premise");
+                self.cut_unrestricted(x, p1, q1)
+            }
+            (_, step) => {
+                // The dual case: swap the premises
+                let p = Derivation {
+                    premises: ps,
+                    step,
+                    ..p
+                };
+                self.principal(&a.negate(), q, p)
+            }
+        }
+    }
+
+    /// Move a cut on `a` above the last step of `p`, which does not
+    /// introduce `a`.
+    fn commute(
+        &mut self,
+        a: &Formula,
+        p: Derivation,
+        q: Derivation,
+    ) -> Result<Derivation, CutError> {
+        self.record(Reduction::Commute {
+            cut: a.clone(),
+            rule: p.step.rule(),
+        })?;
+        let mut premises = p.premises;
+        match &p.step {
+            // Both premises of a with contain the cut formula
+            Step::With(_) => {
+                premises = premises
+                    .into_iter()
+                    .map(|premise| self.cut(a, premise, q.clone()))
+                    .collect::<Result<_, _>>()?;
+            }
+            // Only one premise of a tensor does
+            Step::Tensor(Formula::Tensor(x, _)) => {
+                let in_left = without(&premises[0].linear, x).is_some_and(|rest| rest.contains(a));
+                let i = if in_left { 0 } else { 1 };
+                let premise = premises.remove(i);
+                premises.insert(i, self.cut(a, premise, q)?);
+            }
+            step => {
+                let premise = premises.pop().expect("one premise");
+                let q = match step.adds() {
+                    Some(b) => q.weaken(b),
+                    None => q,
+                };
+                premises.push(self.cut(a, premise, q)?);
+            }
+        }
+        Ok(Derivation::infer(p.step, p.unrestricted, premises).expect("commuted cut"))
+    }
+
+    /// Cut cut-free proofs of ⊢ Θ ; a and ⊢ Θ, a⊥ ; Δ into a cut-free proof
+    /// of ⊢ Θ ; Δ. This is what a cut on `!a` against `?a⊥` leaves.
+    fn cut_unrestricted(
+        &mut self,
+        a: &Formula,
+        p: Derivation,
+        q: Derivation,
+    ) -> Result<Derivation, CutError> {
+        let cut = Formula::of_course(a.clone());
+        let dual = a.negate();
+
+        // Θ already has a⊥, or the proof never uses it
+        if p.unrestricted.contains(&dual) || q.premises.is_empty() {
+            self.record(Reduction::Weakening { cut })?;
+            return Ok(Derivation {
+                unrestricted: p.unrestricted,
+                ..q
+            });
+        }
+
+        // A copy of a⊥ gets a linear cut of its own
+        if q.step == Step::Copy(dual.clone()) {
+            self.record(Reduction::Dereliction { cut })?;
+            let mut q = q;
+            let q1 = q.premises.pop().expect("dereliction has one premise");
+            let r = self.cut_unrestricted(a, p.clone(), q1)?;
+            return self.cut(a, p, r);
+        }
+
+        self.record(Reduction::Commute {
+            cut,
+            rule: q.step.rule(),
+        })?;
+        let premises = q
+            .premises
+            .into_iter()
+            .map(|premise| {
+                let p = match q.step.adds() {
+                    Some(b) => p.clone().weaken(b),
+                    None => p.clone(),
+                };
+                self.cut_unrestricted(a, p, premise)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Derivation::infer(q.step, p.unrestricted, premises).expect("commuted cut"))
+    }
+}
+
+/// Add `formula` to a set.
+fn add(set: &mut Vec<Formula>, formula: &Formula) {
+    if !set.contains(formula) {
+        set.push(formula.clone());
+    }
+}
+
+/// `items` with `formula` added.
+fn extended(items: &[Formula], formula: Formula) -> Vec<Formula> {
+    let mut items = items.to_vec();
+    items.push(formula);
+    items
+}
+
+fn same_set(a: &[Formula], b: &[Formula]) -> bool {
+    a.iter().all(|f| b.contains(f)) && b.iter().all(|f| a.contains(f))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{verify_proof, Prover};
+    use lolli_core::TwoSidedSequent;
+
+    fn atom(name: &str) -> Formula {
+        Formula::atom(name)
+    }
+
+    fn lemma(antecedent: Vec<Formula>, succedent: Formula) -> Proof {
+        let seq = TwoSidedSequent::new(antecedent, vec![succedent]);
+        Prover::new(100)
+            .prove_two_sided(&seq)
+            .unwrap_or_else(|| panic!("no proof of {}", seq.pretty()))
+    }
+
+    fn linear(proof: &Proof) -> Vec<Formula> {
+        proof
+            .conclusion
+            .linear
+            .iter()
+            .map(Formula::desugar)
+            .collect()
+    }
+
+    /// Cut `p`, which proves `a`, against `q`, which uses it.
+    fn compose(a: &Formula, p: Proof, q: Proof) -> Proof {
+        let a = a.desugar();
+        let mut conclusion = without(&linear(&p), &a).unwrap();
+        conclusion.extend(without(&linear(&q), &a.negate()).unwrap());
+        Proof {
+            conclusion: Sequent::new(conclusion),
+            rule: Rule::Cut(a),
+            premises: vec![p, q],
+        }
+    }
+
+    fn eliminate(proof: &Proof) -> Elimination {
+        let eliminator = CutEliminator {
+            trace: true,
+            ..CutEliminator::new()
+        };
+        let result = eliminator.eliminate(proof).unwrap();
+        assert!(result.proof.is_cut_free());
+        assert_eq!(result.proof.conclusion, proof.conclusion);
+        assert!(verify_proof(&result.proof).is_ok());
+        assert!(read(&result.proof).is_ok());
+        assert_eq!(result.trace.len(), result.steps);
+        result
+    }
+
+    #[test]
+    fn test_cut_free_proof_unchanged() {
+        let proof = lemma(
+            vec![Formula::tensor(atom("A"), atom("B"))],
+            Formula::tensor(atom("B"), atom("A")),
+        );
+        let result = eliminate(&proof);
+        assert_eq!(result.steps, 0);
+        assert_eq!(result.proof.depth(), proof.depth());
+    }
+
+    #[test]
+    fn test_multiplicative_cut() {
+        let ab = Formula::tensor(atom("A"), atom("B"));
+        let proof = compose(
+            &ab,
+            lemma(vec![atom("A"), atom("B")], ab.clone()),
+            lemma(vec![ab.clone()], Formula::tensor(atom("B"), atom("A"))),
+        );
+        let result = eliminate(&proof);
+        assert!(result.trace.contains(&Reduction::Principal { cut: ab }));
+    }
+
+    #[test]
+    fn test_additive_and_unit_cuts() {
+        let (a, b) = (atom("A"), atom("B"));
+        let ab = Formula::plus(a.clone(), b.clone());
+        let ba = Formula::plus(b.clone(), a.clone());
+        // A ⊢ A ⊕ B ⊢ B ⊕ A ⊢ A ⊕ B, with two cuts
+        let proof = compose(
+            &ba,
+            compose(
+                &ab,
+                lemma(vec![a.clone()], ab.clone()),
+                lemma(vec![ab.clone()], ba.clone()),
+            ),
+            lemma(vec![ba.clone()], ab.clone()),
+        );
+        assert_eq!(proof.cut_count(), 2);
+        eliminate(&proof);
+
+        let id = Formula::lolli(a.clone(), a.clone());
+        let proof = compose(
+            &Formula::One,
+            lemma(vec![], Formula::One),
+            lemma(vec![Formula::One], id),
+        );
+        eliminate(&proof);
+
+        let proof = compose(
+            &Formula::Top,
+            lemma(vec![a.clone()], Formula::Top),
+            lemma(vec![Formula::Top, b], Formula::Top),
+        );
+        eliminate(&proof);
+    }
+
+    #[test]
+    fn test_exponential_cut() {
+        let a = atom("A");
+        let bang = Formula::of_course(a.clone());
+        let bang_bang = Formula::of_course(bang.clone());
+        // !A ⊢ !!A and !!A ⊢ A ⊗ A give !A ⊢ A ⊗ A
+        let proof = compose(
+            &bang_bang,
+            lemma(vec![bang.clone()], bang_bang.clone()),
+            lemma(
+                vec![bang_bang.clone()],
+                Formula::tensor(a.clone(), a.clone()),
+            ),
+        );
+        let result = eliminate(&proof);
+        assert!(result
+            .trace
+            .iter()
+            .any(|r| matches!(r, Reduction::Dereliction { .. })));
+
+        // An unused !(B ⊸ B) is dropped
+        let id = Formula::of_course(Formula::lolli(atom("B"), atom("B")));
+        let proof = compose(
+            &id,
+            lemma(vec![], id.clone()),
+            lemma(vec![id.clone(), a.clone()], a),
+        );
+        let result = eliminate(&proof);
+        assert!(result
+            .trace
+            .iter()
+            .any(|r| matches!(r, Reduction::Weakening { .. })));
+    }
+
+    #[test]
+    fn test_step_limit() {
+        let ab = Formula::with(atom("A"), atom("B"));
+        let proof = compose(
+            &ab,
+            lemma(vec![ab.clone()], ab.clone()),
+            lemma(vec![ab.clone()], atom("B")),
+        );
+        let steps = eliminate(&proof).steps;
+        assert!(steps > 0);
+
+        let mut eliminator = CutEliminator::new();
+        eliminator.max_steps = Some(steps - 1);
+        assert_eq!(
+            eliminator.eliminate(&proof).unwrap_err(),
+            CutError::StepLimit(steps - 1)
+        );
+        eliminator.max_steps = Some(steps);
+        let result = eliminator.eliminate(&proof).unwrap();
+        assert!(result.trace.is_empty());
+        assert!(eliminate_cuts(&proof).unwrap().is_cut_free());
+    }
+
+    #[test]
+    fn test_invalid_cut() {
+        let mut proof = compose(
+            &atom("A"),
+            lemma(vec![atom("A")], atom("A")),
+            lemma(vec![atom("A")], atom("A")),
+        );
+        proof.rule = Rule::Cut(atom("B"));
+        assert!(matches!(
+            CutEliminator::new().eliminate(&proof),
+            Err(CutError::InvalidRule { .. })
+        ));
+    }
+}
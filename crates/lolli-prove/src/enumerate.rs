@@ -0,0 +1,921 @@
+//! Enumeration of proofs.
+//!
+//! [`Prover::prove`] stops at the first proof, but a sequent often has
+//! several that compute different things: `A & A ⊢ A` can project either
+//! half. [`Prover::proofs`] lists them all, lazily.
+//!
+//! The enumeration is a best-first walk over partial proofs, smallest
+//! first, so that each call to `next` only does the work up to the next
+//! finished proof and a sequent with infinitely many proofs still yields
+//! its small ones. A partial proof is ranked by the size of its smallest
+//! completion: each open goal without exponentials counts the fewest steps
+//! a proof of it takes, memoized by sequent, and any other goal one step.
+//! Among partial proofs of equal rank the one with the least left to do
+//! goes first, so complete branches are finished rather than set aside.
+//!
+//! Invertible rules are applied first and in a fixed order, `⊤` before the
+//! others, which already rules out most reorderings of the same proof. Each
+//! new premise is checked with the focused prover, or only by counting
+//! atoms when it has exponentials, and partial proofs with a refuted premise
+//! are dropped rather than explored.
+//!
+//! Contexts are dyadic: `?A` moves A into the unrestricted zone, and
+//! dereliction copies A back out while leaving it there, so contraction and
+//! weakening stay implicit. As in focused search, a copy is decomposed right
+//! away, and so are the positive parts of a `⊗` or `⊕`.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use lolli_core::{Formula, Proof, Rule, Sequent};
+
+use crate::balance::can_link;
+use crate::search::has_exponentials;
+use crate::{check_balance, PartialProof, ProofOutcome, Prover};
+
+/// Where a formula occurs in the goal: the index of the goal formula, then
+/// the child taken at each connective.
+type Address = Vec<usize>;
+
+/// The `&` branches a proof step lies in, as the address of each `&` and
+/// the side taken, sorted.
+type Slice = Vec<(Address, usize)>;
+
+/// A formula together with where it came from in the goal.
+#[derive(Clone, Debug)]
+struct Occurrence {
+    formula: Formula,
+    address: Address,
+}
+
+impl Occurrence {
+    fn child(&self, formula: Formula, side: usize) -> Occurrence {
+        let mut address = self.address.clone();
+        address.push(side);
+        Occurrence { formula, address }
+    }
+}
+
+/// A sequent still to be proved.
+#[derive(Clone, Debug)]
+struct Goal {
+    linear: Vec<Occurrence>,
+    unrestricted: Vec<Occurrence>,
+    depth: usize,
+    slice: Slice,
+    /// Whether the next rule must decompose the last linear occurrence,
+    /// which is a positive part of the previous one or was just copied out
+    /// of the unrestricted zone
+    focused: bool,
+    /// A lower bound on the steps a proof of the goal takes
+    estimate: usize,
+}
+
+impl Goal {
+    fn root(seq: &Sequent) -> Goal {
+        let occurrences = |formulas: &[Formula], offset: usize| {
+            formulas
+                .iter()
+                .enumerate()
+                .map(|(i, f)| Occurrence {
+                    formula: f.clone(),
+                    address: vec![offset + i],
+                })
+                .collect()
+        };
+        Goal {
+            linear: occurrences(&seq.linear, 0),
+            unrestricted: occurrences(&seq.unrestricted, seq.linear.len()),
+            depth: 0,
+            slice: Vec::new(),
+            focused: false,
+            estimate: 1,
+        }
+    }
+
+    fn sequent(&self) -> Sequent {
+        Sequent {
+            linear: self.linear.iter().map(|o| o.formula.clone()).collect(),
+            unrestricted: self
+                .unrestricted
+                .iter()
+                .map(|o| o.formula.clone())
+                .collect(),
+            focus: None,
+        }
+    }
+
+    /// A premise with `linear` and this goal's unrestricted zone.
+    fn premise(&self, linear: Vec<Occurrence>) -> Goal {
+        Goal {
+            linear,
+            unrestricted: self.unrestricted.clone(),
+            depth: self.depth + 1,
+            slice: self.slice.clone(),
+            focused: false,
+            estimate: 1,
+        }
+    }
+
+    /// A premise with `linear`, which stays focused on its last occurrence
+    /// while that is positive.
+    fn focused_premise(&self, linear: Vec<Occurrence>) -> Goal {
+        let focused = linear.last().is_some_and(|o| o.formula.is_positive());
+        let mut premise = self.premise(linear);
+        premise.focused = focused;
+        premise
+    }
+
+    /// The linear occurrences other than the one at `i`.
+    fn without(&self, i: usize) -> Vec<Occurrence> {
+        let mut linear = self.linear.clone();
+        linear.remove(i);
+        linear
+    }
+}
+
+/// A choice that tells proofs apart when rule order is ignored.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Choice {
+    /// An axiom linking two atom occurrences
+    Link(Address, Address),
+    /// A `1` closing its branch
+    One(Address),
+    /// A `⊤` closing its branch, whatever it absorbs
+    Top(Address),
+    /// The side of a `⊕`
+    Plus(Address, usize),
+}
+
+/// A proof under construction.
+#[derive(Clone, Debug)]
+struct Partial {
+    /// The steps taken so far, in pre-order, with their premise counts
+    steps: Vec<(Sequent, Rule, usize)>,
+    /// Goals still to prove, the next one last
+    open: Vec<Goal>,
+    /// The sum of the estimates of the open goals
+    remaining: usize,
+    /// The choices made so far, in the `&` branch each lies in
+    choices: Vec<(Slice, Choice)>,
+}
+
+/// What decides the completions of a partial proof up to permutation: its
+/// choices and its open goals, as the addresses of their occurrences, all
+/// sorted.
+type State = (
+    Vec<(Slice, Choice)>,
+    Vec<(Vec<Address>, Vec<Address>, Slice, usize, Option<Address>)>,
+);
+
+impl Partial {
+    fn state(&self) -> State {
+        let sorted = |occurrences: &[Occurrence]| {
+            let mut addresses: Vec<Address> =
+                occurrences.iter().map(|o| o.address.clone()).collect();
+            addresses.sort();
+            addresses
+        };
+        let mut choices = self.choices.clone();
+        choices.sort();
+        let mut open: Vec<_> = self
+            .open
+            .iter()
+            .map(|g| {
+                let focus = g.focused.then(|| g.linear.last()).flatten();
+                let focus = focus.map(|o| o.address.clone());
+                (
+                    sorted(&g.linear),
+                    sorted(&g.unrestricted),
+                    g.slice.clone(),
+                    g.depth,
+                    focus,
+                )
+            })
+            .collect();
+        open.sort();
+        (choices, open)
+    }
+}
+
+/// One way to apply a rule to a goal.
+struct Expansion {
+    rule: Rule,
+    premises: Vec<Goal>,
+    choice: Option<Choice>,
+}
+
+/// An iterator over the cut-free proofs of a sequent, see [`Prover::proofs`].
+pub struct Proofs<'a> {
+    prover: &'a mut Prover,
+    /// The goal to prove, until the first call to `next` queues it, so that
+    /// it is estimated with the options set
+    root: Option<Goal>,
+    /// Partial proofs not yet expanded, by id
+    pending: HashMap<usize, Partial>,
+    /// Pending ids by the size of their smallest completion, then the
+    /// steps left, then oldest first
+    queue: BinaryHeap<Reverse<(usize, usize, usize)>>,
+    /// Number of partial proofs queued so far
+    queued: usize,
+    /// Choices of the proofs yielded so far, when deduplicating
+    seen: Option<HashSet<Vec<(Slice, Choice)>>>,
+    /// The states of the partial proofs queued so far, when deduplicating
+    states: HashSet<State>,
+    /// Whether a sequent is refuted, by its key
+    refutations: HashMap<Vec<String>, bool>,
+    /// The fewest steps a goal without exponentials takes, by its key and
+    /// the formula it is focused on, or `None` if it has no proof
+    sizes: HashMap<(Vec<String>, Option<String>), Option<usize>>,
+}
+
+impl Prover {
+    /// Enumerate the proofs of ⊢ Γ up to `max_depth`, lazily.
+    ///
+    /// Premises are checked with this prover, so its strategy and limits
+    /// apply and its cache is shared. Each proof is a different tree of
+    /// rules. Use
+    /// [`Proofs::up_to_permutation`] to keep only one proof per way of
+    /// linking atoms and choosing sides. A `⊗` with more than 16 other
+    /// formulas beside it is not split, so its proofs are left out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lolli_prove::{Prover, Rule};
+    /// use lolli_core::{Formula, TwoSidedSequent};
+    ///
+    /// // A & A ⊢ A projects either half
+    /// let a = Formula::atom("A");
+    /// let seq = TwoSidedSequent::new(vec![Formula::with(a.clone(), a.clone())], vec![a]);
+    ///
+    /// let rules: Vec<Rule> = Prover::new(10)
+    ///     .proofs(&seq.to_one_sided())
+    ///     .map(|proof| proof.rule)
+    ///     .collect();
+    /// assert_eq!(rules, vec![Rule::PlusIntroLeft, Rule::PlusIntroRight]);
+    /// ```
+    pub fn proofs(&mut self, seq: &Sequent) -> Proofs<'_> {
+        Proofs {
+            prover: self,
+            root: Some(Goal::root(seq)),
+            pending: HashMap::new(),
+            queue: BinaryHeap::new(),
+            queued: 0,
+            seen: None,
+            states: HashSet::new(),
+            refutations: HashMap::new(),
+            sizes: HashMap::new(),
+        }
+    }
+}
+
+impl Proofs<'_> {
+    /// Skip proofs that differ from an earlier one only in the order of
+    /// their rules.
+    ///
+    /// Two proofs count as the same if they link the same atom occurrences,
+    /// take the same sides of each `⊕` and close the same branches with `1`
+    /// and `⊤`, in the same branches of each `&`. What a `⊤` absorbs is not
+    /// a choice, so where both halves of a `⊗` are closed by `⊤` whatever
+    /// they get, only one way of splitting the context is tried, and a
+    /// partial proof that reaches the state of an earlier one by another
+    /// order of rules is dropped.
+    pub fn up_to_permutation(mut self) -> Self {
+        self.seen = Some(HashSet::new());
+        self
+    }
+
+    fn expand(&mut self, goal: &Goal) -> Vec<Expansion> {
+        if goal.depth > self.prover.max_depth {
+            return Vec::new();
+        }
+        let mut expansions = rules(goal, self.seen.is_some(), true);
+
+        // Drop expansions with a premise that cannot be proved
+        expansions.retain_mut(|e| {
+            e.premises.iter_mut().all(|p| match self.estimate(p) {
+                Some(estimate) => {
+                    p.estimate = estimate;
+                    true
+                }
+                None => false,
+            })
+        });
+        expansions
+    }
+
+    fn push(&mut self, partial: Partial) {
+        // Partial proofs in the same state have the same completions
+        if self.seen.is_some() && !self.states.insert(partial.state()) {
+            return;
+        }
+        let remaining = partial.remaining;
+        let size = partial.steps.len() + remaining;
+        self.pending.insert(self.queued, partial);
+        self.queue.push(Reverse((size, remaining, self.queued)));
+        self.queued += 1;
+    }
+
+    /// A lower bound on the steps a proof of `goal` takes, or `None` if it
+    /// has no proof.
+    fn estimate(&mut self, goal: &Goal) -> Option<usize> {
+        let seq = goal.sequent();
+        if has_exponentials(&seq) {
+            return (!self.refuted(&seq)).then_some(1);
+        }
+        self.smallest(goal)
+    }
+
+    /// The fewest steps a proof of `goal`, which has no exponentials, takes.
+    /// The search is finite, since every rule makes the goal smaller.
+    fn smallest(&mut self, goal: &Goal) -> Option<usize> {
+        let seq = goal.sequent();
+        let focus = goal
+            .focused
+            .then(|| goal.linear.last().map(|o| o.formula.pretty()))
+            .flatten();
+        let key = (self.prover.sequent_key(&seq), focus);
+        if let Some(&size) = self.sizes.get(&key) {
+            return size;
+        }
+        let size = if self.refuted(&seq) {
+            None
+        } else {
+            rules(goal, self.seen.is_some(), true)
+                .iter()
+                .filter_map(|e| {
+                    let premises: Option<usize> = e.premises.iter().map(|p| self.smallest(p)).sum();
+                    premises.map(|size| size + 1)
+                })
+                .min()
+        };
+        self.sizes.insert(key, size);
+        size
+    }
+
+    /// Whether ⊢ Γ is known to have no proof.
+    fn refuted(&mut self, seq: &Sequent) -> bool {
+        if !can_link(seq) {
+            return true;
+        }
+        // With exponentials the prover only refutes unbalanced sequents,
+        // after searching in vain
+        if has_exponentials(seq) {
+            return check_balance(seq).is_err();
+        }
+        let key = self.prover.sequent_key(seq);
+        if let Some(&refuted) = self.refutations.get(&key) {
+            return refuted;
+        }
+        let refuted = matches!(self.prover.decide(seq), ProofOutcome::Refuted);
+        self.refutations.insert(key, refuted);
+        refuted
+    }
+}
+
+impl Iterator for Proofs<'_> {
+    type Item = Proof;
+
+    fn next(&mut self) -> Option<Proof> {
+        if let Some(goal) = self.root.take() {
+            if let Some(estimate) = self.estimate(&goal) {
+                let goal = Goal { estimate, ..goal };
+                self.push(Partial {
+                    steps: Vec::new(),
+                    remaining: goal.estimate,
+                    open: vec![goal],
+                    choices: Vec::new(),
+                });
+            }
+        }
+        while let Some(Reverse((_, _, id))) = self.queue.pop() {
+            let mut partial = self.pending.remove(&id).expect("a queued partial proof");
+            let Some(goal) = partial.open.pop() else {
+                if let Some(seen) = &mut self.seen {
+                    let mut choices = partial.choices;
+                    choices.sort();
+                    if !seen.insert(choices) {
+                        continue;
+                    }
+                }
+                return Some(build(&mut partial.steps.into_iter()));
+            };
+
+            let conclusion = goal.sequent();
+            let expansions = self.expand(&goal);
+            for expansion in expansions {
+                let mut next = partial.clone();
+                next.steps
+                    .push((conclusion.clone(), expansion.rule, expansion.premises.len()));
+                next.remaining -= goal.estimate;
+                next.remaining += expansion.premises.iter().map(|p| p.estimate).sum::<usize>();
+                next.open.extend(expansion.premises.into_iter().rev());
+                if let Some(choice) = expansion.choice {
+                    next.choices.push((goal.slice.clone(), choice));
+                }
+                self.push(next);
+            }
+        }
+        None
+    }
+}
+
+/// Goals the search for a deepest partial proof expands before it gives up.
+const FRONTIER_GOALS: usize = 1000;
+
+impl Prover {
+    /// The deepest partial proof of ⊢ Γ, see [`Prover::explain`].
+    pub(crate) fn deepest_partial(&mut self, seq: &Sequent) -> PartialProof {
+        Frontier {
+            prover: self,
+            memo: HashMap::new(),
+            budget: FRONTIER_GOALS,
+        }
+        .deepest(&Goal::root(seq))
+    }
+}
+
+/// A search for the deepest partial proof of a goal without a proof.
+struct Frontier<'a> {
+    prover: &'a mut Prover,
+    /// The deepest partial proof of each goal, by its key and the formula
+    /// it is focused on
+    memo: HashMap<(Vec<String>, Option<String>), PartialProof>,
+    /// Goals left to expand
+    budget: usize,
+}
+
+impl Frontier<'_> {
+    /// Among the rules that apply to `goal`, the one whose premises get
+    /// furthest, with the fewest goals left open on a tie.
+    fn deepest(&mut self, goal: &Goal) -> PartialProof {
+        let seq = goal.sequent();
+        let focus = goal
+            .focused
+            .then(|| goal.linear.last().map(|o| o.formula.pretty()))
+            .flatten();
+        let key = (self.prover.sequent_key(&seq), focus);
+        if let Some(partial) = self.memo.get(&key) {
+            return partial.clone();
+        }
+        let mut best = PartialProof::open(seq.clone());
+        if self.budget == 0 || goal.depth >= self.prover.max_depth {
+            return best;
+        }
+        self.budget -= 1;
+        // A goal that comes back while it is being expanded stays open
+        self.memo.insert(key.clone(), best.clone());
+
+        let rank = |p: &PartialProof| (p.depth(), Reverse(p.open_goals().len()));
+        for expansion in rules(goal, true, false) {
+            let premises = expansion
+                .premises
+                .iter()
+                .map(|p| match self.prover.decide(&p.sequent()) {
+                    ProofOutcome::Provable(proof) => PartialProof::from(proof),
+                    _ => self.deepest(p),
+                })
+                .collect();
+            let candidate = PartialProof {
+                conclusion: seq.clone(),
+                rule: Some(expansion.rule),
+                premises,
+            };
+            if rank(&candidate) > rank(&best) {
+                best = candidate;
+            }
+        }
+        self.memo.insert(key, best.clone());
+        best
+    }
+}
+
+/// Every rule that applies to `goal`: the invertible rule for its first
+/// invertible formula, with `⊤` first, or else the synchronous rules.
+fn rules(goal: &Goal, collapse_top: bool, linkable: bool) -> Vec<Expansion> {
+    let position = |p: fn(&Formula) -> bool| goal.linear.iter().position(|o| p(&o.formula));
+    match position(|f| *f == Formula::Top).or_else(|| position(invertible)) {
+        Some(i) => vec![invert(goal, i)],
+        None => synchronous(goal, collapse_top, linkable),
+    }
+}
+
+/// Whether the rule for `formula` is invertible, so it can be applied first.
+fn invertible(formula: &Formula) -> bool {
+    matches!(
+        formula,
+        Formula::Par(..)
+            | Formula::Lolli(..)
+            | Formula::Bottom
+            | Formula::Top
+            | Formula::With(..)
+            | Formula::WhyNot(_)
+    )
+}
+
+/// Apply the invertible rule for the formula at `i`.
+fn invert(goal: &Goal, i: usize) -> Expansion {
+    let occurrence = &goal.linear[i];
+    let rest = goal.without(i);
+    let with = |extra: Vec<Occurrence>| {
+        let mut linear = rest.clone();
+        linear.extend(extra);
+        linear
+    };
+
+    let (rule, premises, choice) = match &occurrence.formula {
+        Formula::Par(a, b) => {
+            let halves = vec![
+                occurrence.child((**a).clone(), 0),
+                occurrence.child((**b).clone(), 1),
+            ];
+            (Rule::ParIntro, vec![goal.premise(with(halves))], None)
+        }
+        Formula::Lolli(a, b) => {
+            let halves = vec![
+                occurrence.child(a.negate(), 0),
+                occurrence.child((**b).clone(), 1),
+            ];
+            (Rule::ParIntro, vec![goal.premise(with(halves))], None)
+        }
+        Formula::Bottom => (Rule::BottomIntro, vec![goal.premise(rest)], None),
+        Formula::Top => {
+            let choice = Choice::Top(occurrence.address.clone());
+            (Rule::TopIntro, vec![], Some(choice))
+        }
+        Formula::With(a, b) => {
+            let branch = |part: &Formula, side: usize| {
+                let mut premise = goal.premise(with(vec![occurrence.child(part.clone(), side)]));
+                premise.slice.push((occurrence.address.clone(), side));
+                premise.slice.sort();
+                premise
+            };
+            (Rule::WithIntro, vec![branch(a, 0), branch(b, 1)], None)
+        }
+        Formula::WhyNot(a) => {
+            let mut premise = goal.premise(rest);
+            premise
+                .unrestricted
+                .push(occurrence.child((**a).clone(), 0));
+            (Rule::WhyNotIntro, vec![premise], None)
+        }
+        _ => unreachable!("invert on a formula without an invertible rule"),
+    };
+    Expansion {
+        rule,
+        premises,
+        choice,
+    }
+}
+
+/// Tensors with more context formulas than this are not split, so their
+/// proofs are not enumerated.
+const MAX_SPLIT_CONTEXT: usize = 16;
+
+/// Every rule that applies to a goal with no invertible formula left.
+///
+/// With `collapse_top`, a `⊗` whose halves are both closed by `⊤` whatever
+/// they get is split only one way. With `linkable`, splits with a half
+/// whose atoms cannot all be linked are dropped.
+fn synchronous(goal: &Goal, collapse_top: bool, linkable: bool) -> Vec<Expansion> {
+    let mut expansions = Vec::new();
+    let leaf = |rule: Rule, choice: Choice| Expansion {
+        rule,
+        premises: vec![],
+        choice: Some(choice),
+    };
+
+    match goal.linear.as_slice() {
+        [x, y] if is_atom(&x.formula) && y.formula == x.formula.negate() => {
+            let mut link = [x.address.clone(), y.address.clone()];
+            link.sort();
+            let [first, second] = link;
+            expansions.push(leaf(Rule::Axiom, Choice::Link(first, second)));
+        }
+        [one] if one.formula == Formula::One => {
+            expansions.push(leaf(Rule::OneIntro, Choice::One(one.address.clone())));
+        }
+        _ => {}
+    }
+
+    for (i, occurrence) in goal.linear.iter().enumerate() {
+        let skip = if goal.focused {
+            i + 1 < goal.linear.len()
+        } else {
+            // Equal formulas give equal proofs
+            goal.linear[..i]
+                .iter()
+                .any(|o| o.formula == occurrence.formula)
+        };
+        if skip {
+            continue;
+        }
+        let replaced = |part: Occurrence| {
+            let mut linear = goal.linear.clone();
+            linear[i] = part;
+            goal.premise(linear)
+        };
+        let moved = |part: Occurrence| {
+            let mut linear = goal.without(i);
+            linear.push(part);
+            goal.focused_premise(linear)
+        };
+
+        match &occurrence.formula {
+            Formula::Tensor(a, b) => {
+                let rest = goal.without(i);
+                let mut splits = HashSet::new();
+                let masks = if collapse_top && absorbs(a) && absorbs(b) {
+                    0..1
+                } else if rest.len() <= MAX_SPLIT_CONTEXT {
+                    0..1usize << rest.len()
+                } else {
+                    0..0
+                };
+                for mask in masks {
+                    let (left, right): (Vec<_>, Vec<_>) =
+                        rest.iter().enumerate().partition(|&(j, _)| {
+                            1usize
+                                .checked_shl(j as u32)
+                                .is_some_and(|bit| mask & bit != 0)
+                        });
+                    let mut key: Vec<String> =
+                        left.iter().map(|(_, o)| o.formula.pretty()).collect();
+                    key.sort();
+                    if !splits.insert(key) {
+                        continue;
+                    }
+                    let side = |part: Vec<(usize, &Occurrence)>, half: Occurrence| {
+                        let mut linear: Vec<Occurrence> =
+                            part.into_iter().map(|(_, o)| o.clone()).collect();
+                        linear.push(half);
+                        goal.focused_premise(linear)
+                    };
+                    let premises = vec![
+                        side(left, occurrence.child((**a).clone(), 0)),
+                        side(right, occurrence.child((**b).clone(), 1)),
+                    ];
+                    if linkable && !premises.iter().all(|p| can_link(&p.sequent())) {
+                        continue;
+                    }
+                    expansions.push(Expansion {
+                        rule: Rule::TensorIntro,
+                        premises,
+                        choice: None,
+                    });
+                }
+            }
+            Formula::Plus(a, b) => {
+                for (side, part, rule) in
+                    [(0, a, Rule::PlusIntroLeft), (1, b, Rule::PlusIntroRight)]
+                {
+                    expansions.push(Expansion {
+                        rule,
+                        premises: vec![moved(occurrence.child((**part).clone(), side))],
+                        choice: Some(Choice::Plus(occurrence.address.clone(), side)),
+                    });
+                }
+            }
+            Formula::OfCourse(a) if goal.linear.len() == 1 => {
+                expansions.push(Expansion {
+                    rule: Rule::OfCourseIntro,
+                    premises: vec![replaced(occurrence.child((**a).clone(), 0))],
+                    choice: None,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    // A focused atom can only meet its dual from the unrestricted zone
+    let dual = match goal.linear.last() {
+        Some(last) if goal.focused && is_atom(&last.formula) => Some(last.formula.negate()),
+        _ if goal.focused => return expansions,
+        _ => None,
+    };
+    for (i, occurrence) in goal.unrestricted.iter().enumerate() {
+        if goal.unrestricted[..i]
+            .iter()
+            .any(|o| o.formula == occurrence.formula)
+            || dual.as_ref().is_some_and(|d| *d != occurrence.formula)
+        {
+            continue;
+        }
+        let mut linear = goal.linear.clone();
+        linear.push(occurrence.clone());
+        let mut premise = goal.premise(linear);
+        premise.focused = true;
+        expansions.push(Expansion {
+            rule: Rule::Dereliction,
+            premises: vec![premise],
+            choice: None,
+        });
+    }
+
+    expansions
+}
+
+/// Whether a goal with `formula` is closed by `⊤` whatever else it holds.
+fn absorbs(formula: &Formula) -> bool {
+    match formula {
+        Formula::Top => true,
+        Formula::Par(a, b) => absorbs(a) || absorbs(b),
+        Formula::Lolli(a, b) => absorbs(&a.negate()) || absorbs(b),
+        Formula::With(a, b) => absorbs(a) && absorbs(b),
+        _ => false,
+    }
+}
+
+fn is_atom(formula: &Formula) -> bool {
+    matches!(formula, Formula::Atom(_) | Formula::NegAtom(_))
+}
+
+/// Rebuild a proof from its steps in pre-order.
+fn build(steps: &mut impl Iterator<Item = (Sequent, Rule, usize)>) -> Proof {
+    let (conclusion, rule, count) = steps.next().expect("a step for every premise");
+    Proof {
+        conclusion,
+        rule,
+        premises: (0..count).map(|_| build(steps)).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify_proof;
+    use lolli_core::TwoSidedSequent;
+    use std::time::{Duration, Instant};
+
+    fn atom(name: &str) -> Formula {
+        Formula::atom(name)
+    }
+
+    fn proofs(
+        prover: &mut Prover,
+        antecedent: Vec<Formula>,
+        succedent: Vec<Formula>,
+    ) -> Proofs<'_> {
+        let seq = TwoSidedSequent::new(antecedent, succedent);
+        prover.proofs(&seq.to_one_sided())
+    }
+
+    #[test]
+    fn test_projections() {
+        let mut prover = Prover::new(20);
+        let all: Vec<Proof> = proofs(
+            &mut prover,
+            vec![Formula::with(atom("A"), atom("A"))],
+            vec![atom("A")],
+        )
+        .collect();
+        assert_eq!(all.len(), 2);
+        assert!(all.iter().all(|p| verify_proof(p).is_ok()));
+    }
+
+    #[test]
+    fn test_up_to_permutation() {
+        let mut prover = Prover::new(20);
+        // The two ⊕ rules can go in either order, before or after the ⊗
+        let hypotheses = || {
+            vec![
+                Formula::with(atom("A"), atom("C")),
+                Formula::with(atom("B"), atom("D")),
+            ]
+        };
+        let goal = || vec![Formula::tensor(atom("A"), atom("B"))];
+
+        let all: Vec<Proof> = proofs(&mut prover, hypotheses(), goal()).collect();
+        assert!(all.len() > 1);
+        assert!(all.iter().all(|p| verify_proof(p).is_ok()));
+        assert_eq!(
+            proofs(&mut prover, hypotheses(), goal())
+                .up_to_permutation()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_distinct_choices() {
+        let mut prover = Prover::new(20);
+        // Each copy of A & B can give the A, the other one the B
+        let copy = || Formula::with(atom("A"), atom("B"));
+        let goal = vec![Formula::tensor(atom("A"), atom("B"))];
+        let distinct: Vec<Proof> = proofs(&mut prover, vec![copy(), copy()], goal)
+            .up_to_permutation()
+            .collect();
+        assert_eq!(distinct.len(), 2);
+        assert!(distinct.iter().all(|p| verify_proof(p).is_ok()));
+
+        // Equal hypotheses are interchangeable
+        let pair = vec![Formula::tensor(atom("A"), atom("A"))];
+        assert_eq!(
+            proofs(&mut prover, vec![atom("A"), atom("A")], pair).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_exponentials() {
+        let mut prover = Prover::new(20);
+        let a = atom("A");
+        let seq = || {
+            (
+                vec![Formula::of_course(a.clone())],
+                vec![Formula::tensor(a.clone(), a.clone())],
+            )
+        };
+        let (antecedent, succedent) = seq();
+        let first = proofs(&mut prover, antecedent, succedent).next().unwrap();
+        assert!(verify_proof(&first).is_ok());
+    }
+
+    #[test]
+    fn test_unprovable() {
+        let mut prover = Prover::new(20);
+        assert_eq!(
+            proofs(&mut prover, vec![atom("A")], vec![atom("B")]).count(),
+            0
+        );
+        assert_eq!(
+            proofs(
+                &mut prover,
+                vec![atom("A")],
+                vec![Formula::tensor(atom("A"), atom("A"))]
+            )
+            .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_lazy() {
+        let mut prover = Prover::new(20);
+        // ⊤ absorbs any part of the context, so there are many proofs; only
+        // the first few are built
+        let context: Vec<Formula> = ["A", "B", "C", "D", "E", "F", "G", "H"]
+            .iter()
+            .map(|n| atom(n))
+            .collect();
+        let goal = vec![Formula::tensor(Formula::Top, Formula::Top)];
+        let some: Vec<Proof> = proofs(&mut prover, context, goal).take(3).collect();
+        assert_eq!(some.len(), 3);
+        assert!(some.iter().all(|p| verify_proof(p).is_ok()));
+    }
+
+    #[test]
+    fn test_top_heavy() {
+        // ⊤ and 0 make most ways of splitting the context provable; the
+        // proofs still come out quickly at the default depth
+        let (a, b) = (atom("A"), atom("B"));
+        let sequent = Sequent::new(vec![
+            Formula::with(Formula::par(b.negate(), Formula::One), Formula::One),
+            Formula::tensor(a.negate(), Formula::par(Formula::Top, b.negate())),
+            Formula::tensor(Formula::Top, a.negate()),
+            Formula::with(b.clone(), Formula::Bottom),
+            Formula::plus(Formula::Top, Formula::Zero),
+            Formula::Zero,
+        ]);
+        let started = Instant::now();
+        let mut prover = Prover::new(100);
+        let first = prover.proofs(&sequent).next().unwrap();
+        assert!(verify_proof(&first).is_ok());
+        let distinct: Vec<Proof> = prover
+            .proofs(&sequent)
+            .up_to_permutation()
+            .take(20)
+            .collect();
+        assert!(distinct.len() > 1);
+        assert!(distinct.iter().all(|p| verify_proof(p).is_ok()));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_large_tensor_context() {
+        // ⊢ X0, …, X69, ⊤ ⊗ ⊤ has too large a context to split every way
+        let mut linear: Vec<Formula> = (0..70).map(|i| atom(&format!("X{}", i))).collect();
+        linear.push(Formula::tensor(Formula::Top, Formula::Top));
+        let sequent = Sequent::new(linear);
+        let mut prover = Prover::new(10);
+        assert!(prover.proofs(&sequent).next().is_none());
+        let proof = prover.proofs(&sequent).up_to_permutation().next().unwrap();
+        assert!(verify_proof(&proof).is_ok());
+    }
+
+    #[test]
+    fn test_infinitely_many() {
+        // A ⊸ A can be applied any number of times; the smallest proofs
+        // come first
+        let step = Formula::of_course(Formula::lolli(atom("A"), atom("A")));
+        let first: Vec<Proof> = Prover::new(100)
+            .proofs(&TwoSidedSequent::new(vec![step, atom("A")], vec![atom("A")]).to_one_sided())
+            .up_to_permutation()
+            .take(4)
+            .collect();
+        assert_eq!(first.len(), 4);
+        assert!(first.iter().all(|p| verify_proof(p).is_ok()));
+        assert!(first.windows(2).all(|w| w[0].depth() < w[1].depth()));
+    }
+}
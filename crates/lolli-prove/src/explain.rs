@@ -0,0 +1,359 @@
+//! Diagnostics for sequents without a proof.
+//!
+//! A resource spec usually fails because some hypothesis is never used up
+//! (a leak) or because the goal needs an atom that nothing provides (a
+//! missing resource). [`Prover::explain`] looks for the smallest repair of
+//! that kind: the search may leave hypotheses unconsumed and may draw extra
+//! copies of the atoms the sequent needs, and the cheapest combination that
+//! yields a proof is reported. Alongside it comes the deepest partial proof
+//! of the sequent itself, showing where the rules get stuck.
+
+use crate::search::{absorb, can_absorb};
+use crate::{ProofOutcome, Prover};
+use lolli_core::{Formula, Proof, Rule, Sequent, TwoSidedSequent};
+
+/// Why a two-sided sequent Γ ⊢ Δ has no proof.
+#[derive(Debug, Clone)]
+pub struct Explanation {
+    /// Hypotheses that are never consumed
+    pub leaks: Vec<Formula>,
+    /// Atoms that are needed but never provided
+    pub missing: Vec<Formula>,
+    /// A proof of Γ ⊢ Δ with the leaks removed from and the missing atoms
+    /// added to Γ, or `None` if no such repair was found
+    pub repaired: Option<Proof>,
+    /// The deepest partial proof of Γ ⊢ Δ, or `None` if it is provable
+    pub partial: Option<PartialProof>,
+}
+
+/// A proof attempt that got stuck: the rules applied, with the goals no
+/// rule could take further left open.
+#[derive(Debug, Clone)]
+pub struct PartialProof {
+    /// The sequent being proved
+    pub conclusion: Sequent,
+    /// The rule applied to it, or `None` if the goal is open
+    pub rule: Option<Rule>,
+    /// The premises of the rule
+    pub premises: Vec<PartialProof>,
+}
+
+impl PartialProof {
+    /// An open goal.
+    pub fn open(conclusion: Sequent) -> Self {
+        PartialProof {
+            conclusion,
+            rule: None,
+            premises: Vec::new(),
+        }
+    }
+
+    /// The goals left open, leftmost first.
+    pub fn open_goals(&self) -> Vec<&Sequent> {
+        match &self.rule {
+            None => vec![&self.conclusion],
+            Some(_) => self
+                .premises
+                .iter()
+                .flat_map(PartialProof::open_goals)
+                .collect(),
+        }
+    }
+
+    /// The number of rules below the deepest open goal, or `None` if the
+    /// proof is complete.
+    pub fn depth(&self) -> Option<usize> {
+        match &self.rule {
+            None => Some(0),
+            Some(_) => self
+                .premises
+                .iter()
+                .filter_map(PartialProof::depth)
+                .max()
+                .map(|d| d + 1),
+        }
+    }
+}
+
+impl From<Proof> for PartialProof {
+    fn from(proof: Proof) -> Self {
+        PartialProof {
+            conclusion: proof.conclusion,
+            rule: Some(proof.rule),
+            premises: proof.premises.into_iter().map(PartialProof::from).collect(),
+        }
+    }
+}
+
+impl Explanation {
+    /// Describe the leaks and missing resources in words, one per line.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lolli_prove::Prover;
+    /// use lolli_core::{Formula, TwoSidedSequent};
+    ///
+    /// // Pool, Available, Available ⊢ Pool ⊗ Available
+    /// let seq = TwoSidedSequent::new(
+    ///     vec![Formula::atom("Pool"), Formula::atom("Available"), Formula::atom("Available")],
+    ///     vec![Formula::tensor(Formula::atom("Pool"), Formula::atom("Available"))],
+    /// );
+    /// let explanation = Prover::new(100).explain(&seq);
+    /// assert_eq!(explanation.summary(), vec!["one Available is never consumed"]);
+    /// ```
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (formula, count) in grouped(&self.leaks) {
+            lines.push(format!("{} never consumed", phrase(&formula, count)));
+        }
+        for (formula, count) in grouped(&self.missing) {
+            lines.push(format!("{} missing", phrase(&formula, count)));
+        }
+        lines
+    }
+}
+
+impl Prover {
+    /// Explain why a two-sided sequent Γ ⊢ Δ has no proof.
+    ///
+    /// Finds the fewest hypotheses to drop and atoms to add that make the
+    /// sequent provable within `max_depth`, preferring to drop a hypothesis
+    /// over adding an atom. Tensors, `1` and `!` in Γ are split up first, so
+    /// leaks are reported for their parts. A provable sequent yields an
+    /// explanation with no leaks and nothing missing.
+    ///
+    /// The partial proof applies the same focused rules as
+    /// [`Prover::proofs`], closes every premise that has a proof and follows
+    /// the others as deep as they go.
+    pub fn explain(&mut self, seq: &TwoSidedSequent) -> Explanation {
+        let one_sided = seq.to_one_sided();
+        let partial = match self.decide(&one_sided) {
+            ProofOutcome::Provable(_) => None,
+            _ => Some(self.deepest_partial(&one_sided)),
+        };
+
+        let mut goal = Sequent::new(seq.succedent.clone());
+        let mut hypotheses = Vec::new();
+        for formula in &seq.antecedent {
+            split_hypothesis(formula, &mut goal, &mut hypotheses);
+        }
+
+        let mut needed = Vec::new();
+        for formula in &goal.linear {
+            needed_atoms(formula, true, &mut needed);
+        }
+        for formula in &hypotheses {
+            needed_atoms(formula, false, &mut needed);
+        }
+
+        // The pool offers every hypothesis and, after them, one candidate
+        // hypothesis per atom occurrence that has to be matched
+        let mut pool: Vec<Formula> = hypotheses.iter().map(Formula::negate).collect();
+        pool.extend(needed.iter().map(|name| Formula::neg_atom(name.clone())));
+        let n = hypotheses.len();
+
+        let mut best: Option<((usize, usize), Proof, Vec<bool>)> = None;
+        self.for_each_outcome(&goal, &pool, |proof, used| {
+            let added = used[n..].iter().filter(|u| **u).count();
+            let dropped = if can_absorb(&proof) {
+                0
+            } else {
+                used[..n].iter().filter(|u| !**u).count()
+            };
+            let cost = (dropped + added, added);
+            if best.as_ref().is_none_or(|(c, _, _)| cost < *c) {
+                best = Some((cost, proof, used));
+            }
+        });
+
+        let Some((_, mut proof, used)) = best else {
+            return Explanation {
+                leaks: Vec::new(),
+                missing: Vec::new(),
+                repaired: None,
+                partial,
+            };
+        };
+
+        let unused: Vec<usize> = (0..n).filter(|&i| !used[i]).collect();
+        let mut leaks = Vec::new();
+        if can_absorb(&proof) {
+            let extra: Vec<Formula> = unused.iter().map(|&i| pool[i].clone()).collect();
+            if !extra.is_empty() {
+                absorb(&mut proof, &extra);
+            }
+        } else {
+            leaks.extend(unused.iter().map(|&i| hypotheses[i].clone()));
+        }
+        let missing = needed
+            .iter()
+            .zip(&used[n..])
+            .filter(|(_, u)| **u)
+            .map(|(name, _)| Formula::atom(name.clone()))
+            .collect();
+
+        Explanation {
+            leaks,
+            missing,
+            repaired: Some(proof),
+            partial,
+        }
+    }
+}
+
+/// Add a hypothesis to `goal`, or to `hypotheses` if it may be left unused.
+///
+/// Only formulas the search can consume from a pool go to `hypotheses`;
+/// the rest must be used and stay in the sequent.
+fn split_hypothesis(formula: &Formula, goal: &mut Sequent, hypotheses: &mut Vec<Formula>) {
+    match formula {
+        Formula::Tensor(a, b) => {
+            split_hypothesis(a, goal, hypotheses);
+            split_hypothesis(b, goal, hypotheses);
+        }
+        Formula::One => {}
+        Formula::OfCourse(a) => goal.unrestricted.push(a.negate()),
+        _ => {
+            let negated = formula.negate();
+            if negated.is_positive() || matches!(negated, Formula::NegAtom(_)) {
+                hypotheses.push(formula.clone());
+            } else {
+                goal.linear.push(negated);
+            }
+        }
+    }
+}
+
+/// Collect the atoms that occur positively in `formula`, negated if
+/// `positive` is false: each of them has to be matched by an axiom.
+fn needed_atoms(formula: &Formula, positive: bool, atoms: &mut Vec<String>) {
+    match formula {
+        Formula::Atom(name) if positive => atoms.push(name.clone()),
+        Formula::NegAtom(name) if !positive => atoms.push(name.clone()),
+        Formula::Lolli(a, b) => {
+            needed_atoms(a, !positive, atoms);
+            needed_atoms(b, positive, atoms);
+        }
+        Formula::Tensor(a, b) | Formula::Par(a, b) | Formula::With(a, b) | Formula::Plus(a, b) => {
+            needed_atoms(a, positive, atoms);
+            needed_atoms(b, positive, atoms);
+        }
+        Formula::OfCourse(a) | Formula::WhyNot(a) => needed_atoms(a, positive, atoms),
+        _ => {}
+    }
+}
+
+/// Count equal formulas, in order of first occurrence.
+pub(crate) fn grouped(formulas: &[Formula]) -> Vec<(Formula, usize)> {
+    let mut groups: Vec<(Formula, usize)> = Vec::new();
+    for formula in formulas {
+        match groups.iter_mut().find(|(f, _)| f == formula) {
+            Some((_, count)) => *count += 1,
+            None => groups.push((formula.clone(), 1)),
+        }
+    }
+    groups
+}
+
+/// "one A", "two (A ⊸ B)", ...
+pub(crate) fn counted(formula: &Formula, count: usize) -> String {
+    const WORDS: [&str; 10] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    ];
+    let number = WORDS
+        .get(count.wrapping_sub(1))
+        .map_or(count.to_string(), |w| w.to_string());
+    let name = match formula {
+        Formula::Atom(_) | Formula::NegAtom(_) => formula.pretty(),
+        _ => format!("({})", formula.pretty()),
+    };
+    format!("{} {}", number, name)
+}
+
+/// "one A is", "two (A ⊸ B) are", ...
+fn phrase(formula: &Formula, count: usize) -> String {
+    let verb = if count == 1 { "is" } else { "are" };
+    format!("{} {}", counted(formula, count), verb)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atom(name: &str) -> Formula {
+        Formula::atom(name)
+    }
+
+    fn explain(antecedent: Vec<Formula>, succedent: Vec<Formula>) -> Explanation {
+        Prover::new(50).explain(&TwoSidedSequent::new(antecedent, succedent))
+    }
+
+    #[test]
+    fn test_leak() {
+        let explanation = explain(
+            vec![atom("Pool"), atom("Available"), atom("Available")],
+            vec![Formula::tensor(atom("Pool"), atom("Available"))],
+        );
+        assert_eq!(explanation.leaks, vec![atom("Available")]);
+        assert!(explanation.missing.is_empty());
+        assert!(explanation.repaired.is_some());
+
+        // The tensor links Pool and one Available, and leaves the other
+        // Available with nothing to link to
+        let partial = explanation.partial.unwrap();
+        assert_eq!(partial.rule, Some(Rule::TensorIntro));
+        assert_eq!(partial.depth(), Some(1));
+        let open = partial.open_goals();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].linear.len(), 3);
+    }
+
+    #[test]
+    fn test_missing_and_leak() {
+        // Pool, InUse ⊢ Pool ⊗ Available ⊗ Available
+        let explanation = explain(
+            vec![atom("Pool"), atom("InUse")],
+            vec![Formula::tensor(
+                atom("Pool"),
+                Formula::tensor(atom("Available"), atom("Available")),
+            )],
+        );
+        assert_eq!(
+            explanation.summary(),
+            vec!["one InUse is never consumed", "two Available are missing"]
+        );
+    }
+
+    #[test]
+    fn test_missing_input() {
+        // Request ⊸ Response ⊢ Response has no Request to start from
+        let explanation = explain(
+            vec![Formula::lolli(atom("Request"), atom("Response"))],
+            vec![atom("Response")],
+        );
+        assert!(explanation.leaks.is_empty());
+        assert_eq!(explanation.missing, vec![atom("Request")]);
+    }
+
+    #[test]
+    fn test_tensor_hypothesis_split() {
+        // Pool ⊗ Available ⊗ Available ⊢ Pool ⊗ Available
+        let explanation = explain(
+            vec![Formula::tensor(
+                atom("Pool"),
+                Formula::tensor(atom("Available"), atom("Available")),
+            )],
+            vec![Formula::tensor(atom("Pool"), atom("Available"))],
+        );
+        assert_eq!(explanation.leaks, vec![atom("Available")]);
+    }
+
+    #[test]
+    fn test_provable_has_nothing_to_explain() {
+        let explanation = explain(vec![atom("A")], vec![atom("A")]);
+        assert!(explanation.summary().is_empty());
+        assert!(explanation.repaired.is_some());
+        assert!(explanation.partial.is_none());
+    }
+}
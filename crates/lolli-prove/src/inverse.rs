@@ -0,0 +1,1082 @@
+//! Inverse-method prover.
+//!
+//! Backward search has to guess how often each `!` hypothesis is used, so
+//! contraction makes it branch heavily. The inverse method works forwards
+//! instead: it starts from the axioms and applies the rules to what it has
+//! derived until it reaches the goal or nothing new follows. Only
+//! subformulas of the goal ever occur, so the search space is fixed up
+//! front.
+//!
+//! Derived sequents are read up to weakening, following Chaudhuri and
+//! Pfenning. The unrestricted zone is a set, which makes contraction
+//! implicit; a sequent proved with `⊤` carries a flag saying any linear
+//! formulas may be added; and formulas that can always be added, such as
+//! `?A` and `⊥`, are left out. A derived sequent thereby stands for all its
+//! weakenings, and only the most general ones are kept.
+
+use crate::balance::check_balance;
+use crate::{CancelHandle, ProofOutcome, ProverStats, SearchLimits, UnknownReason};
+use lolli_core::{Formula, Proof, Rule, Sequent, TwoSidedSequent};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::time::Instant;
+
+/// A forward, saturation-based prover for MALL and MELL.
+///
+/// It offers the same `prove` and `decide` methods as [`crate::Prover`].
+/// In its [`ProverStats`], `sequents_explored` counts the derived sequents
+/// kept and `cache_hits` those dropped because a kept one subsumes them.
+///
+/// # Example
+///
+/// ```
+/// use lolli_prove::InverseProver;
+/// use lolli_core::{Formula, TwoSidedSequent};
+///
+/// // !A ⊢ A ⊗ A ⊗ A uses the hypothesis three times
+/// let a = Formula::atom("A");
+/// let seq = TwoSidedSequent::new(
+///     vec![Formula::of_course(a.clone())],
+///     vec![Formula::tensor(a.clone(), Formula::tensor(a.clone(), a))],
+/// );
+/// assert!(InverseProver::new(2).prove_two_sided(&seq).is_some());
+/// ```
+pub struct InverseProver {
+    /// How many copies beyond its occurrences in the goal a derived sequent
+    /// may hold of a formula under `?`
+    pub max_copies: usize,
+    /// Reject sequents that fail the atom-count balance check, see [`check_balance`]
+    pub use_balance: bool,
+    /// Bounds on the work a single search may do
+    pub limits: SearchLimits,
+    /// Set from outside to stop the search
+    cancel: CancelHandle,
+    /// Statistics
+    stats: ProverStats,
+}
+
+impl InverseProver {
+    /// Create a new prover allowing `max_copies` extra copies of formulas under `?`.
+    pub fn new(max_copies: usize) -> Self {
+        Self {
+            max_copies,
+            use_balance: true,
+            limits: SearchLimits::default(),
+            cancel: CancelHandle::default(),
+            stats: ProverStats::default(),
+        }
+    }
+
+    /// Get proof search statistics.
+    pub fn stats(&self) -> &ProverStats {
+        &self.stats
+    }
+
+    /// A handle that stops this prover's searches when cancelled.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+
+    /// Reset statistics and lift any cancellation.
+    pub fn reset(&mut self) {
+        self.stats = ProverStats::default();
+        self.cancel.clear();
+    }
+
+    /// Prove a two-sided sequent Γ ⊢ Δ.
+    pub fn prove_two_sided(&mut self, seq: &TwoSidedSequent) -> Option<Proof> {
+        self.prove(&seq.to_one_sided())
+    }
+
+    /// Prove a one-sided sequent ⊢ Γ.
+    pub fn prove(&mut self, seq: &Sequent) -> Option<Proof> {
+        self.decide(seq).into_proof()
+    }
+
+    /// Decide a two-sided sequent Γ ⊢ Δ.
+    pub fn decide_two_sided(&mut self, seq: &TwoSidedSequent) -> ProofOutcome {
+        self.decide(&seq.to_one_sided())
+    }
+
+    /// Decide a one-sided sequent ⊢ Γ.
+    ///
+    /// Saturation without a proof is a refutation, unless some derived
+    /// sequent was dropped for holding more than `max_copies` extra copies
+    /// of a formula; then the outcome is [`UnknownReason::Exponentials`].
+    pub fn decide(&mut self, seq: &Sequent) -> ProofOutcome {
+        self.stats = ProverStats::default();
+        let started = Instant::now();
+
+        if self.use_balance && check_balance(seq).is_err() {
+            self.stats.balance_prunes += 1;
+            return ProofOutcome::Refuted;
+        }
+
+        let mut state = Saturation::new(seq, self.max_copies);
+        let outcome = loop {
+            if let Some(reason) = self.exceeded_limit(&state, started) {
+                break ProofOutcome::Unknown(reason);
+            }
+            if let Some(proof) = state.proof() {
+                break ProofOutcome::Provable(proof);
+            }
+            match state.queue.pop() {
+                Some(Reverse((_, given))) => state.process(given),
+                None if state.truncated => {
+                    break ProofOutcome::Unknown(UnknownReason::Exponentials)
+                }
+                None => break ProofOutcome::Refuted,
+            }
+        };
+
+        self.stats.sequents_explored = state.facts.len();
+        self.stats.cache_hits = state.subsumed;
+        self.stats.elapsed = started.elapsed();
+        outcome
+    }
+
+    /// The limit the search has run into, if any.
+    fn exceeded_limit(&self, state: &Saturation, started: Instant) -> Option<UnknownReason> {
+        let limits = &self.limits;
+        if self.cancel.is_cancelled() {
+            Some(UnknownReason::Cancelled)
+        } else if limits
+            .max_sequents
+            .is_some_and(|max| state.facts.len() >= max)
+        {
+            Some(UnknownReason::SequentLimit)
+        } else if limits
+            .max_cache_entries
+            .is_some_and(|max| state.facts.len() >= max)
+        {
+            Some(UnknownReason::CacheLimit)
+        } else if limits
+            .timeout
+            .is_some_and(|timeout| started.elapsed() >= timeout)
+        {
+            Some(UnknownReason::Timeout)
+        } else {
+            None
+        }
+    }
+}
+
+/// The outermost connective of a subformula, with the ids of its parts.
+#[derive(Debug, Clone, Copy)]
+enum Shape {
+    /// An atom, a negated atom or `0`
+    Leaf,
+    One,
+    Bottom,
+    Top,
+    Tensor(usize, usize),
+    /// `A ⅋ B`, or `A ⊸ B` with parts `A⊥` and `B`
+    Par(usize, usize),
+    With(usize, usize),
+    Plus(usize, usize),
+    OfCourse(usize),
+    WhyNot(usize),
+}
+
+/// A derived sequent ⊢ ?U, L, standing for all its weakenings.
+#[derive(Debug, Clone)]
+struct Fact {
+    /// Unrestricted formulas, sorted and without duplicates
+    unrestricted: Vec<usize>,
+    /// Linear formulas, sorted
+    linear: Vec<usize>,
+    /// Proved with `⊤`, so any linear formulas may be added
+    weak: bool,
+    /// How the sequent was derived
+    origin: Origin,
+}
+
+/// The last rule of a derivation, with the facts it used.
+#[derive(Debug, Clone, Copy)]
+enum Origin {
+    Axiom,
+    One,
+    Top,
+    Par {
+        principal: usize,
+        premise: usize,
+    },
+    Plus {
+        principal: usize,
+        left: bool,
+        premise: usize,
+    },
+    OfCourse {
+        principal: usize,
+        premise: usize,
+    },
+    WhyNot {
+        principal: usize,
+        premise: usize,
+    },
+    Dereliction {
+        formula: usize,
+        premise: usize,
+    },
+    Tensor {
+        principal: usize,
+        left: usize,
+        right: usize,
+    },
+    With {
+        principal: usize,
+        left: usize,
+        right: usize,
+    },
+}
+
+/// The state of a saturation run. Formulas are referred to by their index
+/// in `formulas`; parts always come before the formulas containing them.
+struct Saturation {
+    formulas: Vec<Formula>,
+    ids: HashMap<Formula, usize>,
+    shapes: Vec<Shape>,
+    /// Whether ⊢ Γ, F follows from ⊢ Γ for every Γ
+    weakenable: Vec<bool>,
+    /// Whether a linear formula may be moved to the unrestricted zone
+    derelictable: Vec<bool>,
+    /// Occurs under a `?` or in the unrestricted zone of the goal
+    scoped: Vec<bool>,
+    /// The most copies of a formula a useful sequent can hold
+    bound: Vec<usize>,
+    /// The goal, in its original order
+    goal_unrestricted: Vec<usize>,
+    goal_linear: Vec<usize>,
+    /// The goal's unrestricted formulas as a sorted set, and its sorted
+    /// linear formulas
+    goal_sorted: (Vec<usize>, Vec<usize>),
+    facts: Vec<Fact>,
+    /// Facts by linear part, for subsumption
+    by_linear: HashMap<Vec<usize>, Vec<usize>>,
+    /// Facts not yet processed, smallest first
+    queue: BinaryHeap<Reverse<(usize, usize)>>,
+    /// Facts processed so far
+    active: Vec<usize>,
+    /// Processed facts by the linear formulas they contain
+    containing: Vec<Vec<usize>>,
+    /// Processed facts proved with `⊤`
+    weak_active: Vec<usize>,
+    /// Whether a fact was dropped for exceeding the bound of a scoped formula
+    truncated: bool,
+    /// Number of facts dropped as subsumed
+    subsumed: usize,
+    /// A fact covering the goal
+    found: Option<usize>,
+}
+
+impl Saturation {
+    fn new(goal: &Sequent, max_copies: usize) -> Self {
+        let mut state = Saturation {
+            formulas: Vec::new(),
+            ids: HashMap::new(),
+            shapes: Vec::new(),
+            weakenable: Vec::new(),
+            derelictable: Vec::new(),
+            scoped: Vec::new(),
+            bound: Vec::new(),
+            goal_unrestricted: Vec::new(),
+            goal_linear: Vec::new(),
+            goal_sorted: (Vec::new(), Vec::new()),
+            facts: Vec::new(),
+            by_linear: HashMap::new(),
+            queue: BinaryHeap::new(),
+            active: Vec::new(),
+            containing: Vec::new(),
+            weak_active: Vec::new(),
+            truncated: false,
+            subsumed: 0,
+            found: None,
+        };
+
+        state.goal_unrestricted = goal.unrestricted.iter().map(|f| state.intern(f)).collect();
+        state.goal_linear = goal.linear.iter().map(|f| state.intern(f)).collect();
+        let mut unrestricted = state.goal_unrestricted.clone();
+        unrestricted.sort_unstable();
+        unrestricted.dedup();
+        let mut linear = state.goal_linear.clone();
+        linear.sort_unstable();
+        state.goal_sorted = (unrestricted, linear);
+
+        let n = state.formulas.len();
+        state.containing = vec![Vec::new(); n];
+
+        // Count occurrences to bound how many copies a sequent may hold
+        let mut occurrences = vec![0; n];
+        for &f in &state.goal_linear.clone() {
+            state.count(f, false, &mut occurrences);
+        }
+        for &f in &state.goal_unrestricted.clone() {
+            state.count(f, true, &mut occurrences);
+            state.derelictable[f] = true;
+        }
+        state.bound = (0..n)
+            .map(|f| occurrences[f] + if state.scoped[f] { max_copies } else { 0 })
+            .collect();
+
+        // The axioms
+        for f in 0..n {
+            match &state.formulas[f] {
+                Formula::Atom(name) => {
+                    if let Some(&dual) = state.ids.get(&Formula::neg_atom(name.clone())) {
+                        state.add(Vec::new(), vec![f, dual], false, Origin::Axiom);
+                    }
+                }
+                Formula::One => state.add(Vec::new(), vec![f], false, Origin::One),
+                Formula::Top => state.add(Vec::new(), vec![f], true, Origin::Top),
+                _ => {}
+            }
+        }
+        state
+    }
+
+    /// The id of `formula`, adding it and its parts if they are new.
+    fn intern(&mut self, formula: &Formula) -> usize {
+        if let Some(&id) = self.ids.get(formula) {
+            return id;
+        }
+
+        let shape = match formula {
+            Formula::Atom(_) | Formula::NegAtom(_) | Formula::Zero => Shape::Leaf,
+            Formula::One => Shape::One,
+            Formula::Bottom => Shape::Bottom,
+            Formula::Top => Shape::Top,
+            Formula::Tensor(a, b) => Shape::Tensor(self.intern(a), self.intern(b)),
+            Formula::Par(a, b) => Shape::Par(self.intern(a), self.intern(b)),
+            Formula::Lolli(a, b) => Shape::Par(self.intern(&a.negate()), self.intern(b)),
+            Formula::With(a, b) => Shape::With(self.intern(a), self.intern(b)),
+            Formula::Plus(a, b) => Shape::Plus(self.intern(a), self.intern(b)),
+            Formula::OfCourse(a) => Shape::OfCourse(self.intern(a)),
+            Formula::WhyNot(a) => {
+                let a = self.intern(a);
+                self.derelictable[a] = true;
+                Shape::WhyNot(a)
+            }
+        };
+        let weakenable = match shape {
+            Shape::Bottom | Shape::WhyNot(_) => true,
+            Shape::Par(a, b) | Shape::With(a, b) => self.weakenable[a] && self.weakenable[b],
+            Shape::Plus(a, b) => self.weakenable[a] || self.weakenable[b],
+            _ => false,
+        };
+
+        let id = self.formulas.len();
+        self.formulas.push(formula.clone());
+        self.ids.insert(formula.clone(), id);
+        self.shapes.push(shape);
+        self.weakenable.push(weakenable);
+        self.derelictable.push(false);
+        self.scoped.push(false);
+        id
+    }
+
+    /// Count the occurrences of `f` and its parts.
+    fn count(&mut self, f: usize, scoped: bool, occurrences: &mut [usize]) {
+        occurrences[f] += 1;
+        self.scoped[f] |= scoped;
+        match self.shapes[f] {
+            Shape::Tensor(a, b) | Shape::Par(a, b) | Shape::With(a, b) | Shape::Plus(a, b) => {
+                self.count(a, scoped, occurrences);
+                self.count(b, scoped, occurrences);
+            }
+            Shape::OfCourse(a) => self.count(a, scoped, occurrences),
+            Shape::WhyNot(a) => self.count(a, true, occurrences),
+            _ => {}
+        }
+    }
+
+    /// Keep a derived sequent unless it is useless or subsumed.
+    fn add(
+        &mut self,
+        unrestricted: Vec<usize>,
+        mut linear: Vec<usize>,
+        weak: bool,
+        origin: Origin,
+    ) {
+        linear.sort_unstable();
+        for run in linear.chunk_by(|a, b| a == b) {
+            if run.len() > self.bound[run[0]] {
+                self.truncated |= self.scoped[run[0]];
+                return;
+            }
+        }
+
+        let fact = Fact {
+            unrestricted,
+            linear,
+            weak,
+            origin,
+        };
+        if self.is_subsumed(&fact) {
+            self.subsumed += 1;
+            return;
+        }
+
+        let id = self.facts.len();
+        let (unrestricted, linear) = &self.goal_sorted;
+        if self.found.is_none() && self.covers(&fact, unrestricted, linear) {
+            self.found = Some(id);
+        }
+
+        self.by_linear
+            .entry(fact.linear.clone())
+            .or_default()
+            .push(id);
+        self.queue
+            .push(Reverse((fact.linear.len() + fact.unrestricted.len(), id)));
+        self.facts.push(fact);
+    }
+
+    /// Returns true if `fact` stands for ⊢ ?U, L, with `unrestricted` and
+    /// `linear` sorted.
+    fn covers(&self, fact: &Fact, unrestricted: &[usize], linear: &[usize]) -> bool {
+        if difference(unrestricted, &fact.unrestricted).is_none() {
+            return false;
+        }
+        match difference(linear, &fact.linear) {
+            Some(extra) => fact.weak || extra.iter().all(|&f| self.weakenable[f]),
+            None => false,
+        }
+    }
+
+    /// Returns true if a kept fact stands for everything `fact` stands for.
+    fn is_subsumed(&self, fact: &Fact) -> bool {
+        let subsumes = |&k: &usize| {
+            let known = &self.facts[k];
+            (known.weak || !fact.weak) && self.covers(known, &fact.unrestricted, &fact.linear)
+        };
+
+        // A subsuming fact has a linear part contained in this one
+        if fact.linear.len() > 12 {
+            return (0..self.facts.len()).any(|k| subsumes(&k));
+        }
+        sub_multisets(&fact.linear)
+            .iter()
+            .filter_map(|linear| self.by_linear.get(linear))
+            .any(|known| known.iter().any(subsumes))
+    }
+
+    /// Apply every rule with `given` and the processed facts as premises.
+    fn process(&mut self, given: usize) {
+        let g = self.facts[given].clone();
+        self.active.push(given);
+        let mut distinct = g.linear.clone();
+        distinct.dedup();
+        for &f in &distinct {
+            self.containing[f].push(given);
+        }
+        if g.weak {
+            self.weak_active.push(given);
+        }
+
+        for &f in &distinct {
+            if self.derelictable[f] {
+                let mut linear = g.linear.clone();
+                remove_one(&mut linear, f);
+                let origin = Origin::Dereliction {
+                    formula: f,
+                    premise: given,
+                };
+                self.add(union(&g.unrestricted, &[f]), linear, g.weak, origin);
+            }
+        }
+
+        for principal in 0..self.formulas.len() {
+            match self.shapes[principal] {
+                Shape::Par(a, b) => {
+                    let mut linear = g.linear.clone();
+                    let mut present = 0;
+                    for part in [a, b] {
+                        if remove_one(&mut linear, part) {
+                            present += 1;
+                        } else if !g.weak && !self.weakenable[part] {
+                            present = 0;
+                            break;
+                        }
+                    }
+                    // With neither part present the premise already covers it
+                    if present > 0 {
+                        linear.push(principal);
+                        let origin = Origin::Par {
+                            principal,
+                            premise: given,
+                        };
+                        self.add(g.unrestricted.clone(), linear, g.weak, origin);
+                    }
+                }
+                Shape::Plus(a, b) => {
+                    for (part, left) in [(a, true), (b, false)] {
+                        let mut linear = g.linear.clone();
+                        if remove_one(&mut linear, part) {
+                            linear.push(principal);
+                            let origin = Origin::Plus {
+                                principal,
+                                left,
+                                premise: given,
+                            };
+                            self.add(g.unrestricted.clone(), linear, g.weak, origin);
+                        }
+                    }
+                }
+                Shape::OfCourse(a) => {
+                    let alone = g.linear == [a];
+                    let implied = g.linear.is_empty() && !g.weak && self.weakenable[a];
+                    if alone || implied {
+                        let origin = Origin::OfCourse {
+                            principal,
+                            premise: given,
+                        };
+                        self.add(g.unrestricted.clone(), vec![principal], false, origin);
+                    }
+                }
+                Shape::WhyNot(a) => {
+                    // Without `a` in the zone this is a weakening, which is implicit
+                    if let Ok(pos) = g.unrestricted.binary_search(&a) {
+                        let mut unrestricted = g.unrestricted.clone();
+                        unrestricted.remove(pos);
+                        let mut linear = g.linear.clone();
+                        linear.push(principal);
+                        let origin = Origin::WhyNot {
+                            principal,
+                            premise: given,
+                        };
+                        self.add(unrestricted, linear, g.weak, origin);
+                    }
+                }
+                Shape::Tensor(a, b) => {
+                    if self.tensor_part(&g, a).is_some() {
+                        for right in self.partners(b, false) {
+                            self.tensor(principal, given, right);
+                        }
+                    }
+                    if self.tensor_part(&g, b).is_some() {
+                        for left in self.partners(a, false) {
+                            self.tensor(principal, left, given);
+                        }
+                    }
+                }
+                Shape::With(a, b) => {
+                    if self.with_part(&g, a).is_some() {
+                        for right in self.partners(b, true) {
+                            self.with(principal, given, right);
+                        }
+                    }
+                    if self.with_part(&g, b).is_some() {
+                        for left in self.partners(a, true) {
+                            self.with(principal, left, given);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Processed facts that may serve as the premise for `part`.
+    fn partners(&self, part: usize, weak: bool) -> Vec<usize> {
+        if self.weakenable[part] {
+            self.active.clone()
+        } else if weak {
+            let mut partners = self.containing[part].clone();
+            partners.extend(&self.weak_active);
+            partners
+        } else {
+            self.containing[part].clone()
+        }
+    }
+
+    /// The rest of `fact` as a premise of a tensor with part `part`.
+    ///
+    /// A weak fact without `part` is skipped: it covers the conclusion
+    /// already.
+    fn tensor_part(&self, fact: &Fact, part: usize) -> Option<Vec<usize>> {
+        let mut rest = fact.linear.clone();
+        if remove_one(&mut rest, part) || (!fact.weak && self.weakenable[part]) {
+            Some(rest)
+        } else {
+            None
+        }
+    }
+
+    /// The rest of `fact` as a premise of a with with part `part`.
+    fn with_part(&self, fact: &Fact, part: usize) -> Option<Vec<usize>> {
+        let mut rest = fact.linear.clone();
+        if remove_one(&mut rest, part) || fact.weak || self.weakenable[part] {
+            Some(rest)
+        } else {
+            None
+        }
+    }
+
+    fn tensor(&mut self, principal: usize, left: usize, right: usize) {
+        let Shape::Tensor(a, b) = self.shapes[principal] else {
+            return;
+        };
+        let (p, q) = (&self.facts[left], &self.facts[right]);
+        let (Some(mut linear), Some(rest)) = (self.tensor_part(p, a), self.tensor_part(q, b))
+        else {
+            return;
+        };
+        linear.extend(rest);
+        linear.push(principal);
+        let unrestricted = union(&p.unrestricted, &q.unrestricted);
+        let weak = p.weak || q.weak;
+        let origin = Origin::Tensor {
+            principal,
+            left,
+            right,
+        };
+        self.add(unrestricted, linear, weak, origin);
+    }
+
+    fn with(&mut self, principal: usize, left: usize, right: usize) {
+        let Shape::With(a, b) = self.shapes[principal] else {
+            return;
+        };
+        let (p, q) = (&self.facts[left], &self.facts[right]);
+        let (Some(rest_p), Some(rest_q)) = (self.with_part(p, a), self.with_part(q, b)) else {
+            return;
+        };
+
+        // Both premises share the smallest context containing both rests
+        let mut linear = max_union(&rest_p, &rest_q);
+        for (fact, rest) in [(p, &rest_p), (q, &rest_q)] {
+            let extra = difference(&linear, rest).unwrap_or_default();
+            if !fact.weak && !extra.iter().all(|&f| self.weakenable[f]) {
+                return;
+            }
+        }
+        linear.push(principal);
+        let unrestricted = union(&p.unrestricted, &q.unrestricted);
+        let weak = p.weak && q.weak;
+        let origin = Origin::With {
+            principal,
+            left,
+            right,
+        };
+        self.add(unrestricted, linear, weak, origin);
+    }
+
+    /// The proof of the goal, once a fact covers it.
+    fn proof(&self) -> Option<Proof> {
+        let found = self.found?;
+        Some(self.build(
+            found,
+            self.goal_unrestricted.clone(),
+            self.goal_linear.clone(),
+        ))
+    }
+
+    /// A proof of ⊢ ?U, L from the derivation of `fact`, which covers it.
+    fn build(&self, fact: usize, unrestricted: Vec<usize>, linear: Vec<usize>) -> Proof {
+        let f = &self.facts[fact];
+        if !f.weak {
+            let mut sorted = linear.clone();
+            sorted.sort_unstable();
+            let extra = difference(&sorted, &f.linear).unwrap_or_default();
+            if let Some(&formula) = extra.first() {
+                return self.weaken(fact, formula, unrestricted, linear);
+            }
+        }
+
+        let mut rest = linear.clone();
+        let (rule, premises) = match f.origin {
+            Origin::Axiom => (Rule::Axiom, vec![]),
+            Origin::One => (Rule::OneIntro, vec![]),
+            Origin::Top => (Rule::TopIntro, vec![]),
+            Origin::Par { principal, premise } => {
+                let Shape::Par(a, b) = self.shapes[principal] else {
+                    unreachable!("par rule for a non-par formula");
+                };
+                remove_one(&mut rest, principal);
+                rest.extend([a, b]);
+                let premise = self.build(premise, unrestricted.clone(), rest);
+                (Rule::ParIntro, vec![premise])
+            }
+            Origin::Plus {
+                principal,
+                left,
+                premise,
+            } => {
+                let Shape::Plus(a, b) = self.shapes[principal] else {
+                    unreachable!("plus rule for a non-plus formula");
+                };
+                remove_one(&mut rest, principal);
+                rest.push(if left { a } else { b });
+                let premise = self.build(premise, unrestricted.clone(), rest);
+                let rule = if left {
+                    Rule::PlusIntroLeft
+                } else {
+                    Rule::PlusIntroRight
+                };
+                (rule, vec![premise])
+            }
+            Origin::OfCourse { principal, premise } => {
+                let Shape::OfCourse(a) = self.shapes[principal] else {
+                    unreachable!("promotion for a non-! formula");
+                };
+                let premise = self.build(premise, unrestricted.clone(), vec![a]);
+                (Rule::OfCourseIntro, vec![premise])
+            }
+            Origin::WhyNot { principal, premise } => {
+                let Shape::WhyNot(a) = self.shapes[principal] else {
+                    unreachable!("? rule for a non-? formula");
+                };
+                remove_one(&mut rest, principal);
+                let premise = self.build(premise, with_added(&unrestricted, a), rest);
+                (Rule::WhyNotIntro, vec![premise])
+            }
+            Origin::Dereliction { formula, premise } => {
+                rest.push(formula);
+                let premise = self.build(premise, unrestricted.clone(), rest);
+                (Rule::Dereliction, vec![premise])
+            }
+            Origin::Tensor {
+                principal,
+                left,
+                right,
+            } => {
+                let Shape::Tensor(a, b) = self.shapes[principal] else {
+                    unreachable!("tensor rule for a non-tensor formula");
+                };
+                remove_one(&mut rest, principal);
+                let (p, q) = (&self.facts[left], &self.facts[right]);
+                let mut need_p = p.linear.clone();
+                remove_one(&mut need_p, a);
+                let mut need_q = q.linear.clone();
+                remove_one(&mut need_q, b);
+
+                // Each premise gets its own context; what neither needs goes
+                // to one proved with ⊤
+                let (mut linear_p, mut linear_q) = (Vec::new(), Vec::new());
+                for formula in rest {
+                    if remove_one(&mut need_p, formula) {
+                        linear_p.push(formula);
+                    } else if remove_one(&mut need_q, formula) || !p.weak {
+                        linear_q.push(formula);
+                    } else {
+                        linear_p.push(formula);
+                    }
+                }
+                linear_p.push(a);
+                linear_q.push(b);
+                let premises = vec![
+                    self.build(left, unrestricted.clone(), linear_p),
+                    self.build(right, unrestricted.clone(), linear_q),
+                ];
+                (Rule::TensorIntro, premises)
+            }
+            Origin::With {
+                principal,
+                left,
+                right,
+            } => {
+                let Shape::With(a, b) = self.shapes[principal] else {
+                    unreachable!("with rule for a non-with formula");
+                };
+                remove_one(&mut rest, principal);
+                let mut linear_p = rest.clone();
+                linear_p.push(a);
+                rest.push(b);
+                let premises = vec![
+                    self.build(left, unrestricted.clone(), linear_p),
+                    self.build(right, unrestricted.clone(), rest),
+                ];
+                (Rule::WithIntro, premises)
+            }
+        };
+
+        Proof {
+            conclusion: self.sequent(&unrestricted, &linear),
+            rule,
+            premises,
+        }
+    }
+
+    /// A proof of ⊢ ?U, L that introduces `formula`, which `fact` leaves
+    /// out, without using it.
+    fn weaken(
+        &self,
+        fact: usize,
+        formula: usize,
+        unrestricted: Vec<usize>,
+        linear: Vec<usize>,
+    ) -> Proof {
+        let mut rest = linear.clone();
+        remove_one(&mut rest, formula);
+        let extended = |part: usize| {
+            let mut extended = rest.clone();
+            extended.push(part);
+            extended
+        };
+
+        let (rule, premises) = match self.shapes[formula] {
+            Shape::WhyNot(a) => {
+                let premise = self.build(fact, with_added(&unrestricted, a), rest.clone());
+                (Rule::WhyNotIntro, vec![premise])
+            }
+            Shape::Bottom => {
+                let premise = self.build(fact, unrestricted.clone(), rest.clone());
+                (Rule::BottomIntro, vec![premise])
+            }
+            Shape::Par(a, b) => {
+                let mut both = extended(a);
+                both.push(b);
+                let premise = self.build(fact, unrestricted.clone(), both);
+                (Rule::ParIntro, vec![premise])
+            }
+            Shape::With(a, b) => {
+                let premises = vec![
+                    self.build(fact, unrestricted.clone(), extended(a)),
+                    self.build(fact, unrestricted.clone(), extended(b)),
+                ];
+                (Rule::WithIntro, premises)
+            }
+            Shape::Plus(a, _) if self.weakenable[a] => {
+                let premise = self.build(fact, unrestricted.clone(), extended(a));
+                (Rule::PlusIntroLeft, vec![premise])
+            }
+            Shape::Plus(_, b) => {
+                let premise = self.build(fact, unrestricted.clone(), extended(b));
+                (Rule::PlusIntroRight, vec![premise])
+            }
+            _ => unreachable!("only weakenable formulas are left out"),
+        };
+
+        Proof {
+            conclusion: self.sequent(&unrestricted, &linear),
+            rule,
+            premises,
+        }
+    }
+
+    fn sequent(&self, unrestricted: &[usize], linear: &[usize]) -> Sequent {
+        Sequent {
+            linear: linear.iter().map(|&f| self.formulas[f].clone()).collect(),
+            unrestricted: unrestricted
+                .iter()
+                .map(|&f| self.formulas[f].clone())
+                .collect(),
+            focus: None,
+        }
+    }
+}
+
+/// Remove one copy of `x`; returns false if there is none.
+fn remove_one(items: &mut Vec<usize>, x: usize) -> bool {
+    match items.iter().position(|&y| y == x) {
+        Some(pos) => {
+            items.remove(pos);
+            true
+        }
+        None => false,
+    }
+}
+
+/// `items` with `x` added unless it is already there.
+fn with_added(items: &[usize], x: usize) -> Vec<usize> {
+    let mut items = items.to_vec();
+    if !items.contains(&x) {
+        items.push(x);
+    }
+    items
+}
+
+/// The multiset `big` minus `small`, if `small` is contained in it. Both
+/// must be sorted.
+fn difference(big: &[usize], small: &[usize]) -> Option<Vec<usize>> {
+    let mut rest = Vec::new();
+    let mut small = small.iter().peekable();
+    for &x in big {
+        match small.peek() {
+            Some(&&y) if y == x => {
+                small.next();
+            }
+            Some(&&y) if y < x => return None,
+            _ => rest.push(x),
+        }
+    }
+    small.next().is_none().then_some(rest)
+}
+
+/// The union of two sorted sets.
+fn union(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let mut items: Vec<usize> = a.iter().chain(b).copied().collect();
+    items.sort_unstable();
+    items.dedup();
+    items
+}
+
+/// The smallest sorted multiset containing both sorted multisets.
+fn max_union(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let mut missing = b.to_vec();
+    for &x in a {
+        remove_one(&mut missing, x);
+    }
+    let mut items = a.to_vec();
+    items.extend(missing);
+    items.sort_unstable();
+    items
+}
+
+/// Every distinct sub-multiset of a sorted multiset, each sorted.
+fn sub_multisets(items: &[usize]) -> Vec<Vec<usize>> {
+    let mut subs = vec![Vec::new()];
+    for run in items.chunk_by(|a, b| a == b) {
+        let mut next = Vec::with_capacity(subs.len() * (run.len() + 1));
+        for sub in &subs {
+            for n in 0..=run.len() {
+                let mut extended = sub.clone();
+                extended.extend(&run[..n]);
+                next.push(extended);
+            }
+        }
+        subs = next;
+    }
+    subs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{verify_proof, Prover};
+
+    fn atom(name: &str) -> Formula {
+        Formula::atom(name)
+    }
+
+    fn decide(antecedent: Vec<Formula>, succedent: Vec<Formula>) -> ProofOutcome {
+        let seq = TwoSidedSequent::new(antecedent, succedent);
+        let outcome = InverseProver::new(2).decide_two_sided(&seq);
+        if let Some(proof) = outcome.proof() {
+            assert!(verify_proof(proof).is_ok());
+            assert_eq!(proof.conclusion.linear, seq.to_one_sided().linear);
+        }
+        outcome
+    }
+
+    #[test]
+    fn test_agrees_with_backward_search() {
+        let sequents = [
+            (
+                vec![atom("A"), atom("B")],
+                vec![Formula::tensor(atom("A"), atom("B"))],
+            ),
+            (vec![atom("A")], vec![Formula::tensor(atom("A"), atom("A"))]),
+            (vec![Formula::with(atom("A"), atom("B"))], vec![atom("B")]),
+            (
+                vec![Formula::with(atom("A"), atom("B"))],
+                vec![Formula::tensor(atom("A"), atom("B"))],
+            ),
+            (
+                vec![Formula::plus(atom("A"), atom("B"))],
+                vec![Formula::plus(atom("B"), atom("A"))],
+            ),
+            (
+                vec![atom("A"), Formula::lolli(atom("A"), atom("B"))],
+                vec![atom("B")],
+            ),
+            (
+                vec![atom("A"), atom("B")],
+                vec![Formula::tensor(atom("A"), Formula::Top)],
+            ),
+            (vec![Formula::Zero, atom("A")], vec![atom("B")]),
+        ];
+        for (antecedent, succedent) in sequents {
+            let seq = TwoSidedSequent::new(antecedent.clone(), succedent.clone());
+            let expected = Prover::new(50).decide_two_sided(&seq);
+            let outcome = decide(antecedent, succedent);
+            assert_eq!(
+                outcome.is_provable(),
+                expected.is_provable(),
+                "{}",
+                seq.pretty()
+            );
+            assert_eq!(
+                outcome.is_refuted(),
+                expected.is_refuted(),
+                "{}",
+                seq.pretty()
+            );
+        }
+    }
+
+    #[test]
+    fn test_contraction_is_implicit() {
+        // !A ⊢ A ⊗ A ⊗ A
+        let outcome = decide(
+            vec![Formula::of_course(atom("A"))],
+            vec![Formula::tensor(
+                atom("A"),
+                Formula::tensor(atom("A"), atom("A")),
+            )],
+        );
+        assert!(outcome.is_provable());
+    }
+
+    #[test]
+    fn test_exponential_specs() {
+        let bang_lolli = |a: &str, b: &str| Formula::of_course(Formula::lolli(atom(a), atom(b)));
+
+        // Nothing produces an A
+        let outcome = decide(
+            vec![bang_lolli("A", "B"), bang_lolli("B", "C")],
+            vec![atom("A")],
+        );
+        assert!(outcome.is_refuted());
+
+        // With a B to start from, the rules turn it into an A
+        let outcome = decide(
+            vec![atom("B"), bang_lolli("A", "B"), bang_lolli("B", "A")],
+            vec![atom("A")],
+        );
+        assert!(outcome.is_provable());
+
+        // Backward search spends seconds on this one; saturation gives up
+        // quickly once the copies of A⊥ exceed the bound
+        let outcome = decide(
+            vec![
+                bang_lolli("A", "B"),
+                bang_lolli("B", "A"),
+                Formula::of_course(atom("C")),
+                Formula::of_course(atom("D")),
+                Formula::of_course(atom("E")),
+            ],
+            vec![atom("A")],
+        );
+        assert!(matches!(
+            outcome,
+            ProofOutcome::Unknown(UnknownReason::Exponentials)
+        ));
+    }
+
+    #[test]
+    fn test_weakenable_formulas_left_out() {
+        // ⊢ ?A, ⊥ ⅋ ?B, 1 needs neither ?A nor ⊥ ⅋ ?B
+        let seq = Sequent::new(vec![
+            Formula::why_not(atom("A")),
+            Formula::par(Formula::Bottom, Formula::why_not(atom("B"))),
+            Formula::One,
+        ]);
+        let proof = InverseProver::new(2).prove(&seq).unwrap();
+        assert!(verify_proof(&proof).is_ok());
+        assert_eq!(proof.conclusion.linear, seq.linear);
+    }
+
+    #[test]
+    fn test_cancelled() {
+        let mut prover = InverseProver::new(2);
+        prover.cancel_handle().cancel();
+        let seq = Sequent::new(vec![atom("A"), atom("A").negate()]);
+        assert!(matches!(
+            prover.decide(&seq),
+            ProofOutcome::Unknown(UnknownReason::Cancelled)
+        ));
+        prover.reset();
+        assert!(prover.decide(&seq).is_provable());
+    }
+}
@@ -29,13 +29,63 @@
 //! let result = prover.prove_two_sided(&seq);
 //! assert!(result.is_some());
 //! ```
+//!
+//! ## Refutations
+//!
+//! [`Prover::decide`] returns a [`ProofOutcome`] that separates "no proof
+//! exists" from "the search gave up":
+//!
+//! ```
+//! use lolli_prove::{ProofOutcome, Prover, UnknownReason};
+//! use lolli_core::{Formula, TwoSidedSequent};
+//!
+//! // A ⊢ A ⊗ A has no proof: the MALL search space is finite and exhausted
+//! let seq = TwoSidedSequent::new(
+//!     vec![Formula::atom("A")],
+//!     vec![Formula::tensor(Formula::atom("A"), Formula::atom("A"))],
+//! );
+//! assert!(Prover::new(100).decide_two_sided(&seq).is_refuted());
+//!
+//! // A shallow depth limit makes a provable sequent inconclusive
+//! let seq = TwoSidedSequent::new(
+//!     vec![Formula::with(Formula::atom("A"), Formula::atom("B"))],
+//!     vec![Formula::atom("B")],
+//! );
+//! assert!(matches!(
+//!     Prover::new(0).decide_two_sided(&seq),
+//!     ProofOutcome::Unknown(UnknownReason::DepthLimit)
+//! ));
+//! ```
 
 #![warn(missing_docs)]
 #![warn(clippy::all)]
 
+mod abduce;
+mod balance;
+mod cut;
+mod enumerate;
+mod explain;
+mod inverse;
+mod mll;
+mod multiset;
+mod residual;
 mod search;
+mod strategy;
+mod tactic;
 mod verify;
 
 pub use lolli_core::{Formula, Proof, Rule, Sequent, TwoSidedSequent};
-pub use search::Prover;
-pub use verify::{verify_proof, ProofError};
+pub use abduce::Abduction;
+pub use balance::{check_balance, Imbalance};
+pub use cut::{eliminate_cuts, CutError, CutEliminator, Elimination, Reduction};
+pub use enumerate::Proofs;
+pub use explain::{Explanation, PartialProof};
+pub use inverse::InverseProver;
+pub use mll::is_mll;
+pub use residual::Residual;
+pub use search::{CancelHandle, ProofOutcome, Prover, ProverStats, SearchLimits, UnknownReason};
+pub use strategy::{
+    strategy_by_name, DepthFirst, IterativeDeepening, SearchStrategy, SmallestFirst, STRATEGY_NAMES,
+};
+pub use tactic::{ProofState, Tactic, TacticError};
+pub use verify::{verify_proof, ProofError, StepError};
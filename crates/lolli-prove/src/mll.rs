@@ -0,0 +1,429 @@
+//! Proof nets for multiplicative linear logic.
+//!
+//! A sequent built from atoms, `⊗`, `⅋` and `⊸` alone (unit-free MLL) is
+//! provable exactly when its formulas, together with a set of axiom links
+//! pairing each atom with an occurrence of its negation, form a correct
+//! proof structure. Correctness is the Danos–Regnier criterion: every
+//! switching, which keeps one premise edge of each `⅋`, is a tree. It is
+//! checked in polynomial time by [`ProofNet::contract`], and a correct net
+//! is sequentialized into a [`Proof`].
+//!
+//! When each atom occurs once with each polarity the linking is forced and
+//! the whole decision is polynomial. Repeated atoms leave a choice of
+//! linking, and MLL provability is NP-complete in general. The search for
+//! a linking drops partial linkings that already close a cycle or cut off
+//! part of the net, and gives up after a number of steps proportional to
+//! the number of links, leaving the sequent to focused search.
+
+use lolli_core::{Formula, Link, Proof, ProofNet, Sequent, UnionFind};
+use std::collections::HashMap;
+
+/// Returns true if `seq` lies in unit-free MLL: atoms, `⊗`, `⅋` and `⊸`
+/// only, with an empty unrestricted zone.
+///
+/// [`crate::Prover`] decides such sequents with proof nets instead of
+/// sequent search.
+pub fn is_mll(seq: &Sequent) -> bool {
+    fn formula(f: &Formula) -> bool {
+        match f {
+            Formula::Atom(_) | Formula::NegAtom(_) => true,
+            Formula::Tensor(a, b) | Formula::Par(a, b) | Formula::Lolli(a, b) => {
+                formula(a) && formula(b)
+            }
+            _ => false,
+        }
+    }
+
+    !seq.linear.is_empty()
+        && seq.unrestricted.is_empty()
+        && seq.focus.is_none()
+        && seq.linear.iter().all(formula)
+}
+
+/// Partial linkings tried per axiom link before the search gives up.
+const STEPS_PER_LINK: usize = 16;
+
+/// The result of searching for a correct net.
+pub(crate) enum NetOutcome {
+    /// A correct net, sequentialized
+    Provable(Proof),
+    /// No linking gives a correct net
+    Refuted,
+    /// The search ran out of steps or was stopped
+    GaveUp,
+}
+
+/// Search for a proof of an MLL sequent through its proof nets.
+///
+/// `step` is called once per partial linking tried; the search gives up as
+/// soon as it returns false, or once it has taken [`STEPS_PER_LINK`] steps
+/// per axiom link.
+pub(crate) fn prove_mll(seq: &Sequent, mut step: impl FnMut() -> bool) -> NetOutcome {
+    let structure = Structure::new(&seq.linear);
+
+    // Each atom needs a partner, and a correct net has one axiom link more
+    // than it has tensors
+    let mut counts: HashMap<&str, isize> = HashMap::new();
+    let mut tensors = 0;
+    for kind in &structure.kinds {
+        match kind {
+            Kind::Atom(name, positive) => {
+                *counts.entry(name).or_default() += if *positive { 1 } else { -1 };
+            }
+            Kind::Tensor(..) => tensors += 1,
+            Kind::Par(..) => {}
+        }
+    }
+    let links = structure.partners.len();
+    if counts.values().any(|&count| count != 0) || links != tensors + 1 {
+        return NetOutcome::Refuted;
+    }
+
+    let mut budget = STEPS_PER_LINK * (links + 1);
+    let mut gave_up = false;
+    let mut net = structure.net.clone();
+    let mut link = vec![None; structure.kinds.len()];
+    let found = structure.link(&mut net, &mut link, &mut || {
+        gave_up = budget == 0 || !step();
+        budget = budget.saturating_sub(1);
+        !gave_up
+    });
+    if found {
+        let proof = net.sequentialize();
+        NetOutcome::Provable(proof.expect("the linking found is correct"))
+    } else if gave_up {
+        NetOutcome::GaveUp
+    } else {
+        NetOutcome::Refuted
+    }
+}
+
+/// The shape of a formula occurrence.
+enum Kind {
+    /// An atom and whether it is positive
+    Atom(String, bool),
+    Tensor(usize, usize),
+    /// Also used for `A ⊸ B`, read as `A⊥ ⅋ B`
+    Par(usize, usize),
+}
+
+/// The formula trees of a sequent, one node per occurrence of a
+/// subformula.
+struct Structure {
+    kinds: Vec<Kind>,
+    /// The formula trees as a net with no axiom links
+    net: ProofNet,
+    /// Each positive atom with the negative occurrences of the same atom
+    partners: Vec<(usize, Vec<usize>)>,
+}
+
+impl Structure {
+    fn new(formulas: &[Formula]) -> Self {
+        let mut structure = Self {
+            kinds: Vec::new(),
+            net: ProofNet::default(),
+            partners: Vec::new(),
+        };
+        for formula in formulas {
+            let root = structure.add(formula);
+            structure.net.conclusions.push(root);
+        }
+
+        for (node, kind) in structure.kinds.iter().enumerate() {
+            let Kind::Atom(name, true) = kind else {
+                continue;
+            };
+            let negatives = structure
+                .kinds
+                .iter()
+                .enumerate()
+                .filter(|(_, other)| matches!(other, Kind::Atom(n, false) if n == name))
+                .map(|(other, _)| other)
+                .collect();
+            structure.partners.push((node, negatives));
+        }
+        structure
+    }
+
+    fn add(&mut self, formula: &Formula) -> usize {
+        let kind = match formula {
+            Formula::Atom(name) => Kind::Atom(name.clone(), true),
+            Formula::NegAtom(name) => Kind::Atom(name.clone(), false),
+            Formula::Tensor(a, b) => Kind::Tensor(self.add(a), self.add(b)),
+            Formula::Par(a, b) => Kind::Par(self.add(a), self.add(b)),
+            Formula::Lolli(a, b) => Kind::Par(self.add(&a.negate()), self.add(b)),
+            _ => unreachable!("not an MLL formula"),
+        };
+        let node = self.kinds.len();
+        // Like focused search, proofs show `A ⊸ B` as `A⊥ ⅋ B`
+        let formula = match formula {
+            Formula::Lolli(a, b) => Formula::Par(Box::new(a.negate()), b.clone()),
+            _ => formula.clone(),
+        };
+        self.net.formulas.push(formula);
+        match kind {
+            Kind::Atom(..) => {}
+            Kind::Tensor(left, right) => self.net.links.push(Link::Tensor {
+                left,
+                right,
+                conclusion: node,
+            }),
+            Kind::Par(left, right) => self.net.links.push(Link::Par {
+                left,
+                right,
+                conclusion: node,
+            }),
+        }
+        self.kinds.push(kind);
+        node
+    }
+
+    /// Complete `net`, whose axiom links are those of `link`, into a
+    /// correct net. Returns false if there is none or `step` stopped the
+    /// search.
+    ///
+    /// The positive atom with the fewest partners left goes first, so
+    /// forced links come before choices. A partner already in the atom's
+    /// contracted vertex would close a cycle, and a part of the net with
+    /// nothing left to link can never be connected to the rest.
+    fn link(
+        &self,
+        net: &mut ProofNet,
+        link: &mut [Option<usize>],
+        step: &mut impl FnMut() -> bool,
+    ) -> bool {
+        if !step() {
+            return false;
+        }
+        let Ok(mut vertices) = net.contract() else {
+            return false;
+        };
+        if self.cut_off(link) {
+            return false;
+        }
+
+        let mut best: Option<(usize, Vec<usize>)> = None;
+        for (positive, negatives) in &self.partners {
+            if link[*positive].is_some() {
+                continue;
+            }
+            let vertex = vertices.find(*positive);
+            let candidates: Vec<usize> = negatives
+                .iter()
+                .copied()
+                .filter(|&n| link[n].is_none() && vertices.find(n) != vertex)
+                .collect();
+            if best
+                .as_ref()
+                .is_none_or(|(_, best)| candidates.len() < best.len())
+            {
+                let done = candidates.is_empty();
+                best = Some((*positive, candidates));
+                if done {
+                    break;
+                }
+            }
+        }
+        let Some((positive, candidates)) = best else {
+            return net.check().is_ok();
+        };
+
+        for negative in candidates {
+            link[positive] = Some(negative);
+            link[negative] = Some(positive);
+            net.links.push(Link::Axiom(negative, positive));
+            if self.link(net, link, step) {
+                return true;
+            }
+            net.links.pop();
+            link[positive] = None;
+            link[negative] = None;
+        }
+        false
+    }
+
+    /// Returns true if some part of the net with every atom linked is not
+    /// connected to the rest, so that no completion of `link` is connected.
+    fn cut_off(&self, link: &[Option<usize>]) -> bool {
+        let mut parts = UnionFind::new(self.kinds.len());
+        for (node, kind) in self.kinds.iter().enumerate() {
+            match *kind {
+                Kind::Atom(..) => {
+                    if let Some(other) = link[node] {
+                        parts.union(node, other);
+                    }
+                }
+                Kind::Tensor(a, b) | Kind::Par(a, b) => {
+                    parts.union(node, a);
+                    parts.union(node, b);
+                }
+            }
+        }
+        if parts.count() == 1 {
+            return false;
+        }
+
+        let mut open = vec![false; self.kinds.len()];
+        for (node, kind) in self.kinds.iter().enumerate() {
+            if matches!(kind, Kind::Atom(..)) && link[node].is_none() {
+                open[parts.find(node)] = true;
+            }
+        }
+        (0..self.kinds.len()).any(|node| parts.find(node) == node && !open[node])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{verify_proof, ProofOutcome, Prover, UnknownReason};
+    use lolli_core::TwoSidedSequent;
+
+    fn atom(name: &str) -> Formula {
+        Formula::atom(name)
+    }
+
+    /// A balanced tree of `depth` levels over distinct atoms, alternating
+    /// ⊗ and ⅋
+    fn tree(depth: usize, next: &mut usize) -> Formula {
+        if depth == 0 {
+            *next += 1;
+            return atom(&format!("X{}", next));
+        }
+        let (a, b) = (tree(depth - 1, next), tree(depth - 1, next));
+        if depth.is_multiple_of(2) {
+            Formula::tensor(a, b)
+        } else {
+            Formula::par(a, b)
+        }
+    }
+
+    /// The same formula with the arguments of every connective swapped
+    fn mirror(formula: &Formula) -> Formula {
+        match formula {
+            Formula::Tensor(a, b) => Formula::tensor(mirror(b), mirror(a)),
+            Formula::Par(a, b) => Formula::par(mirror(b), mirror(a)),
+            _ => formula.clone(),
+        }
+    }
+
+    #[test]
+    fn test_is_mll() {
+        let lolli = Formula::lolli(atom("A"), Formula::par(atom("B"), atom("C")));
+        assert!(is_mll(&Sequent::new(vec![lolli, Formula::neg_atom("A")])));
+        assert!(!is_mll(&Sequent::new(vec![Formula::tensor(
+            atom("A"),
+            Formula::One
+        )])));
+        assert!(!is_mll(&Sequent::new(vec![Formula::with(
+            atom("A"),
+            atom("B")
+        )])));
+        assert!(!is_mll(&Sequent::new(vec![Formula::of_course(atom("A"))])));
+    }
+
+    #[test]
+    fn test_nets_agree_with_search() {
+        let sequents = [
+            TwoSidedSequent::new(vec![atom("A")], vec![atom("A")]),
+            TwoSidedSequent::new(
+                vec![Formula::tensor(atom("A"), atom("B"))],
+                vec![Formula::tensor(atom("B"), atom("A"))],
+            ),
+            TwoSidedSequent::new(
+                vec![Formula::par(atom("A"), atom("B"))],
+                vec![Formula::tensor(atom("A"), atom("B"))],
+            ),
+            TwoSidedSequent::new(
+                vec![Formula::tensor(atom("A"), atom("B"))],
+                vec![Formula::par(atom("A"), atom("B"))],
+            ),
+            TwoSidedSequent::new(
+                vec![
+                    atom("A"),
+                    Formula::lolli(atom("A"), atom("B")),
+                    Formula::lolli(atom("B"), atom("C")),
+                ],
+                vec![atom("C")],
+            ),
+            TwoSidedSequent::new(
+                vec![Formula::lolli(
+                    Formula::lolli(atom("A"), atom("A")),
+                    atom("B"),
+                )],
+                vec![atom("B")],
+            ),
+        ];
+        for seq in &sequents {
+            let mut search = Prover::new(100);
+            search.use_proof_nets = false;
+            let expected = search.decide_two_sided(seq);
+            let outcome = Prover::new(100).decide_two_sided(seq);
+            assert_eq!(
+                outcome.is_provable(),
+                expected.is_provable(),
+                "{}",
+                seq.pretty()
+            );
+            assert_eq!(
+                outcome.is_refuted(),
+                expected.is_refuted(),
+                "{}",
+                seq.pretty()
+            );
+            if let Some(proof) = outcome.proof() {
+                assert!(verify_proof(proof).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn test_correctness_criterion() {
+        // ⊢ B ⊗ B⊥, A ⅋ A⊥ has the right number of links, but its only
+        // linking closes a cycle through the tensor
+        let seq = Sequent::new(vec![
+            Formula::tensor(atom("B"), Formula::neg_atom("B")),
+            Formula::par(atom("A"), Formula::neg_atom("A")),
+        ]);
+        assert!(Prover::new(100).decide(&seq).is_refuted());
+    }
+
+    #[test]
+    fn test_many_atoms_without_depth_limit() {
+        // F ⊢ F with F's connectives mirrored, over 128 distinct atoms
+        let formula = tree(7, &mut 0);
+        let seq = TwoSidedSequent::new(vec![formula.clone()], vec![mirror(&formula)]);
+        let mut prover = Prover::new(0);
+        let proof = prover.decide_two_sided(&seq).into_proof().unwrap();
+        assert!(verify_proof(&proof).is_ok());
+        // The linking is forced: one step per axiom link, plus the last
+        assert_eq!(prover.stats().sequents_explored, 129);
+    }
+
+    #[test]
+    fn test_repeated_atoms() {
+        // Pool, (Available ⊸ InUse)×3, Available×3 ⊢ Pool ⊗ InUse×3
+        let mut context = vec![atom("Pool")];
+        let mut goal = atom("Pool");
+        for _ in 0..3 {
+            context.push(Formula::lolli(atom("Available"), atom("InUse")));
+            context.push(atom("Available"));
+            goal = Formula::tensor(goal, atom("InUse"));
+        }
+        let seq = TwoSidedSequent::new(context, vec![goal]);
+        let proof = Prover::new(100).prove_two_sided(&seq).unwrap();
+        assert!(verify_proof(&proof).is_ok());
+    }
+
+    #[test]
+    fn test_cancelled() {
+        let formula = tree(4, &mut 0);
+        let seq = TwoSidedSequent::new(vec![formula.clone()], vec![mirror(&formula)]);
+        let mut prover = Prover::new(100);
+        prover.cancel_handle().cancel();
+        assert!(matches!(
+            prover.decide_two_sided(&seq),
+            ProofOutcome::Unknown(UnknownReason::Cancelled)
+        ));
+    }
+}
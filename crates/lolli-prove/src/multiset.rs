@@ -0,0 +1,24 @@
+//! Contexts as multisets of formulas, kept in plain lists.
+
+use lolli_core::Formula;
+
+/// `items` with one copy of `formula` removed, if there is one.
+pub(crate) fn without(items: &[Formula], formula: &Formula) -> Option<Vec<Formula>> {
+    let pos = items.iter().position(|f| f == formula)?;
+    let mut items = items.to_vec();
+    items.remove(pos);
+    Some(items)
+}
+
+/// Whether two lists are equal as multisets.
+pub(crate) fn same_multiset(a: &[Formula], b: &[Formula]) -> bool {
+    let mut rest = b.to_vec();
+    a.len() == b.len()
+        && a.iter().all(|f| match rest.iter().position(|g| g == f) {
+            Some(pos) => {
+                rest.swap_remove(pos);
+                true
+            }
+            None => false,
+        })
+}
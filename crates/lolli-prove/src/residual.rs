@@ -0,0 +1,156 @@
+//! Residual computation.
+//!
+//! Starting from hypotheses Γ and a goal G, [`Prover::residual`] finds the
+//! atoms Δ that remain once G is achieved, that is a Δ with `Γ ⊢ G ⊗ Δ`
+//! provable. For atoms that only occur multiplicatively the balance check
+//! already determines Δ: every surplus occurrence has to be left over. Atoms
+//! under additives or exponentials are searched for, fewest first.
+
+use crate::balance::{check_balance, flexible_atoms};
+use crate::Prover;
+use lolli_core::{Formula, Proof, TwoSidedSequent};
+
+/// The resources left over after proving a goal.
+#[derive(Debug, Clone)]
+pub struct Residual {
+    /// The atoms of Δ: surplus atoms sorted by name, then any atoms the
+    /// search had to add
+    pub remaining: Vec<Formula>,
+    /// The sequent Γ ⊢ G ⊗ Δ, or Γ ⊢ G if nothing remains
+    pub sequent: TwoSidedSequent,
+    /// A proof of `sequent`
+    pub proof: Proof,
+}
+
+impl Residual {
+    /// Δ as a single formula: the tensor of the remaining atoms, or `1`.
+    pub fn formula(&self) -> Formula {
+        tensor_all(&self.remaining).unwrap_or(Formula::One)
+    }
+}
+
+impl Prover {
+    /// Find the smallest multiset of atoms Δ such that Γ ⊢ G ⊗ Δ is provable.
+    ///
+    /// Returns `None` if the goal needs atoms the context does not provide,
+    /// or if no Δ yields a proof within `max_depth`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lolli_prove::Prover;
+    /// use lolli_core::Formula;
+    ///
+    /// // Acquire a connection from Pool, Available, Available
+    /// let pool = Formula::atom("Pool");
+    /// let available = Formula::atom("Available");
+    /// let in_use = Formula::atom("InUse");
+    /// let acquire = Formula::lolli(
+    ///     Formula::tensor(pool.clone(), available.clone()),
+    ///     Formula::tensor(pool.clone(), in_use.clone()),
+    /// );
+    /// let context = [pool.clone(), available.clone(), available.clone(), acquire];
+    ///
+    /// let residual = Prover::new(100).residual(&context, &in_use).unwrap();
+    /// assert_eq!(residual.remaining, vec![available, pool]);
+    /// ```
+    pub fn residual(&mut self, context: &[Formula], goal: &Formula) -> Option<Residual> {
+        let seq = TwoSidedSequent::new(context.to_vec(), vec![goal.clone()]);
+        let one_sided = seq.to_one_sided();
+
+        let mut surplus = Vec::new();
+        if let Err(unbalanced) = check_balance(&one_sided) {
+            for imbalance in unbalanced {
+                // The goal needs more than the context has
+                if imbalance.positive > imbalance.negative {
+                    return None;
+                }
+                let count = imbalance.negative - imbalance.positive;
+                surplus.extend(std::iter::repeat_n(Formula::atom(imbalance.atom), count));
+            }
+        }
+
+        // Each flexible occurrence may leave at most one atom over
+        let flexible = flexible_atoms(&one_sided);
+        let options: Vec<Formula> = flexible
+            .iter()
+            .map(|(name, _)| Formula::atom(name.clone()))
+            .collect();
+        let max = surplus.len() + flexible.iter().map(|(_, n)| n).sum::<usize>();
+
+        let extend = |remaining: &[Formula]| {
+            let goal = match tensor_all(remaining) {
+                Some(rest) => Formula::tensor(goal.clone(), rest),
+                None => goal.clone(),
+            };
+            TwoSidedSequent::new(context.to_vec(), vec![goal])
+        };
+        let (remaining, proof) = self.smallest_extension(surplus, &options, max, extend)?;
+        Some(Residual {
+            sequent: extend(&remaining),
+            remaining,
+            proof,
+        })
+    }
+}
+
+/// The tensor of `formulas`, or `None` if there are none.
+fn tensor_all(formulas: &[Formula]) -> Option<Formula> {
+    let mut formulas = formulas.iter().cloned();
+    let first = formulas.next()?;
+    Some(formulas.fold(first, Formula::tensor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atom(name: &str) -> Formula {
+        Formula::atom(name)
+    }
+
+    #[test]
+    fn test_residual_after_operation() {
+        // Pool, Available, Available, Pool ⊗ Available ⊸ Pool ⊗ InUse ⊢ InUse
+        let acquire = Formula::lolli(
+            Formula::tensor(atom("Pool"), atom("Available")),
+            Formula::tensor(atom("Pool"), atom("InUse")),
+        );
+        let context = [atom("Pool"), atom("Available"), atom("Available"), acquire];
+        let residual = Prover::new(100).residual(&context, &atom("InUse")).unwrap();
+        assert_eq!(residual.remaining, vec![atom("Available"), atom("Pool")]);
+        assert_eq!(residual.formula().pretty(), "(Available ⊗ Pool)");
+        assert_eq!(
+            residual.sequent.succedent[0].pretty(),
+            "(InUse ⊗ (Available ⊗ Pool))"
+        );
+    }
+
+    #[test]
+    fn test_residual_empty() {
+        let residual = Prover::new(100)
+            .residual(
+                &[atom("A"), atom("B")],
+                &Formula::tensor(atom("A"), atom("B")),
+            )
+            .unwrap();
+        assert!(residual.remaining.is_empty());
+        assert_eq!(residual.formula(), Formula::One);
+    }
+
+    #[test]
+    fn test_residual_additive() {
+        // From A & B, proving 1 leaves A or B; the first choice is A
+        let residual = Prover::new(100)
+            .residual(&[Formula::with(atom("A"), atom("B"))], &Formula::One)
+            .unwrap();
+        assert_eq!(residual.remaining, vec![atom("A")]);
+    }
+
+    #[test]
+    fn test_residual_goal_out_of_reach() {
+        let residual =
+            Prover::new(100).residual(&[atom("A")], &Formula::tensor(atom("A"), atom("B")));
+        assert!(residual.is_none());
+    }
+}
@@ -9,9 +9,24 @@
 //! - **Dereliction**: Use an unrestricted formula linearly
 //! - **Contraction**: Duplicate an unrestricted formula
 //! - **Weakening**: Discard an unrestricted formula
-
+//!
+//! ## Resource Management
+//!
+//! The tensor rule does not enumerate the 2^n ways of splitting its context.
+//! Following Hodas and Miller, the left premise receives the rest of the
+//! context as a pool, consumes what it needs, and the right premise must use
+//! up the leftovers. A `⊤` in the left premise leaves its share open, so the
+//! right premise may leave formulas unused and `⊤` absorbs them afterwards.
+//! Proofs still carry the concrete sequent at every step.
+
+use crate::balance::{can_balance, check_balance};
+use crate::mll::{is_mll, prove_mll, NetOutcome};
+use crate::strategy::{formula_size, sequent_size, DepthFirst, SearchStrategy};
 use lolli_core::{Formula, Proof, Rule, Sequent, TwoSidedSequent};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// A prover for linear logic sequents.
 ///
@@ -21,12 +36,195 @@ pub struct Prover {
     pub max_depth: usize,
     /// Enable caching of failed sequents
     pub use_cache: bool,
-    /// Cache of unprovable sequents (normalized form)
-    cache: HashSet<Vec<String>>,
+    /// Reject sequents that fail the atom-count balance check, see [`check_balance`]
+    pub use_balance: bool,
+    /// Decide unit-free MLL sequents with proof nets, see [`is_mll`]
+    pub use_proof_nets: bool,
+    /// Bounds on the work a single search may do
+    pub limits: SearchLimits,
+    /// How to explore the search space, [`DepthFirst`] by default
+    pub strategy: Box<dyn SearchStrategy>,
+    /// Set from outside to stop the search
+    cancel: CancelHandle,
+    /// When the current search started
+    started: Instant,
+    /// Why the current search was stopped, if it was
+    stopped: Option<UnknownReason>,
+    /// Cache of unprovable sequents (normalized form), see [`Failure`]
+    cache: HashMap<Vec<String>, Failure>,
+    /// Every outcome of exhausted resource-passing searches, by sequent and pool
+    outcomes: HashMap<(Vec<String>, Vec<String>), (Outcomes, Failure)>,
     /// Statistics
     stats: ProverStats,
 }
 
+/// How a cached failure was established.
+///
+/// A failure found without ever hitting `max_depth` holds at any depth. One
+/// that hit the limit only says the sequent has no proof within the remaining
+/// depth budget, so it answers later visits with the same budget or less.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Failure {
+    /// No proof exists.
+    Definitive,
+    /// No proof exists within this many further steps.
+    WithinBudget(usize),
+}
+
+impl Failure {
+    fn covers(self, budget: usize) -> bool {
+        match self {
+            Failure::Definitive => true,
+            Failure::WithinBudget(b) => b >= budget,
+        }
+    }
+
+    fn max(self, other: Failure) -> Failure {
+        match (self, other) {
+            (Failure::WithinBudget(a), Failure::WithinBudget(b)) => Failure::WithinBudget(a.max(b)),
+            _ => Failure::Definitive,
+        }
+    }
+}
+
+/// Bounds on a proof search, on top of `max_depth`. `None` means unlimited.
+///
+/// A search that hits a limit ends with [`UnknownReason`] saying which one;
+/// [`Prover::stats`] then describes the work done so far.
+#[derive(Debug, Clone, Default)]
+pub struct SearchLimits {
+    /// Maximum number of sequents to explore
+    pub max_sequents: Option<usize>,
+    /// Maximum wall-clock time
+    pub timeout: Option<Duration>,
+    /// Maximum number of entries in the search caches
+    pub max_cache_entries: Option<usize>,
+}
+
+/// A handle to stop a running search from another thread.
+///
+/// Cancelling stops the current search of the prover the handle came from
+/// and every later one, until [`Prover::reset`].
+///
+/// ```
+/// use lolli_prove::{ProofOutcome, Prover, UnknownReason};
+/// use lolli_core::{Formula, Sequent};
+///
+/// let mut prover = Prover::new(100);
+/// let handle = prover.cancel_handle();
+/// std::thread::spawn(move || handle.cancel()).join().unwrap();
+///
+/// let seq = Sequent::new(vec![Formula::atom("A"), Formula::neg_atom("A")]);
+/// assert!(matches!(
+///     prover.decide(&seq),
+///     ProofOutcome::Unknown(UnknownReason::Cancelled)
+/// ));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle(Arc<AtomicBool>);
+
+impl CancelHandle {
+    /// Ask the search to stop.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if the search was asked to stop.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Lift the cancellation.
+    pub(crate) fn clear(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Proofs found by a resource-passing search, with the pool formulas each used.
+type Outcomes = Vec<(Proof, Vec<bool>)>;
+
+/// Success continuation of the resource-passing search.
+///
+/// Receives a proof together with a mask of the pool formulas it consumed,
+/// and returns the finished proof, or `None` to ask for another alternative.
+type Cont<'a> = dyn FnMut(&mut Prover, Proof, Vec<bool>) -> Option<Proof> + 'a;
+
+/// The result of a proof search.
+#[derive(Debug, Clone)]
+pub enum ProofOutcome {
+    /// A proof was found.
+    Provable(Proof),
+    /// The search space was exhausted: the sequent has no proof.
+    Refuted,
+    /// The search ended without a definite answer.
+    Unknown(UnknownReason),
+}
+
+/// Why a proof search ended without a definite answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnknownReason {
+    /// At least one branch was cut off by `max_depth`.
+    DepthLimit,
+    /// The sequent uses exponentials, where an exhausted search is not a refutation.
+    Exponentials,
+    /// The search explored [`SearchLimits::max_sequents`] sequents.
+    SequentLimit,
+    /// The search ran for [`SearchLimits::timeout`].
+    Timeout,
+    /// The caches grew to [`SearchLimits::max_cache_entries`] entries.
+    CacheLimit,
+    /// The search was stopped through a [`CancelHandle`].
+    Cancelled,
+}
+
+impl ProofOutcome {
+    /// Returns true if a proof was found.
+    pub fn is_provable(&self) -> bool {
+        matches!(self, ProofOutcome::Provable(_))
+    }
+
+    /// Returns true if the sequent was shown to be unprovable.
+    pub fn is_refuted(&self) -> bool {
+        matches!(self, ProofOutcome::Refuted)
+    }
+
+    /// Returns true if the search was inconclusive.
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, ProofOutcome::Unknown(_))
+    }
+
+    /// The proof, if one was found.
+    pub fn proof(&self) -> Option<&Proof> {
+        match self {
+            ProofOutcome::Provable(proof) => Some(proof),
+            _ => None,
+        }
+    }
+
+    /// Convert into the proof, if one was found.
+    pub fn into_proof(self) -> Option<Proof> {
+        match self {
+            ProofOutcome::Provable(proof) => Some(proof),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for UnknownReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnknownReason::DepthLimit => write!(f, "search cut off by the depth limit"),
+            UnknownReason::Exponentials => {
+                write!(f, "search is incomplete for sequents with exponentials")
+            }
+            UnknownReason::SequentLimit => write!(f, "search hit the sequent limit"),
+            UnknownReason::Timeout => write!(f, "search timed out"),
+            UnknownReason::CacheLimit => write!(f, "search hit the cache limit"),
+            UnknownReason::Cancelled => write!(f, "search was cancelled"),
+        }
+    }
+}
+
 /// Statistics about the proof search.
 #[derive(Debug, Default, Clone)]
 pub struct ProverStats {
@@ -36,6 +234,12 @@ pub struct ProverStats {
     pub cache_hits: usize,
     /// Maximum depth reached
     pub max_depth_reached: usize,
+    /// Number of branches cut off by the depth limit
+    pub depth_cutoffs: usize,
+    /// Number of sequents rejected by the atom-count balance check
+    pub balance_prunes: usize,
+    /// Wall-clock time spent searching
+    pub elapsed: Duration,
 }
 
 impl Prover {
@@ -44,7 +248,15 @@ impl Prover {
         Self {
             max_depth,
             use_cache: true,
-            cache: HashSet::new(),
+            use_balance: true,
+            use_proof_nets: true,
+            limits: SearchLimits::default(),
+            strategy: Box::new(DepthFirst),
+            cancel: CancelHandle::default(),
+            started: Instant::now(),
+            stopped: None,
+            cache: HashMap::new(),
+            outcomes: HashMap::new(),
             stats: ProverStats::default(),
         }
     }
@@ -54,10 +266,17 @@ impl Prover {
         &self.stats
     }
 
-    /// Clear the cache and reset statistics.
+    /// A handle that stops this prover's searches when cancelled.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+
+    /// Clear the cache, reset statistics and lift any cancellation.
     pub fn reset(&mut self) {
         self.cache.clear();
+        self.outcomes.clear();
         self.stats = ProverStats::default();
+        self.cancel.clear();
     }
 
     /// Prove a two-sided sequent Γ ⊢ Δ.
@@ -70,50 +289,325 @@ impl Prover {
 
     /// Prove a one-sided sequent ⊢ Γ.
     ///
-    /// Returns `Some(proof)` if provable, `None` otherwise.
+    /// Returns `Some(proof)` if provable, `None` otherwise. Use [`Prover::decide`]
+    /// to tell a refutation apart from a search that gave up.
     pub fn prove(&mut self, seq: &Sequent) -> Option<Proof> {
-        self.stats.sequents_explored = 0;
-        self.stats.cache_hits = 0;
-        self.stats.max_depth_reached = 0;
-        self.prove_with_depth(seq, 0)
+        self.decide(seq).into_proof()
     }
 
-    fn prove_with_depth(&mut self, seq: &Sequent, depth: usize) -> Option<Proof> {
-        self.stats.sequents_explored += 1;
-        if depth > self.stats.max_depth_reached {
-            self.stats.max_depth_reached = depth;
+    /// Decide a two-sided sequent Γ ⊢ Δ.
+    pub fn decide_two_sided(&mut self, seq: &TwoSidedSequent) -> ProofOutcome {
+        let one_sided = seq.to_one_sided();
+        self.decide(&one_sided)
+    }
+
+    /// Decide a one-sided sequent ⊢ Γ.
+    ///
+    /// Unlike [`Prover::prove`], this distinguishes a sequent that has no proof
+    /// ([`ProofOutcome::Refuted`]) from a search that ended without an answer
+    /// ([`ProofOutcome::Unknown`]). Refutations are only reported for the
+    /// exponential-free (MALL) fragment, where the search is complete, and
+    /// for sequents that fail the atom-count balance check.
+    pub fn decide(&mut self, seq: &Sequent) -> ProofOutcome {
+        self.begin();
+
+        if self.use_balance && check_balance(seq).is_err() {
+            self.stats.balance_prunes += 1;
+            return ProofOutcome::Refuted;
         }
 
-        // Depth limit check
-        if depth > self.max_depth {
+        // Proof nets decide MLL without a depth limit, unless the choice of
+        // axiom links is too large
+        if self.use_proof_nets && is_mll(seq) {
+            let outcome = match prove_mll(seq, || self.enter(0)) {
+                NetOutcome::Provable(proof) => Some(ProofOutcome::Provable(proof)),
+                NetOutcome::Refuted => Some(ProofOutcome::Refuted),
+                NetOutcome::GaveUp => self.stopped.map(ProofOutcome::Unknown),
+            };
+            if let Some(outcome) = outcome {
+                self.stats.elapsed = self.started.elapsed();
+                return outcome;
+            }
+        }
+
+        // Each pass searches up to its own depth limit; only the last one
+        // decides whether the depth limit was involved
+        let max_depth = self.max_depth;
+        let mut result = None;
+        let mut cut_off = true;
+        for limit in self.strategy.depths(max_depth) {
+            self.max_depth = limit.min(max_depth);
+            let cutoffs_before = self.stats.depth_cutoffs;
+            result = self.prove_with_depth(seq, 0);
+            cut_off = self.stats.depth_cutoffs > cutoffs_before;
+            if result.is_some() || self.stopped.is_some() || !cut_off {
+                break;
+            }
+        }
+        self.max_depth = max_depth;
+
+        self.stats.elapsed = self.started.elapsed();
+        if let Some(proof) = result {
+            ProofOutcome::Provable(proof)
+        } else if let Some(reason) = self.stopped {
+            ProofOutcome::Unknown(reason)
+        } else if cut_off {
+            ProofOutcome::Unknown(UnknownReason::DepthLimit)
+        } else if has_exponentials(seq) {
+            ProofOutcome::Unknown(UnknownReason::Exponentials)
+        } else {
+            ProofOutcome::Refuted
+        }
+    }
+
+    fn prove_with_depth(&mut self, seq: &Sequent, depth: usize) -> Option<Proof> {
+        if !self.enter(depth) {
             return None;
         }
 
         // Check cache
+        let budget = self.max_depth - depth;
         if self.use_cache {
             let key = self.sequent_key(seq);
-            if self.cache.contains(&key) {
-                self.stats.cache_hits += 1;
-                return None;
+            if let Some(&failure) = self.cache.get(&key) {
+                if failure.covers(budget) {
+                    self.stats.cache_hits += 1;
+                    if failure != Failure::Definitive {
+                        // The answer still depends on the depth limit
+                        self.stats.depth_cutoffs += 1;
+                    }
+                    return None;
+                }
             }
         }
 
         // Try to prove
-        let result = self.prove_async(seq, depth);
-
-        // Cache negative result
-        if result.is_none() && self.use_cache {
+        let cutoffs_before = self.stats.depth_cutoffs;
+        let result = self.prove_async(seq, &[], depth, &mut |_, proof, _| Some(proof));
+
+        // Cache negative result, remembering whether the depth limit was
+        // involved. A stopped search proves nothing.
+        if result.is_none() && self.use_cache && self.stopped.is_none() {
+            let failure = if self.stats.depth_cutoffs == cutoffs_before {
+                Failure::Definitive
+            } else {
+                Failure::WithinBudget(budget)
+            };
             let key = self.sequent_key(seq);
-            self.cache.insert(key);
+            self.cache
+                .entry(key)
+                .and_modify(|f| *f = f.max(failure))
+                .or_insert(failure);
         }
 
         result
     }
 
+    /// Hand every way of proving ⊢ Γ, Δ, with Γ `seq` and Δ drawn from
+    /// `pool`, to `f` along with a mask of the pool formulas consumed.
+    pub(crate) fn for_each_outcome(
+        &mut self,
+        seq: &Sequent,
+        pool: &[Formula],
+        mut f: impl FnMut(Proof, Vec<bool>),
+    ) {
+        self.begin();
+        self.prove_io(seq, pool, 0, &mut |_, proof, used| {
+            f(proof, used);
+            None
+        });
+        self.stats.elapsed = self.started.elapsed();
+    }
+
+    /// Prove ⊢ Γ by a ⊗ rule on the tensor at `idx`, with the rest of Γ
+    /// passed from the left premise to the right one as in focused search,
+    /// so that the split of Γ can be read off the premises.
+    pub(crate) fn prove_tensor(&mut self, seq: &Sequent, idx: usize) -> Option<Proof> {
+        self.begin();
+        let proof = self.prove_focused(seq, idx, &[], 0, &mut |_, proof, _| Some(proof));
+        self.stats.elapsed = self.started.elapsed();
+        proof
+    }
+
+    /// Start a new search.
+    fn begin(&mut self) {
+        self.stats = ProverStats::default();
+        self.started = Instant::now();
+        self.stopped = None;
+    }
+
+    /// Returns false if no part of `pool` can make `seq` balanced.
+    fn balanced(&mut self, seq: &Sequent, pool: &[Formula]) -> bool {
+        if !self.use_balance || can_balance(seq, pool) {
+            return true;
+        }
+        self.stats.balance_prunes += 1;
+        false
+    }
+
+    /// The limit the search has run into, if any.
+    fn exceeded_limit(&self) -> Option<UnknownReason> {
+        let limits = &self.limits;
+        if self.cancel.is_cancelled() {
+            Some(UnknownReason::Cancelled)
+        } else if limits
+            .max_sequents
+            .is_some_and(|max| self.stats.sequents_explored >= max)
+        {
+            Some(UnknownReason::SequentLimit)
+        } else if limits
+            .max_cache_entries
+            .is_some_and(|max| self.cache.len() + self.outcomes.len() >= max)
+        {
+            Some(UnknownReason::CacheLimit)
+        } else if limits
+            .timeout
+            .is_some_and(|timeout| self.started.elapsed() >= timeout)
+        {
+            Some(UnknownReason::Timeout)
+        } else {
+            None
+        }
+    }
+
+    /// Record a visit at `depth`; returns false if it lies past the depth
+    /// limit or the search has to stop.
+    fn enter(&mut self, depth: usize) -> bool {
+        if self.stopped.is_none() {
+            self.stopped = self.exceeded_limit();
+        }
+        if self.stopped.is_some() {
+            return false;
+        }
+
+        self.stats.sequents_explored += 1;
+        if depth > self.stats.max_depth_reached {
+            self.stats.max_depth_reached = depth;
+        }
+
+        if depth > self.max_depth {
+            self.stats.depth_cutoffs += 1;
+            return false;
+        }
+        true
+    }
+
+    /// Prove ⊢ Γ, Δ where Γ is `seq` and Δ is some part of `pool`.
+    ///
+    /// This is input/output resource management (Hodas and Miller): rather
+    /// than guessing how a tensor splits its context, the left premise gets
+    /// the whole context as a pool, consumes what it needs and passes the
+    /// leftovers on. Every way of consuming the pool is handed to `k` along
+    /// with a mask of the pool formulas used, until `k` returns a proof.
+    fn prove_io(
+        &mut self,
+        seq: &Sequent,
+        pool: &[Formula],
+        depth: usize,
+        k: &mut Cont<'_>,
+    ) -> Option<Proof> {
+        if !pool.is_empty() {
+            // Pools that differ only in order have the same outcomes, and
+            // tensors pass leftovers on in many orders: search the pool in
+            // sorted order, so that equal formulas sit together and every
+            // order shares one cache entry
+            let mut order: Vec<usize> = (0..pool.len()).collect();
+            order.sort_by_cached_key(|&i| pool[i].pretty());
+            if order.iter().enumerate().any(|(j, &i)| i != j) {
+                let sorted: Vec<Formula> = order.iter().map(|&i| pool[i].clone()).collect();
+                return self.prove_io(seq, &sorted, depth, &mut |p, proof, sorted_used| {
+                    let mut used = vec![false; sorted_used.len()];
+                    for (&i, u) in order.iter().zip(sorted_used) {
+                        used[i] = u;
+                    }
+                    k(p, proof, used)
+                });
+            }
+
+            if !self.enter(depth) {
+                return None;
+            }
+
+            // A search that ran out of alternatives before has nothing new
+            // to offer: replay what it found
+            let budget = self.max_depth - depth;
+            let key = (
+                self.sequent_key(seq),
+                pool.iter().map(Formula::pretty).collect(),
+            );
+            if self.use_cache {
+                if let Some((outcomes, failure)) = self.outcomes.get(&key) {
+                    if failure.covers(budget) {
+                        self.stats.cache_hits += 1;
+                        if *failure != Failure::Definitive {
+                            self.stats.depth_cutoffs += 1;
+                        }
+                        for (proof, used) in outcomes.clone() {
+                            if let Some(result) = k(self, proof, used) {
+                                return Some(result);
+                            }
+                        }
+                        return None;
+                    }
+                }
+            }
+
+            // Different rule orders reach the same outcome; the caller only
+            // sees what was used and whether a ⊤ can absorb more, so each
+            // such outcome is offered once.
+            let cutoffs_before = self.stats.depth_cutoffs;
+            let mut seen = HashSet::new();
+            let mut outcomes = Vec::new();
+            let result = self.prove_async(seq, pool, depth, &mut |p, proof, used| {
+                let used = earliest_copies(pool, &used);
+                if !seen.insert((used.clone(), can_absorb(&proof))) {
+                    return None;
+                }
+                outcomes.push((proof.clone(), used.clone()));
+                k(p, proof, used)
+            });
+
+            if result.is_none() && self.use_cache && self.stopped.is_none() {
+                let failure = if self.stats.depth_cutoffs == cutoffs_before {
+                    Failure::Definitive
+                } else {
+                    Failure::WithinBudget(budget)
+                };
+                self.outcomes.insert(key, (outcomes, failure));
+            }
+            return result;
+        }
+
+        // Nothing left to choose: any proof will do, and failures are cached
+        let proof = self.prove_with_depth(seq, depth)?;
+        let slack = can_absorb(&proof);
+        if let Some(result) = k(self, proof, Vec::new()) {
+            return Some(result);
+        }
+
+        // The caller may still need a ⊤ that can absorb its leftovers. Such a
+        // proof exists iff ⊢ Γ, Z⊥ is provable for an atom Z that occurs
+        // nowhere else, since only a ⊤ can consume Z⊥.
+        if slack || !mentions_top(seq) {
+            return None;
+        }
+        let mut padded = seq.clone();
+        padded.linear.push(slack_marker());
+        let mut proof = self.prove_with_depth(&padded, depth)?;
+        strip_marker(&mut proof);
+        k(self, proof, Vec::new())
+    }
+
     /// Asynchronous phase: apply all invertible (negative) rules.
-    fn prove_async(&mut self, seq: &Sequent, depth: usize) -> Option<Proof> {
+    fn prove_async(
+        &mut self,
+        seq: &Sequent,
+        pool: &[Formula],
+        depth: usize,
+        k: &mut Cont<'_>,
+    ) -> Option<Proof> {
         // First, check for empty sequent (contradiction/impossible)
-        if seq.linear.is_empty() && seq.focus.is_none() {
+        if seq.linear.is_empty() && pool.is_empty() {
             // Empty sequent is not provable in linear logic
             return None;
         }
@@ -133,14 +627,10 @@ impl Prover {
                         focus: None,
                     };
 
-                    if let Some(premise) = self.prove_with_depth(&new_seq, depth + 1) {
-                        return Some(Proof {
-                            conclusion: seq.clone(),
-                            rule: Rule::ParIntro,
-                            premises: vec![premise],
-                        });
-                    }
-                    return None;
+                    return self.prove_io(&new_seq, pool, depth + 1, &mut |p, premise, used| {
+                        let proof = conclude(seq, pool, &used, Rule::ParIntro, vec![premise]);
+                        k(p, proof, used)
+                    });
                 }
 
                 // Bottom (⊥) - invertible: ⊢ Γ, ⊥ becomes ⊢ Γ
@@ -153,23 +643,18 @@ impl Prover {
                         focus: None,
                     };
 
-                    if let Some(premise) = self.prove_with_depth(&new_seq, depth + 1) {
-                        return Some(Proof {
-                            conclusion: seq.clone(),
-                            rule: Rule::BottomIntro,
-                            premises: vec![premise],
-                        });
-                    }
-                    return None;
+                    return self.prove_io(&new_seq, pool, depth + 1, &mut |p, premise, used| {
+                        let proof = conclude(seq, pool, &used, Rule::BottomIntro, vec![premise]);
+                        k(p, proof, used)
+                    });
                 }
 
-                // Top (⊤) - always provable, no premise needed
+                // Top (⊤) - always provable, no premise needed. It takes nothing
+                // from the pool here; leftovers are absorbed later if needed.
                 Formula::Top => {
-                    return Some(Proof {
-                        conclusion: seq.clone(),
-                        rule: Rule::TopIntro,
-                        premises: vec![],
-                    });
+                    let used = vec![false; pool.len()];
+                    let proof = conclude(seq, pool, &used, Rule::TopIntro, vec![]);
+                    return k(self, proof, used);
                 }
 
                 // With (&) - invertible: ⊢ Γ, A & B needs ⊢ Γ, A AND ⊢ Γ, B
@@ -192,16 +677,21 @@ impl Prover {
                         focus: None,
                     };
 
-                    if let Some(left_proof) = self.prove_with_depth(&left_seq, depth + 1) {
-                        if let Some(right_proof) = self.prove_with_depth(&right_seq, depth + 1) {
-                            return Some(Proof {
-                                conclusion: seq.clone(),
-                                rule: Rule::WithIntro,
-                                premises: vec![left_proof, right_proof],
-                            });
-                        }
-                    }
-                    return None;
+                    return self.prove_io(&left_seq, pool, depth + 1, &mut |p, left, left_used| {
+                        p.prove_io(&right_seq, pool, depth + 1, &mut |p, right, right_used| {
+                            // Both premises must end up with the same context
+                            let used: Vec<bool> = left_used
+                                .iter()
+                                .zip(&right_used)
+                                .map(|(l, r)| *l || *r)
+                                .collect();
+                            let left = absorb_missing(left.clone(), pool, &left_used, &used)?;
+                            let right = absorb_missing(right, pool, &right_used, &used)?;
+                            let proof =
+                                conclude(seq, pool, &used, Rule::WithIntro, vec![left, right]);
+                            k(p, proof, used)
+                        })
+                    });
                 }
 
                 // WhyNot (?) - move to unrestricted zone
@@ -216,14 +706,10 @@ impl Prover {
                         focus: None,
                     };
 
-                    if let Some(premise) = self.prove_with_depth(&new_seq, depth + 1) {
-                        return Some(Proof {
-                            conclusion: seq.clone(),
-                            rule: Rule::WhyNotIntro,
-                            premises: vec![premise],
-                        });
-                    }
-                    return None;
+                    return self.prove_io(&new_seq, pool, depth + 1, &mut |p, premise, used| {
+                        let proof = conclude(seq, pool, &used, Rule::WhyNotIntro, vec![premise]);
+                        k(p, proof, used)
+                    });
                 }
 
                 // Lolli (⊸) is sugar for Par, so desugar it
@@ -237,7 +723,7 @@ impl Prover {
                         focus: None,
                     };
 
-                    return self.prove_with_depth(&new_seq, depth);
+                    return self.prove_io(&new_seq, pool, depth, k);
                 }
 
                 _ => {}
@@ -245,170 +731,213 @@ impl Prover {
         }
 
         // No invertible rules apply, go to synchronous phase
-        self.prove_sync(seq, depth)
+        self.prove_sync(seq, pool, depth, k)
     }
 
     /// Synchronous phase: choose a formula to focus on.
-    fn prove_sync(&mut self, seq: &Sequent, depth: usize) -> Option<Proof> {
-        // Try focusing on each positive formula
+    fn prove_sync(
+        &mut self,
+        seq: &Sequent,
+        pool: &[Formula],
+        depth: usize,
+        k: &mut Cont<'_>,
+    ) -> Option<Proof> {
+        let mut choices = Vec::new();
+
+        // Focus on a positive formula; equal formulas are interchangeable
         for i in 0..seq.linear.len() {
-            if seq.linear[i].is_positive() {
-                if let Some(proof) = self.prove_focused(seq, i, depth) {
-                    return Some(proof);
-                }
+            if seq.linear[i].is_positive() && !seq.linear[..i].contains(&seq.linear[i]) {
+                choices.push(Choice::Focus(i));
             }
         }
 
-        // Also try focusing on negated atoms (they act like axioms with their positive counterpart)
-        for i in 0..seq.linear.len() {
-            if matches!(&seq.linear[i], Formula::NegAtom(_)) {
-                if let Some(proof) = self.try_axiom(seq, i) {
-                    return Some(proof);
-                }
+        // Focus on a positive formula from the pool, which consumes it.
+        // Equal pool formulas are interchangeable, so only the first is tried.
+        for i in 0..pool.len() {
+            if pool[i].is_positive() && !pool[..i].contains(&pool[i]) {
+                choices.push(Choice::FocusPool(i));
             }
         }
 
-        // Try using unrestricted formulas (exponentials)
-        if !seq.unrestricted.is_empty() {
-            // Try dereliction: bring an unrestricted formula into linear context
-            if let Some(proof) = self.try_dereliction(seq, depth) {
-                return Some(proof);
-            }
-
-            // Try contraction: duplicate an unrestricted formula
-            if let Some(proof) = self.try_contraction(seq, depth) {
-                return Some(proof);
+        // Negated atoms act like axioms with their positive counterpart
+        for i in 0..seq.linear.len() {
+            if matches!(&seq.linear[i], Formula::NegAtom(_)) {
+                choices.push(Choice::Axiom(i));
             }
+        }
 
-            // Try weakening: discard unused unrestricted formulas
-            if let Some(proof) = self.try_weakening(seq, depth) {
-                return Some(proof);
+        // Use unrestricted formulas (exponentials)
+        let n = seq.unrestricted.len();
+        choices.extend((0..n).map(Choice::Dereliction));
+        choices.extend((0..n).map(Choice::Contraction));
+        choices.extend((0..n).map(Choice::Weakening));
+
+        let total = sequent_size(seq);
+        let sizes: Vec<usize> = choices
+            .iter()
+            .map(|choice| choice.size_after(seq, pool, total))
+            .collect();
+        for i in self.strategy.order(&sizes) {
+            let result = match choices[i] {
+                Choice::Focus(i) => self.prove_focused(seq, i, pool, depth, k),
+                Choice::FocusPool(i) => self.focus_pool(seq, i, pool, depth, k),
+                Choice::Axiom(i) => self.try_axiom(seq, i, pool, k),
+                Choice::Dereliction(i) => self.try_dereliction(seq, i, pool, depth, k),
+                Choice::Contraction(i) => self.try_contraction(seq, i, pool, depth, k),
+                Choice::Weakening(i) => self.try_weakening(seq, i, pool, depth, k),
+            };
+            if result.is_some() {
+                return result;
             }
         }
-
         None
     }
 
-    /// Try dereliction: move a formula from unrestricted to linear zone.
-    fn try_dereliction(&mut self, seq: &Sequent, depth: usize) -> Option<Proof> {
-        for i in 0..seq.unrestricted.len() {
-            let formula = &seq.unrestricted[i];
-
-            // Add the formula to linear zone (keeping it in unrestricted for potential reuse)
-            let mut new_linear = seq.linear.clone();
-            new_linear.push(formula.clone());
-
-            // Remove from unrestricted (for this branch - it can be reused via contraction)
-            let mut new_unrestricted = seq.unrestricted.clone();
-            new_unrestricted.remove(i);
-
-            let new_seq = Sequent {
-                linear: new_linear,
-                unrestricted: new_unrestricted,
-                focus: None,
-            };
+    /// Focus on the pool formula at index `i`, consuming it.
+    fn focus_pool(
+        &mut self,
+        seq: &Sequent,
+        i: usize,
+        pool: &[Formula],
+        depth: usize,
+        k: &mut Cont<'_>,
+    ) -> Option<Proof> {
+        let mut new_linear = seq.linear.clone();
+        new_linear.push(pool[i].clone());
+        let new_seq = Sequent {
+            linear: new_linear,
+            unrestricted: seq.unrestricted.clone(),
+            focus: None,
+        };
+        let mut rest = pool.to_vec();
+        rest.remove(i);
+
+        let focus = seq.linear.len();
+        self.prove_focused(&new_seq, focus, &rest, depth, &mut |p, proof, mut used| {
+            used.insert(i, true);
+            k(p, proof, used)
+        })
+    }
 
-            if let Some(premise) = self.prove_with_depth(&new_seq, depth + 1) {
-                return Some(Proof {
-                    conclusion: seq.clone(),
-                    rule: Rule::Dereliction,
-                    premises: vec![premise],
-                });
-            }
-        }
-        None
+    /// Try dereliction: move a formula from unrestricted to linear zone.
+    fn try_dereliction(
+        &mut self,
+        seq: &Sequent,
+        i: usize,
+        pool: &[Formula],
+        depth: usize,
+        k: &mut Cont<'_>,
+    ) -> Option<Proof> {
+        // Add the formula to linear zone
+        let mut new_linear = seq.linear.clone();
+        new_linear.push(seq.unrestricted[i].clone());
+
+        // Remove from unrestricted (for this branch - it can be reused via contraction)
+        let mut new_unrestricted = seq.unrestricted.clone();
+        new_unrestricted.remove(i);
+
+        let new_seq = Sequent {
+            linear: new_linear,
+            unrestricted: new_unrestricted,
+            focus: None,
+        };
+
+        self.prove_io(&new_seq, pool, depth + 1, &mut |p, premise, used| {
+            let proof = conclude(seq, pool, &used, Rule::Dereliction, vec![premise]);
+            k(p, proof, used)
+        })
     }
 
     /// Try contraction: duplicate an unrestricted formula.
-    fn try_contraction(&mut self, seq: &Sequent, depth: usize) -> Option<Proof> {
-        for i in 0..seq.unrestricted.len() {
-            let formula = &seq.unrestricted[i];
-
-            // Add two copies to linear zone
-            let mut new_linear = seq.linear.clone();
-            new_linear.push(formula.clone());
-            new_linear.push(formula.clone());
-
-            // Remove from unrestricted
-            let mut new_unrestricted = seq.unrestricted.clone();
-            new_unrestricted.remove(i);
-
-            let new_seq = Sequent {
-                linear: new_linear,
-                unrestricted: new_unrestricted,
-                focus: None,
-            };
-
-            if let Some(premise) = self.prove_with_depth(&new_seq, depth + 1) {
-                return Some(Proof {
-                    conclusion: seq.clone(),
-                    rule: Rule::Contraction,
-                    premises: vec![premise],
-                });
-            }
-        }
-        None
+    fn try_contraction(
+        &mut self,
+        seq: &Sequent,
+        i: usize,
+        pool: &[Formula],
+        depth: usize,
+        k: &mut Cont<'_>,
+    ) -> Option<Proof> {
+        let formula = &seq.unrestricted[i];
+
+        // Add two copies to linear zone
+        let mut new_linear = seq.linear.clone();
+        new_linear.push(formula.clone());
+        new_linear.push(formula.clone());
+
+        // Remove from unrestricted
+        let mut new_unrestricted = seq.unrestricted.clone();
+        new_unrestricted.remove(i);
+
+        let new_seq = Sequent {
+            linear: new_linear,
+            unrestricted: new_unrestricted,
+            focus: None,
+        };
+
+        self.prove_io(&new_seq, pool, depth + 1, &mut |p, premise, used| {
+            let proof = conclude(seq, pool, &used, Rule::Contraction, vec![premise]);
+            k(p, proof, used)
+        })
     }
 
     /// Try weakening: discard an unrestricted formula.
-    fn try_weakening(&mut self, seq: &Sequent, depth: usize) -> Option<Proof> {
-        for i in 0..seq.unrestricted.len() {
-            // Remove the unrestricted formula (discard it)
-            let mut new_unrestricted = seq.unrestricted.clone();
-            new_unrestricted.remove(i);
-
-            let new_seq = Sequent {
-                linear: seq.linear.clone(),
-                unrestricted: new_unrestricted,
-                focus: None,
-            };
-
-            if let Some(premise) = self.prove_with_depth(&new_seq, depth + 1) {
-                return Some(Proof {
-                    conclusion: seq.clone(),
-                    rule: Rule::Weakening,
-                    premises: vec![premise],
-                });
-            }
-        }
-        None
+    fn try_weakening(
+        &mut self,
+        seq: &Sequent,
+        i: usize,
+        pool: &[Formula],
+        depth: usize,
+        k: &mut Cont<'_>,
+    ) -> Option<Proof> {
+        // Remove the unrestricted formula (discard it)
+        let mut new_unrestricted = seq.unrestricted.clone();
+        new_unrestricted.remove(i);
+
+        let new_seq = Sequent {
+            linear: seq.linear.clone(),
+            unrestricted: new_unrestricted,
+            focus: None,
+        };
+
+        self.prove_io(&new_seq, pool, depth + 1, &mut |p, premise, used| {
+            let proof = conclude(seq, pool, &used, Rule::Weakening, vec![premise]);
+            k(p, proof, used)
+        })
     }
 
     /// Focused phase: decompose a positive formula.
-    fn prove_focused(&mut self, seq: &Sequent, idx: usize, depth: usize) -> Option<Proof> {
+    fn prove_focused(
+        &mut self,
+        seq: &Sequent,
+        idx: usize,
+        pool: &[Formula],
+        depth: usize,
+        k: &mut Cont<'_>,
+    ) -> Option<Proof> {
         let formula = &seq.linear[idx];
+        let nothing_used = vec![false; pool.len()];
 
         match formula {
             // Atom - look for matching negated atom (axiom)
             Formula::Atom(name) => {
-                // Look for A⊥ in the context
-                for (j, other) in seq.linear.iter().enumerate() {
-                    if j != idx {
-                        if let Formula::NegAtom(other_name) = other {
-                            if name == other_name {
-                                // Check that these are the only two formulas
-                                if seq.linear.len() == 2 {
-                                    return Some(Proof {
-                                        conclusion: seq.clone(),
-                                        rule: Rule::Axiom,
-                                        premises: vec![],
-                                    });
-                                }
-                            }
-                        }
-                    }
+                let dual = Formula::NegAtom(name.clone());
+                let mut used = nothing_used;
+                match seq.linear.len() {
+                    // ⊢ A, A⊥ with both formulas already in the sequent
+                    2 if seq.linear[1 - idx] == dual => {}
+                    // Take A⊥ from the pool
+                    1 => used[pool.iter().position(|f| *f == dual)?] = true,
+                    _ => return None,
                 }
-                None
+                let proof = conclude(seq, pool, &used, Rule::Axiom, vec![]);
+                k(self, proof, used)
             }
 
             // One (1) - context must be empty
             Formula::One => {
                 if seq.linear.len() == 1 {
-                    Some(Proof {
-                        conclusion: seq.clone(),
-                        rule: Rule::OneIntro,
-                        premises: vec![],
-                    })
+                    let proof = conclude(seq, pool, &nothing_used, Rule::OneIntro, vec![]);
+                    k(self, proof, nothing_used)
                 } else {
                     None
                 }
@@ -417,80 +946,101 @@ impl Prover {
             // Zero (0) - never provable
             Formula::Zero => None,
 
-            // Tensor (⊗) - split the context
+            // Tensor (⊗) - the left premise takes what it needs from the rest
+            // of the context, and the right premise gets the leftovers
             Formula::Tensor(a, b) => {
-                let mut other_formulas: Vec<Formula> = seq.linear.clone();
-                other_formulas.remove(idx);
-
-                // Try all possible splits of the remaining context
-                for split in all_splits(&other_formulas) {
-                    let (left_ctx, right_ctx) = split;
+                let mut rest = seq.linear.clone();
+                rest.remove(idx);
 
-                    let mut left_linear = left_ctx;
-                    left_linear.push(a.as_ref().clone());
-                    let left_seq = Sequent {
-                        linear: left_linear,
-                        unrestricted: seq.unrestricted.clone(),
-                        focus: None,
-                    };
+                let left_seq = Sequent {
+                    linear: vec![a.as_ref().clone()],
+                    unrestricted: seq.unrestricted.clone(),
+                    focus: None,
+                };
+                let mut left_pool = rest.clone();
+                left_pool.extend(pool.iter().cloned());
+
+                // Leftovers already handed to the right premise without success
+                let mut tried = HashSet::new();
+
+                let mut on_left = |p: &mut Prover, left: Proof, left_used: Vec<bool>| {
+                    let (rest_used, pool_used) = left_used.split_at(rest.len());
+                    let rest_left = unused(&rest, rest_used);
+                    let pool_left = unused(pool, pool_used);
+
+                    // A ⊤ in the left premise can take over any of the rest of
+                    // the context, so the right premise need not consume it
+                    let slack = can_absorb(&left);
+                    let mut key: Vec<String> = rest_left.iter().map(Formula::pretty).collect();
+                    key.sort();
+                    if !tried.insert((key, pool_used.to_vec(), slack)) {
+                        return None;
+                    }
 
-                    let mut right_linear = right_ctx;
-                    right_linear.push(b.as_ref().clone());
+                    let optional = if slack { rest_left.len() } else { 0 };
+                    let mut right_linear = vec![b.as_ref().clone()];
+                    right_linear.extend(rest_left[optional..].iter().cloned());
                     let right_seq = Sequent {
                         linear: right_linear,
                         unrestricted: seq.unrestricted.clone(),
                         focus: None,
                     };
+                    let mut right_pool = rest_left[..optional].to_vec();
+                    right_pool.extend(pool_left);
+                    if !p.balanced(&right_seq, &right_pool) {
+                        return None;
+                    }
 
-                    if let Some(left_proof) = self.prove_with_depth(&left_seq, depth + 1) {
-                        if let Some(right_proof) = self.prove_with_depth(&right_seq, depth + 1) {
-                            return Some(Proof {
-                                conclusion: seq.clone(),
-                                rule: Rule::TensorIntro,
-                                premises: vec![left_proof, right_proof],
-                            });
+                    let mut on_right = |p: &mut Prover, right: Proof, right_used: Vec<bool>| {
+                        let (absorbed, from_pool) = right_used.split_at(optional);
+                        let mut left = left.clone();
+                        let extra = unused(&rest_left[..optional], absorbed);
+                        if !extra.is_empty() {
+                            absorb(&mut left, &extra);
                         }
-                    }
+
+                        let mut used = pool_used.to_vec();
+                        let mut from_pool = from_pool.iter();
+                        for u in used.iter_mut().filter(|u| !**u) {
+                            *u = *from_pool.next().unwrap_or(&false);
+                        }
+
+                        let premises = vec![left, right];
+                        let proof = conclude(seq, pool, &used, Rule::TensorIntro, premises);
+                        k(p, proof, used)
+                    };
+                    p.prove_io(&right_seq, &right_pool, depth + 1, &mut on_right)
+                };
+                if !self.balanced(&left_seq, &left_pool) {
+                    return None;
                 }
-                None
+                self.prove_io(&left_seq, &left_pool, depth + 1, &mut on_left)
             }
 
             // Plus (⊕) - choose left or right
             Formula::Plus(a, b) => {
-                // Try left
-                let mut left_linear = seq.linear.clone();
-                left_linear[idx] = a.as_ref().clone();
-                let left_seq = Sequent {
-                    linear: left_linear,
-                    unrestricted: seq.unrestricted.clone(),
-                    focus: None,
-                };
-
-                if let Some(premise) = self.prove_with_depth(&left_seq, depth + 1) {
-                    return Some(Proof {
-                        conclusion: seq.clone(),
-                        rule: Rule::PlusIntroLeft,
-                        premises: vec![premise],
-                    });
-                }
-
-                // Try right
-                let mut right_linear = seq.linear.clone();
-                right_linear[idx] = b.as_ref().clone();
-                let right_seq = Sequent {
-                    linear: right_linear,
-                    unrestricted: seq.unrestricted.clone(),
-                    focus: None,
-                };
+                let branches = [(a, Rule::PlusIntroLeft), (b, Rule::PlusIntroRight)];
+                let base = sequent_size(seq) - formula_size(formula);
+                let sizes = [base + formula_size(a), base + formula_size(b)];
+                for i in self.strategy.order(&sizes) {
+                    let (part, rule) = &branches[i];
+                    let mut new_linear = seq.linear.clone();
+                    new_linear[idx] = part.as_ref().clone();
+                    let new_seq = Sequent {
+                        linear: new_linear,
+                        unrestricted: seq.unrestricted.clone(),
+                        focus: None,
+                    };
 
-                if let Some(premise) = self.prove_with_depth(&right_seq, depth + 1) {
-                    return Some(Proof {
-                        conclusion: seq.clone(),
-                        rule: Rule::PlusIntroRight,
-                        premises: vec![premise],
-                    });
+                    let result =
+                        self.prove_io(&new_seq, pool, depth + 1, &mut |p, premise, used| {
+                            let proof = conclude(seq, pool, &used, rule.clone(), vec![premise]);
+                            k(p, proof, used)
+                        });
+                    if result.is_some() {
+                        return result;
+                    }
                 }
-
                 None
             }
 
@@ -505,13 +1055,10 @@ impl Prover {
                         focus: None,
                     };
 
-                    if let Some(premise) = self.prove_with_depth(&new_seq, depth + 1) {
-                        return Some(Proof {
-                            conclusion: seq.clone(),
-                            rule: Rule::OfCourseIntro,
-                            premises: vec![premise],
-                        });
-                    }
+                    let premise = self.prove_with_depth(&new_seq, depth + 1)?;
+                    let proof =
+                        conclude(seq, pool, &nothing_used, Rule::OfCourseIntro, vec![premise]);
+                    return k(self, proof, nothing_used);
                 }
                 None
             }
@@ -521,18 +1068,22 @@ impl Prover {
     }
 
     /// Try to apply the axiom rule with a negated atom.
-    fn try_axiom(&mut self, seq: &Sequent, neg_idx: usize) -> Option<Proof> {
+    fn try_axiom(
+        &mut self,
+        seq: &Sequent,
+        neg_idx: usize,
+        pool: &[Formula],
+        k: &mut Cont<'_>,
+    ) -> Option<Proof> {
         if let Formula::NegAtom(name) = &seq.linear[neg_idx] {
             // Look for matching positive atom
             for (j, other) in seq.linear.iter().enumerate() {
                 if j != neg_idx {
                     if let Formula::Atom(other_name) = other {
                         if name == other_name && seq.linear.len() == 2 {
-                            return Some(Proof {
-                                conclusion: seq.clone(),
-                                rule: Rule::Axiom,
-                                premises: vec![],
-                            });
+                            let used = vec![false; pool.len()];
+                            let proof = conclude(seq, pool, &used, Rule::Axiom, vec![]);
+                            return k(self, proof, used);
                         }
                     }
                 }
@@ -542,43 +1093,212 @@ impl Prover {
     }
 
     /// Create a canonical key for a sequent (for caching).
-    fn sequent_key(&self, seq: &Sequent) -> Vec<String> {
+    pub(crate) fn sequent_key(&self, seq: &Sequent) -> Vec<String> {
         let mut keys: Vec<String> = seq.linear.iter().map(|f| f.pretty()).collect();
-        // Include unrestricted formulas in cache key with a marker
-        for f in &seq.unrestricted {
-            keys.push(format!("?{}", f.pretty()));
-        }
         keys.sort();
+        // Unrestricted formulas follow a separator. Marking them with `?`
+        // instead would give ⊢ ?A and ⊢ A in the unrestricted zone one key,
+        // though they lie a step apart in depth.
+        let mut unrestricted: Vec<String> = seq.unrestricted.iter().map(|f| f.pretty()).collect();
+        unrestricted.sort();
+        keys.push(";".to_string());
+        keys.extend(unrestricted);
         keys
     }
 }
 
-/// Generate all possible ways to split a list into two parts.
-fn all_splits<T: Clone>(items: &[T]) -> Vec<(Vec<T>, Vec<T>)> {
-    if items.is_empty() {
-        return vec![(vec![], vec![])];
+/// One way to continue the synchronous phase.
+#[derive(Debug, Clone, Copy)]
+enum Choice {
+    /// Focus on the linear formula at this index
+    Focus(usize),
+    /// Focus on the pool formula at this index
+    FocusPool(usize),
+    /// Close an axiom with the negated atom at this index
+    Axiom(usize),
+    /// Use the unrestricted formula at this index once
+    Dereliction(usize),
+    /// Use the unrestricted formula at this index twice
+    Contraction(usize),
+    /// Discard the unrestricted formula at this index
+    Weakening(usize),
+}
+
+impl Choice {
+    /// The size of the sequent this choice leads to, given the size `total`
+    /// of `seq`. Focusing removes the outermost connective.
+    fn size_after(self, seq: &Sequent, pool: &[Formula], total: usize) -> usize {
+        match self {
+            Choice::Focus(i) => match &seq.linear[i] {
+                Formula::Atom(_) => total.saturating_sub(2),
+                _ => total - 1,
+            },
+            Choice::FocusPool(i) => total + formula_size(&pool[i]) - 1,
+            Choice::Axiom(_) => total.saturating_sub(2),
+            Choice::Dereliction(_) => total,
+            Choice::Contraction(i) => total + formula_size(&seq.unrestricted[i]),
+            Choice::Weakening(i) => total - formula_size(&seq.unrestricted[i]),
+        }
     }
+}
 
-    let n = items.len();
-    let mut splits = Vec::new();
+/// Returns true if the sequent mentions `!` or `?` anywhere.
+pub(crate) fn has_exponentials(seq: &Sequent) -> bool {
+    fn check(f: &Formula) -> bool {
+        match f {
+            Formula::OfCourse(_) | Formula::WhyNot(_) => true,
+            Formula::Tensor(a, b)
+            | Formula::Par(a, b)
+            | Formula::With(a, b)
+            | Formula::Plus(a, b)
+            | Formula::Lolli(a, b) => check(a) || check(b),
+            _ => false,
+        }
+    }
+    !seq.unrestricted.is_empty() || seq.linear.iter().chain(&seq.focus).any(check)
+}
 
-    // Each item can go to left (0) or right (1)
-    for mask in 0..(1 << n) {
-        let mut left = Vec::new();
-        let mut right = Vec::new();
+/// Returns true if `⊤` occurs anywhere in the sequent.
+fn mentions_top(seq: &Sequent) -> bool {
+    fn check(f: &Formula) -> bool {
+        match f {
+            Formula::Top => true,
+            Formula::Tensor(a, b)
+            | Formula::Par(a, b)
+            | Formula::With(a, b)
+            | Formula::Plus(a, b)
+            | Formula::Lolli(a, b) => check(a) || check(b),
+            Formula::OfCourse(a) | Formula::WhyNot(a) => check(a),
+            _ => false,
+        }
+    }
+    seq.linear.iter().chain(&seq.unrestricted).any(check)
+}
 
-        for (i, item) in items.iter().enumerate() {
-            if (mask >> i) & 1 == 0 {
-                left.push(item.clone());
-            } else {
-                right.push(item.clone());
-            }
+/// A negated atom that no parsed formula can mention.
+fn slack_marker() -> Formula {
+    Formula::NegAtom("#slack".to_string())
+}
+
+/// Remove the [`slack_marker`] from every conclusion of `proof`.
+fn strip_marker(proof: &mut Proof) {
+    let marker = slack_marker();
+    if let Some(pos) = proof.conclusion.linear.iter().position(|f| *f == marker) {
+        proof.conclusion.linear.remove(pos);
+        for premise in &mut proof.premises {
+            strip_marker(premise);
         }
+    }
+}
 
-        splits.push((left, right));
+/// Build the proof step for `rule` applied to `seq` plus the `used` pool formulas.
+fn conclude(
+    seq: &Sequent,
+    pool: &[Formula],
+    used: &[bool],
+    rule: Rule,
+    premises: Vec<Proof>,
+) -> Proof {
+    let mut linear = seq.linear.clone();
+    linear.extend(
+        pool.iter()
+            .zip(used)
+            .filter(|(_, u)| **u)
+            .map(|(f, _)| f.clone()),
+    );
+    Proof {
+        conclusion: Sequent {
+            linear,
+            unrestricted: seq.unrestricted.clone(),
+            focus: None,
+        },
+        rule,
+        premises,
     }
+}
 
-    splits
+/// Move each mark in `used` to the earliest copy of its formula in `pool`.
+///
+/// Equal formulas are interchangeable, so this describes the same multiset.
+/// Callers put the formulas they most want consumed first.
+fn earliest_copies(pool: &[Formula], used: &[bool]) -> Vec<bool> {
+    let mut moved = vec![false; pool.len()];
+    for (i, _) in used.iter().enumerate().filter(|(_, u)| **u) {
+        let first = (0..pool.len())
+            .find(|&j| !moved[j] && pool[j] == pool[i])
+            .unwrap_or(i);
+        moved[first] = true;
+    }
+    moved
+}
+
+/// The formulas of `items` not marked in `used`.
+fn unused(items: &[Formula], used: &[bool]) -> Vec<Formula> {
+    items
+        .iter()
+        .zip(used)
+        .filter(|(_, u)| !**u)
+        .map(|(f, _)| f.clone())
+        .collect()
+}
+
+/// Returns true if a `⊤` leaf of `proof` can absorb extra formulas.
+///
+/// The extra formulas are added along a path from the root to the leaf, so
+/// every step on the way must pass its context to a premise unchanged.
+pub(crate) fn can_absorb(proof: &Proof) -> bool {
+    match proof.rule {
+        Rule::TopIntro => true,
+        Rule::TensorIntro => proof.premises.iter().any(can_absorb),
+        Rule::WithIntro => proof.premises.iter().all(can_absorb),
+        Rule::ParIntro
+        | Rule::BottomIntro
+        | Rule::PlusIntroLeft
+        | Rule::PlusIntroRight
+        | Rule::WhyNotIntro
+        | Rule::Dereliction
+        | Rule::Contraction
+        | Rule::Weakening => proof.premises.iter().all(can_absorb),
+        _ => false,
+    }
+}
+
+/// Add `extra` to the context of `proof`, handing it down to a `⊤` leaf.
+///
+/// The proof must satisfy [`can_absorb`].
+pub(crate) fn absorb(proof: &mut Proof, extra: &[Formula]) {
+    proof.conclusion.linear.extend(extra.iter().cloned());
+    if proof.rule == Rule::WithIntro {
+        for premise in &mut proof.premises {
+            absorb(premise, extra);
+        }
+    } else if let Some(premise) = proof.premises.iter_mut().find(|p| can_absorb(p)) {
+        absorb(premise, extra);
+    }
+}
+
+/// Make `proof`, which consumed the `used` pool formulas, consume `target`.
+///
+/// Returns `None` if the missing formulas cannot be absorbed by a `⊤`.
+fn absorb_missing(
+    mut proof: Proof,
+    pool: &[Formula],
+    used: &[bool],
+    target: &[bool],
+) -> Option<Proof> {
+    let extra: Vec<Formula> = pool
+        .iter()
+        .zip(used.iter().zip(target))
+        .filter(|(_, (u, t))| **t && !**u)
+        .map(|(f, _)| f.clone())
+        .collect();
+    if !extra.is_empty() {
+        if !can_absorb(&proof) {
+            return None;
+        }
+        absorb(&mut proof, &extra);
+    }
+    Some(proof)
 }
 
 #[cfg(test)]
@@ -589,11 +1309,98 @@ mod tests {
         Formula::atom(name)
     }
 
-    #[allow(dead_code)]
     fn neg_atom(name: &str) -> Formula {
         Formula::neg_atom(name)
     }
 
+    fn sorted(formulas: &[Formula]) -> Vec<String> {
+        // The search unfolds ⊸ without a proof step of its own
+        let mut keys: Vec<String> = formulas.iter().map(|f| f.desugar().pretty()).collect();
+        keys.sort();
+        keys
+    }
+
+    /// Check that every step of a MALL proof has exactly the context its
+    /// rule requires, as multisets.
+    fn assert_concrete(proof: &Proof) {
+        let conclusion = &proof.conclusion.linear;
+        let premise = |i: usize| &proof.premises[i].conclusion.linear;
+        // Remove `principal` from the conclusion and add `replaced`
+        let expect = |principal: &Formula, replaced: &[&Formula]| -> Vec<Formula> {
+            let mut expected = conclusion.clone();
+            let pos = expected.iter().position(|f| f == principal).unwrap();
+            expected.remove(pos);
+            expected.extend(replaced.iter().map(|f| (*f).clone()));
+            expected
+        };
+
+        match &proof.rule {
+            Rule::Axiom => assert_eq!(conclusion.len(), 2),
+            Rule::OneIntro => assert_eq!(conclusion, &vec![Formula::One]),
+            Rule::TopIntro => assert!(conclusion.contains(&Formula::Top)),
+            Rule::TensorIntro => {
+                let (left, right) = (premise(0), premise(1));
+                let found = conclusion.iter().any(|f| match f {
+                    Formula::Tensor(a, b) => {
+                        let (mut l, mut r) = (left.clone(), right.clone());
+                        let find = |v: &[Formula], g: &Formula| {
+                            v.iter().position(|x| x.desugar() == g.desugar())
+                        };
+                        match (find(&l, a), find(&r, b)) {
+                            (Some(i), Some(j)) => {
+                                l.remove(i);
+                                r.remove(j);
+                                l.extend(r);
+                                l.push(f.clone());
+                                sorted(&l) == sorted(conclusion)
+                            }
+                            _ => false,
+                        }
+                    }
+                    _ => false,
+                });
+                assert!(found, "bad tensor step: {}", proof.conclusion.pretty());
+            }
+            Rule::WithIntro => {
+                let found = conclusion.iter().any(|f| match f {
+                    Formula::With(a, b) => {
+                        sorted(premise(0)) == sorted(&expect(f, &[a]))
+                            && sorted(premise(1)) == sorted(&expect(f, &[b]))
+                    }
+                    _ => false,
+                });
+                assert!(found, "bad with step: {}", proof.conclusion.pretty());
+            }
+            Rule::ParIntro => {
+                let found = conclusion.iter().any(|f| match f {
+                    Formula::Par(a, b) => sorted(premise(0)) == sorted(&expect(f, &[a, b])),
+                    _ => false,
+                });
+                assert!(found, "bad par step: {}", proof.conclusion.pretty());
+            }
+            Rule::BottomIntro => {
+                let expected = expect(&Formula::Bottom, &[]);
+                assert_eq!(sorted(premise(0)), sorted(&expected));
+            }
+            Rule::PlusIntroLeft | Rule::PlusIntroRight => {
+                let left = proof.rule == Rule::PlusIntroLeft;
+                let found = conclusion.iter().any(|f| match f {
+                    Formula::Plus(a, b) => {
+                        let chosen = if left { a } else { b };
+                        sorted(premise(0)) == sorted(&expect(f, &[chosen]))
+                    }
+                    _ => false,
+                });
+                assert!(found, "bad plus step: {}", proof.conclusion.pretty());
+            }
+            _ => {}
+        }
+
+        for p in &proof.premises {
+            assert_concrete(p);
+        }
+    }
+
     #[test]
     fn test_identity() {
         // A ⊢ A
@@ -703,11 +1510,112 @@ mod tests {
         assert!(result.is_some(), "⊢ A ⊸ A should be provable");
     }
 
+    // ===== Resource Management Tests =====
+
+    fn prove_concrete(seq: &TwoSidedSequent) -> Option<Proof> {
+        let proof = Prover::new(100).prove_two_sided(seq)?;
+        assert_concrete(&proof);
+        Some(proof)
+    }
+
+    #[test]
+    fn test_top_absorbs_leftovers() {
+        // A, B ⊢ ⊤ ⊗ A: the ⊤ must take B, but not A
+        let seq = TwoSidedSequent::new(
+            vec![atom("A"), atom("B")],
+            vec![Formula::tensor(Formula::Top, atom("A"))],
+        );
+        let proof = prove_concrete(&seq).expect("A, B ⊢ ⊤ ⊗ A should be provable");
+        let top = &proof.premises[0];
+        assert_eq!(top.rule, Rule::TopIntro);
+        assert_eq!(
+            sorted(&top.conclusion.linear),
+            sorted(&[neg_atom("B"), Formula::Top])
+        );
+    }
+
+    #[test]
+    fn test_with_shares_leftovers() {
+        // A, B ⊢ (B & B) ⊗ A: both branches of & must take the same B
+        let seq = TwoSidedSequent::new(
+            vec![atom("A"), atom("B")],
+            vec![Formula::tensor(
+                Formula::with(atom("B"), atom("B")),
+                atom("A"),
+            )],
+        );
+        assert!(prove_concrete(&seq).is_some());
+
+        // C, A ⊢ (C & ⊤) ⊗ A: the ⊤ branch must absorb the C used by the other
+        let seq = TwoSidedSequent::new(
+            vec![atom("C"), atom("A")],
+            vec![Formula::tensor(
+                Formula::with(atom("C"), Formula::Top),
+                atom("A"),
+            )],
+        );
+        assert!(prove_concrete(&seq).is_some());
+
+        // A, B ⊢ (A & B) ⊗ 1 has no proof: the branches would need different resources
+        let seq = TwoSidedSequent::new(
+            vec![atom("A"), atom("B")],
+            vec![Formula::tensor(
+                Formula::with(atom("A"), atom("B")),
+                Formula::One,
+            )],
+        );
+        assert!(Prover::new(100).decide_two_sided(&seq).is_refuted());
+    }
+
+    #[test]
+    fn test_large_context() {
+        // Six acquisitions used to take tens of seconds with 2^n context splits
+        let n = 6;
+        let mut antecedent = vec![atom("Pool")];
+        antecedent.extend((0..n).map(|_| Formula::lolli(atom("Available"), atom("InUse"))));
+        antecedent.extend((0..n).map(|_| atom("Available")));
+        let goal = (0..n).fold(atom("Pool"), |acc, _| Formula::tensor(acc, atom("InUse")));
+        let seq = TwoSidedSequent::new(antecedent.clone(), vec![goal]);
+        assert!(prove_concrete(&seq).is_some());
+
+        // One resource short
+        antecedent.pop();
+        let goal = (0..n).fold(atom("Pool"), |acc, _| Formula::tensor(acc, atom("InUse")));
+        let seq = TwoSidedSequent::new(antecedent, vec![goal]);
+        assert!(Prover::new(100).decide_two_sided(&seq).is_refuted());
+    }
+
     #[test]
-    fn test_all_splits() {
-        let items = vec![1, 2];
-        let splits = all_splits(&items);
-        assert_eq!(splits.len(), 4); // 2^2 = 4 ways to split 2 items
+    fn test_interchangeable_hypotheses() {
+        // Without balance pruning or proof nets, resource passing alone has
+        // to treat equal hypotheses as one. 18 hypotheses took seconds when
+        // each order of the leftovers was searched separately.
+        let n = 9;
+        let mut antecedent = vec![atom("Pool")];
+        antecedent.extend((0..n).map(|_| Formula::lolli(atom("Available"), atom("InUse"))));
+        antecedent.extend((0..n).map(|_| atom("Available")));
+        let goal = || (0..n).fold(atom("Pool"), |acc, _| Formula::tensor(acc, atom("InUse")));
+        let unpruned = || {
+            let mut prover = Prover::new(100);
+            prover.use_balance = false;
+            prover.use_proof_nets = false;
+            prover
+        };
+        let seq = TwoSidedSequent::new(antecedent.clone(), vec![goal()]);
+        let proof = unpruned().prove_two_sided(&seq).expect("provable");
+        assert!(crate::verify_proof(&proof).is_ok());
+
+        // One resource short
+        antecedent.pop();
+        let seq = TwoSidedSequent::new(antecedent, vec![goal()]);
+        assert!(unpruned().decide_two_sided(&seq).is_refuted());
+    }
+
+    #[test]
+    fn test_proofs_have_concrete_contexts() {
+        for seq in cache_samples() {
+            prove_concrete(&seq);
+        }
     }
 
     // ===== Exponential Tests =====
@@ -801,4 +1709,302 @@ mod tests {
         let result = prover.prove_two_sided(&seq);
         assert!(result.is_some(), "!A ⊢ A ⊗ A ⊗ A should be provable");
     }
+
+    // ===== Outcome Tests =====
+
+    #[test]
+    fn test_decide_provable() {
+        let mut prover = Prover::new(100);
+        let seq = TwoSidedSequent::new(vec![atom("A")], vec![atom("A")]);
+        let outcome = prover.decide_two_sided(&seq);
+        assert!(outcome.is_provable());
+        assert!(outcome.proof().is_some());
+    }
+
+    #[test]
+    fn test_decide_refuted() {
+        // A, A ⊢ A is finite MALL search with no proof
+        let mut prover = Prover::new(100);
+        let seq = TwoSidedSequent::new(vec![atom("A"), atom("A")], vec![atom("A")]);
+        assert!(prover.decide_two_sided(&seq).is_refuted());
+        assert_eq!(prover.stats().depth_cutoffs, 0);
+    }
+
+    #[test]
+    fn test_decide_depth_limit() {
+        // Provable, but not within one step
+        let mut prover = Prover::new(1);
+        prover.use_proof_nets = false;
+        let seq = TwoSidedSequent::new(
+            vec![Formula::tensor(atom("A"), atom("B"))],
+            vec![Formula::tensor(atom("B"), atom("A"))],
+        );
+        let outcome = prover.decide_two_sided(&seq);
+        assert!(matches!(
+            outcome,
+            ProofOutcome::Unknown(UnknownReason::DepthLimit)
+        ));
+        assert!(prover.stats().depth_cutoffs > 0);
+
+        // The same prover, given enough depth, must not reuse stale failures
+        prover.max_depth = 100;
+        assert!(prover.decide_two_sided(&seq).is_provable());
+    }
+
+    #[test]
+    fn test_decide_exponentials_not_refuted() {
+        let mut prover = Prover::new(100);
+        // !(A ⊗ B) ⊢ B passes the balance check, since A and B sit under a !
+        let seq = TwoSidedSequent::new(
+            vec![Formula::of_course(Formula::tensor(atom("A"), atom("B")))],
+            vec![atom("B")],
+        );
+        assert!(matches!(
+            prover.decide_two_sided(&seq),
+            ProofOutcome::Unknown(UnknownReason::Exponentials)
+        ));
+    }
+
+    // ===== Cache Tests =====
+
+    fn outcome_kind(outcome: &ProofOutcome) -> &'static str {
+        match outcome {
+            ProofOutcome::Provable(_) => "provable",
+            ProofOutcome::Refuted => "refuted",
+            ProofOutcome::Unknown(_) => "unknown",
+        }
+    }
+
+    fn cache_samples() -> Vec<TwoSidedSequent> {
+        let a = || atom("A");
+        let b = || atom("B");
+        let c = || atom("C");
+        vec![
+            TwoSidedSequent::new(vec![a(), b()], vec![Formula::tensor(b(), a())]),
+            TwoSidedSequent::new(
+                vec![Formula::tensor(a(), b()), c()],
+                vec![Formula::tensor(c(), Formula::tensor(b(), a()))],
+            ),
+            TwoSidedSequent::new(
+                vec![Formula::lolli(a(), b()), Formula::lolli(b(), c()), a()],
+                vec![c()],
+            ),
+            TwoSidedSequent::new(
+                vec![Formula::with(a(), b()), c()],
+                vec![Formula::tensor(Formula::plus(b(), c()), c())],
+            ),
+            TwoSidedSequent::new(
+                vec![Formula::plus(a(), b())],
+                vec![Formula::plus(b(), Formula::with(a(), a()))],
+            ),
+            TwoSidedSequent::new(
+                vec![a(), a(), b()],
+                vec![Formula::tensor(Formula::tensor(a(), b()), a())],
+            ),
+            TwoSidedSequent::new(vec![a(), b()], vec![Formula::tensor(a(), a())]),
+        ]
+    }
+
+    #[test]
+    fn test_cache_does_not_change_answers() {
+        for seq in cache_samples() {
+            for depth in 0..12 {
+                let mut cached = Prover::new(depth);
+                let mut uncached = Prover::new(depth);
+                uncached.use_cache = false;
+                assert_eq!(
+                    outcome_kind(&cached.decide_two_sided(&seq)),
+                    outcome_kind(&uncached.decide_two_sided(&seq)),
+                    "cache changed the answer for {} at depth {}",
+                    seq.pretty(),
+                    depth
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_cache_reused_across_depths() {
+        // A prover whose cache survives changes to max_depth must find every
+        // proof a fresh prover finds. It may settle more questions than a
+        // fresh prover, thanks to definitive failures, but never wrongly.
+        for seq in cache_samples() {
+            let truth = outcome_kind(&Prover::new(100).decide_two_sided(&seq));
+            let mut shared = Prover::new(0);
+            for depth in (0..12).chain((0..12).rev()) {
+                shared.max_depth = depth;
+                let got = outcome_kind(&shared.decide_two_sided(&seq));
+                let fresh = outcome_kind(&Prover::new(depth).decide_two_sided(&seq));
+                assert!(
+                    got == fresh || (fresh == "unknown" && got == truth),
+                    "stale cache entry for {} at depth {}: got {}, expected {}",
+                    seq.pretty(),
+                    depth,
+                    got,
+                    fresh
+                );
+            }
+        }
+    }
+
+    // ===== Limit Tests =====
+
+    /// Unprovable, and without a cache the contractions make it explode.
+    fn runaway() -> (Prover, TwoSidedSequent) {
+        let mut prover = Prover::new(60);
+        prover.use_cache = false;
+        let seq = TwoSidedSequent::new(
+            vec![
+                Formula::of_course(Formula::lolli(atom("A"), atom("B"))),
+                Formula::of_course(Formula::lolli(atom("B"), atom("A"))),
+                Formula::of_course(atom("C")),
+                Formula::of_course(atom("D")),
+                Formula::of_course(atom("E")),
+            ],
+            vec![atom("A")],
+        );
+        (prover, seq)
+    }
+
+    #[test]
+    fn test_sequent_limit() {
+        let (mut prover, seq) = runaway();
+        prover.limits.max_sequents = Some(1000);
+        assert!(matches!(
+            prover.decide_two_sided(&seq),
+            ProofOutcome::Unknown(UnknownReason::SequentLimit)
+        ));
+        assert_eq!(prover.stats().sequents_explored, 1000);
+    }
+
+    #[test]
+    fn test_timeout() {
+        let (mut prover, seq) = runaway();
+        prover.limits.timeout = Some(Duration::from_millis(20));
+        assert!(matches!(
+            prover.decide_two_sided(&seq),
+            ProofOutcome::Unknown(UnknownReason::Timeout)
+        ));
+        assert!(prover.stats().elapsed < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_cache_limit() {
+        let (mut prover, seq) = runaway();
+        prover.use_cache = true;
+        prover.limits.max_cache_entries = Some(3);
+        assert!(matches!(
+            prover.decide_two_sided(&seq),
+            ProofOutcome::Unknown(UnknownReason::CacheLimit)
+        ));
+    }
+
+    #[test]
+    fn test_cancel_from_another_thread() {
+        let (mut prover, seq) = runaway();
+        let handle = prover.cancel_handle();
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            handle.cancel();
+        });
+        assert!(matches!(
+            prover.decide_two_sided(&seq),
+            ProofOutcome::Unknown(UnknownReason::Cancelled)
+        ));
+        canceller.join().unwrap();
+
+        // Cancellation sticks until the prover is reset
+        let identity = TwoSidedSequent::new(vec![atom("A")], vec![atom("A")]);
+        assert!(prover.decide_two_sided(&identity).is_unknown());
+        prover.reset();
+        assert!(prover.decide_two_sided(&identity).is_provable());
+    }
+
+    #[test]
+    fn test_stopped_search_leaves_no_cache() {
+        // A failure found after stopping is not a failure
+        let seq = TwoSidedSequent::new(
+            vec![Formula::tensor(atom("A"), atom("B")), atom("C")],
+            vec![Formula::tensor(
+                atom("C"),
+                Formula::tensor(atom("B"), atom("A")),
+            )],
+        );
+        let mut prover = Prover::new(100);
+        prover.limits.max_sequents = Some(3);
+        assert!(prover.decide_two_sided(&seq).is_unknown());
+        prover.limits.max_sequents = None;
+        assert!(prover.decide_two_sided(&seq).is_provable());
+    }
+
+    // ===== Balance Tests =====
+
+    #[test]
+    fn test_unbalanced_refuted_without_search() {
+        // Pool, Available, Available ⊢ Pool ⊗ Available has an Available over
+        let seq = TwoSidedSequent::new(
+            vec![atom("Pool"), atom("Available"), atom("Available")],
+            vec![Formula::tensor(atom("Pool"), atom("Available"))],
+        );
+        let mut prover = Prover::new(0);
+        assert!(prover.decide_two_sided(&seq).is_refuted());
+        assert_eq!(prover.stats().sequents_explored, 0);
+        assert_eq!(prover.stats().balance_prunes, 1);
+    }
+
+    #[test]
+    fn test_balance_prunes_splits() {
+        // ⊢ A ⊗ A⊥ is balanced, but neither premise of the tensor is
+        let seq = Sequent::new(vec![Formula::tensor(atom("A"), neg_atom("A"))]);
+        let mut prover = Prover::new(100);
+        prover.use_proof_nets = false;
+        assert!(prover.decide(&seq).is_refuted());
+        assert_eq!(prover.stats().balance_prunes, 1);
+    }
+
+    #[test]
+    fn test_balance_does_not_change_answers() {
+        for seq in cache_samples() {
+            let mut pruned = Prover::new(100);
+            let mut unpruned = Prover::new(100);
+            unpruned.use_balance = false;
+            assert_eq!(
+                outcome_kind(&pruned.decide_two_sided(&seq)),
+                outcome_kind(&unpruned.decide_two_sided(&seq)),
+                "balance check changed the answer for {}",
+                seq.pretty()
+            );
+        }
+    }
+
+    #[test]
+    fn test_cache_shallow_revisit() {
+        // This sequent meets the same subgoal deep in a failing branch and
+        // again near the root. A depth-blind failure cache used to reuse the
+        // deep failure and miss the proof.
+        let seq = Sequent::new(vec![
+            neg_atom("A"),
+            Formula::lolli(
+                atom("B"),
+                Formula::lolli(
+                    Formula::par(atom("A"), atom("A")),
+                    Formula::tensor(atom("A"), atom("A")),
+                ),
+            ),
+            Formula::plus(
+                Formula::lolli(Formula::plus(atom("A"), atom("A")), neg_atom("A")),
+                Formula::with(
+                    Formula::tensor(atom("A"), atom("B")),
+                    Formula::tensor(atom("B"), atom("A")),
+                ),
+            ),
+        ]);
+
+        let mut uncached = Prover::new(6);
+        uncached.use_cache = false;
+        assert!(uncached.decide(&seq).is_provable());
+
+        let mut cached = Prover::new(6);
+        assert!(cached.decide(&seq).is_provable());
+    }
 }
@@ -0,0 +1,223 @@
+//! Search strategies.
+//!
+//! The prover always searches depth-first, but a [`SearchStrategy`] decides
+//! how deep each pass may go and which alternative to try first at every
+//! choice point. [`DepthFirst`] makes a single pass with the full depth
+//! limit and tries alternatives in a fixed order. [`IterativeDeepening`]
+//! raises the limit one step at a time, so it finds the shallowest proof.
+//! [`SmallestFirst`] tries the alternative that leaves the smallest sequent
+//! first.
+
+use lolli_core::{Formula, Sequent};
+
+/// How the prover explores the search space.
+pub trait SearchStrategy: Send + Sync {
+    /// The name accepted by [`strategy_by_name`].
+    fn name(&self) -> &'static str;
+
+    /// The depth limits of successive passes, up to `max_depth`.
+    ///
+    /// The search stops after the first pass that finds a proof or is not
+    /// cut off by its limit.
+    fn depths(&self, max_depth: usize) -> Vec<usize> {
+        vec![max_depth]
+    }
+
+    /// The order in which to try the alternatives at a choice point, given
+    /// the size of the sequent each one leads to. Returns indices into
+    /// `sizes`.
+    fn order(&self, sizes: &[usize]) -> Vec<usize> {
+        (0..sizes.len()).collect()
+    }
+}
+
+/// A single depth-first pass, trying alternatives in a fixed order.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DepthFirst;
+
+impl SearchStrategy for DepthFirst {
+    fn name(&self) -> &'static str {
+        "dfs"
+    }
+}
+
+/// Depth-first passes with limits 0, 1, 2, ... up to `max_depth`.
+///
+/// Failures cached by one pass carry over to the next, so the repeated work
+/// stays small, and the first proof found is one of the shallowest.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IterativeDeepening;
+
+impl SearchStrategy for IterativeDeepening {
+    fn name(&self) -> &'static str {
+        "iterative-deepening"
+    }
+
+    fn depths(&self, max_depth: usize) -> Vec<usize> {
+        (0..=max_depth).collect()
+    }
+}
+
+/// Depth-first search that tries the alternative leading to the smallest
+/// sequent first, such as closing an axiom before decomposing a formula.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SmallestFirst;
+
+impl SearchStrategy for SmallestFirst {
+    fn name(&self) -> &'static str {
+        "smallest-first"
+    }
+
+    fn order(&self, sizes: &[usize]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..sizes.len()).collect();
+        order.sort_by_key(|&i| sizes[i]);
+        order
+    }
+}
+
+/// The names of the built-in strategies.
+pub const STRATEGY_NAMES: [&str; 3] = ["dfs", "iterative-deepening", "smallest-first"];
+
+/// Look up a built-in strategy by name.
+pub fn strategy_by_name(name: &str) -> Option<Box<dyn SearchStrategy>> {
+    match name {
+        "dfs" => Some(Box::new(DepthFirst)),
+        "iterative-deepening" => Some(Box::new(IterativeDeepening)),
+        "smallest-first" => Some(Box::new(SmallestFirst)),
+        _ => None,
+    }
+}
+
+/// The number of connectives and atoms in `formula`.
+pub(crate) fn formula_size(formula: &Formula) -> usize {
+    match formula {
+        Formula::Tensor(a, b)
+        | Formula::Par(a, b)
+        | Formula::With(a, b)
+        | Formula::Plus(a, b)
+        | Formula::Lolli(a, b) => 1 + formula_size(a) + formula_size(b),
+        Formula::OfCourse(a) | Formula::WhyNot(a) => 1 + formula_size(a),
+        _ => 1,
+    }
+}
+
+/// The total size of the formulas of `seq`.
+pub(crate) fn sequent_size(seq: &Sequent) -> usize {
+    seq.linear
+        .iter()
+        .chain(&seq.unrestricted)
+        .chain(&seq.focus)
+        .map(formula_size)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ProofOutcome, Prover, UnknownReason};
+    use lolli_core::TwoSidedSequent;
+
+    fn atom(name: &str) -> Formula {
+        Formula::atom(name)
+    }
+
+    fn prover(depth: usize, strategy: impl SearchStrategy + 'static) -> Prover {
+        let mut prover = Prover::new(depth);
+        prover.strategy = Box::new(strategy);
+        prover
+    }
+
+    /// A ⊢ (A ⊗ (B ⊸ B)) ⊕ A: the left disjunct has a deeper proof
+    fn shallow_on_the_right() -> Sequent {
+        TwoSidedSequent::new(
+            vec![atom("A")],
+            vec![Formula::plus(
+                Formula::tensor(atom("A"), Formula::lolli(atom("B"), atom("B"))),
+                atom("A"),
+            )],
+        )
+        .to_one_sided()
+    }
+
+    #[test]
+    fn test_iterative_deepening_finds_shallowest_proof() {
+        let seq = shallow_on_the_right();
+        let deep = prover(100, DepthFirst).prove(&seq).unwrap();
+        let shallow = prover(100, IterativeDeepening).prove(&seq).unwrap();
+        assert!(shallow.depth() < deep.depth());
+    }
+
+    #[test]
+    fn test_smallest_first_prefers_smaller_sequent() {
+        let proof = prover(100, SmallestFirst)
+            .prove(&shallow_on_the_right())
+            .unwrap();
+        assert_eq!(proof.rule, lolli_core::Rule::PlusIntroRight);
+    }
+
+    #[test]
+    fn test_strategies_agree() {
+        let sequents = [
+            TwoSidedSequent::new(
+                vec![atom("A"), Formula::lolli(atom("A"), atom("B"))],
+                vec![atom("B")],
+            ),
+            TwoSidedSequent::new(
+                vec![Formula::with(atom("A"), atom("B"))],
+                vec![Formula::tensor(atom("A"), atom("B"))],
+            ),
+            TwoSidedSequent::new(
+                vec![Formula::of_course(atom("A"))],
+                vec![Formula::tensor(atom("A"), atom("A"))],
+            ),
+            TwoSidedSequent::new(
+                vec![Formula::plus(atom("A"), atom("B"))],
+                vec![Formula::plus(atom("B"), atom("A"))],
+            ),
+        ];
+        for seq in &sequents {
+            let expected = prover(50, DepthFirst).decide_two_sided(seq).is_provable();
+            for name in STRATEGY_NAMES {
+                let mut prover = Prover::new(50);
+                prover.strategy = strategy_by_name(name).unwrap();
+                let outcome = prover.decide_two_sided(seq);
+                assert_eq!(
+                    outcome.is_provable(),
+                    expected,
+                    "{} on {}",
+                    name,
+                    seq.pretty()
+                );
+                if let Some(proof) = outcome.proof() {
+                    assert!(crate::verify_proof(proof).is_ok());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_iterative_deepening_refutes() {
+        // A & B ⊢ A ⊗ B has no proof at any depth
+        let seq = TwoSidedSequent::new(
+            vec![Formula::with(atom("A"), atom("B"))],
+            vec![Formula::tensor(atom("A"), atom("B"))],
+        );
+        let outcome = prover(100, IterativeDeepening).decide_two_sided(&seq);
+        assert!(outcome.is_refuted());
+
+        // A proof deeper than the limit stays unknown
+        let outcome = prover(0, IterativeDeepening).decide(&shallow_on_the_right());
+        assert!(matches!(
+            outcome,
+            ProofOutcome::Unknown(UnknownReason::DepthLimit)
+        ));
+    }
+
+    #[test]
+    fn test_strategy_by_name() {
+        for name in STRATEGY_NAMES {
+            assert_eq!(strategy_by_name(name).unwrap().name(), name);
+        }
+        assert!(strategy_by_name("random").is_none());
+    }
+}
@@ -0,0 +1,600 @@
+//! Goal-directed proof construction.
+//!
+//! A [`ProofState`] starts from a single open goal. Each [`Tactic`] applies a
+//! rule to the first open goal, replacing it by the premises of the rule,
+//! until no goals remain and [`ProofState::proof`] assembles the proof.
+//!
+//! The exponential tactics use the textbook rules on `?` formulas, so every
+//! goal keeps all of its formulas in the linear zone.
+
+use lolli_core::{Formula, Proof, Rule, Sequent};
+
+use crate::multiset::without;
+use crate::{ProofOutcome, Prover};
+
+/// A rule to apply to the current goal.
+///
+/// Indices point into the linear formulas of the goal, starting at 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Tactic {
+    /// Close ⊢ A⊥, A for an atom A
+    Axiom,
+    /// Close ⊢ 1
+    One,
+    /// Close a goal containing ⊤
+    Top,
+    /// Drop the ⊥ at the index
+    Bottom(usize),
+    /// Split the ⅋ (or ⊸) at the index into its two halves
+    Par(usize),
+    /// Prove both halves of the & at the index
+    With(usize),
+    /// Prove the left half of the ⊕ at the index
+    PlusLeft(usize),
+    /// Prove the right half of the ⊕ at the index
+    PlusRight(usize),
+    /// Prove the two halves of the ⊗ at the index in separate goals
+    Tensor {
+        /// Index of the tensor
+        formula: usize,
+        /// The context formulas that go with the left half; if `None`, a
+        /// split with both premises provable is searched for
+        left: Option<Vec<usize>>,
+    },
+    /// Strip the ! at the index; every other formula must be a ?
+    Promotion(usize),
+    /// Use the ?A at the index as A
+    Dereliction(usize),
+    /// Drop the ?A at the index
+    Weakening(usize),
+    /// Duplicate the ?A at the index
+    Contraction(usize),
+    /// Close the goal with automatic proof search
+    Auto,
+}
+
+impl Tactic {
+    /// The name of the tactic, as used in error messages.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tactic::Axiom => "axiom",
+            Tactic::One => "one",
+            Tactic::Top => "top",
+            Tactic::Bottom(_) => "bottom",
+            Tactic::Par(_) => "par",
+            Tactic::With(_) => "with",
+            Tactic::PlusLeft(_) => "plus_left",
+            Tactic::PlusRight(_) => "plus_right",
+            Tactic::Tensor { .. } => "tensor",
+            Tactic::Promotion(_) => "promotion",
+            Tactic::Dereliction(_) => "dereliction",
+            Tactic::Weakening(_) => "weakening",
+            Tactic::Contraction(_) => "contraction",
+            Tactic::Auto => "auto",
+        }
+    }
+}
+
+/// Why a tactic could not be applied.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TacticError {
+    /// Every goal is already closed
+    #[error("No goals left")]
+    NoGoals,
+
+    /// The index is past the end of the goal (shown counting from 1)
+    #[error("The goal has no formula {}", .0 + 1)]
+    NoFormula(usize),
+
+    /// The rule does not match the goal
+    #[error("{tactic} does not apply: {reason}")]
+    NotApplicable {
+        /// Name of the tactic
+        tactic: &'static str,
+        /// What is wrong with the goal
+        reason: String,
+    },
+
+    /// `auto` found no proof of the goal
+    #[error("No proof found for the goal")]
+    NoProof,
+
+    /// `undo` with no step to take back
+    #[error("Nothing to undo")]
+    NothingToUndo,
+}
+
+/// A partial proof: the steps taken so far and the goals still open.
+///
+/// # Example
+///
+/// ```
+/// use lolli_prove::{ProofState, Tactic};
+/// use lolli_core::{Formula, TwoSidedSequent};
+///
+/// // A, B ⊢ A ⊗ B, that is ⊢ A⊥, B⊥, A ⊗ B
+/// let seq = TwoSidedSequent::new(
+///     vec![Formula::atom("A"), Formula::atom("B")],
+///     vec![Formula::tensor(Formula::atom("A"), Formula::atom("B"))],
+/// );
+/// let mut state = ProofState::new(seq.to_one_sided());
+///
+/// state.apply(Tactic::Tensor { formula: 2, left: Some(vec![0]) }).unwrap();
+/// assert_eq!(state.goals().len(), 2);
+///
+/// state.apply(Tactic::Axiom).unwrap();
+/// state.apply(Tactic::Axiom).unwrap();
+/// assert!(state.proof().is_some());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProofState {
+    /// Depth limit for `auto` and for finding tensor splits
+    pub max_depth: usize,
+    root: Node,
+    history: Vec<Node>,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Open(Sequent),
+    Step {
+        conclusion: Sequent,
+        rule: Rule,
+        premises: Vec<Node>,
+    },
+    Closed(Proof),
+}
+
+impl ProofState {
+    /// Start proving `goal`.
+    pub fn new(goal: Sequent) -> Self {
+        ProofState {
+            max_depth: 100,
+            root: Node::Open(goal),
+            history: Vec::new(),
+        }
+    }
+
+    /// The open goals, the current one first.
+    pub fn goals(&self) -> Vec<&Sequent> {
+        let mut goals = Vec::new();
+        self.root.collect_goals(&mut goals);
+        goals
+    }
+
+    /// The goal the next tactic applies to.
+    pub fn current(&self) -> Option<&Sequent> {
+        self.goals().into_iter().next()
+    }
+
+    /// Whether every goal is closed.
+    pub fn is_complete(&self) -> bool {
+        self.current().is_none()
+    }
+
+    /// The finished proof, once every goal is closed.
+    pub fn proof(&self) -> Option<Proof> {
+        self.root.to_proof()
+    }
+
+    /// Apply `tactic` to the current goal.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `TacticError` if there is no goal left or the tactic does
+    /// not fit the goal; the state is then unchanged.
+    pub fn apply(&mut self, tactic: Tactic) -> Result<(), TacticError> {
+        let goal = self.current().ok_or(TacticError::NoGoals)?.clone();
+        let node = self.step(&goal, &tactic)?;
+
+        self.history.push(self.root.clone());
+        if let Some(open) = self.root.first_open() {
+            *open = node;
+        }
+        Ok(())
+    }
+
+    /// Take back the last tactic.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TacticError::NothingToUndo` if no tactic has been applied.
+    pub fn undo(&mut self) -> Result<(), TacticError> {
+        self.root = self.history.pop().ok_or(TacticError::NothingToUndo)?;
+        Ok(())
+    }
+
+    fn step(&self, goal: &Sequent, tactic: &Tactic) -> Result<Node, TacticError> {
+        let linear = &goal.linear;
+        let not_applicable = |reason: &str| TacticError::NotApplicable {
+            tactic: tactic.name(),
+            reason: reason.to_string(),
+        };
+        let formula = |i: usize| linear.get(i).ok_or(TacticError::NoFormula(i));
+        let open = |rule: Rule, premises: Vec<Vec<Formula>>| Node::Step {
+            conclusion: goal.clone(),
+            rule,
+            premises: premises
+                .into_iter()
+                .map(|p| Node::Open(Sequent::new(p)))
+                .collect(),
+        };
+
+        match tactic {
+            Tactic::Axiom => match linear.as_slice() {
+                [x @ (Formula::Atom(_) | Formula::NegAtom(_)), y] if *y == x.negate() => {
+                    Ok(open(Rule::Axiom, vec![]))
+                }
+                _ => Err(not_applicable("the goal is not ⊢ A⊥, A for an atom A")),
+            },
+
+            Tactic::One => match linear.as_slice() {
+                [Formula::One] => Ok(open(Rule::OneIntro, vec![])),
+                _ => Err(not_applicable("the goal is not ⊢ 1")),
+            },
+
+            Tactic::Top => {
+                if linear.contains(&Formula::Top) {
+                    Ok(open(Rule::TopIntro, vec![]))
+                } else {
+                    Err(not_applicable("the goal has no ⊤"))
+                }
+            }
+
+            Tactic::Bottom(i) => match formula(*i)? {
+                Formula::Bottom => Ok(open(Rule::BottomIntro, vec![replaced(linear, *i, &[])])),
+                _ => Err(not_applicable("not a ⊥")),
+            },
+
+            Tactic::Par(i) => match formula(*i)? {
+                Formula::Par(a, b) => Ok(open(
+                    Rule::ParIntro,
+                    vec![replaced(linear, *i, &[(**a).clone(), (**b).clone()])],
+                )),
+                Formula::Lolli(a, b) => Ok(open(
+                    Rule::ParIntro,
+                    vec![replaced(linear, *i, &[a.negate(), (**b).clone()])],
+                )),
+                _ => Err(not_applicable("not a ⅋ or ⊸")),
+            },
+
+            Tactic::With(i) => match formula(*i)? {
+                Formula::With(a, b) => Ok(open(
+                    Rule::WithIntro,
+                    vec![
+                        replaced(linear, *i, &[(**a).clone()]),
+                        replaced(linear, *i, &[(**b).clone()]),
+                    ],
+                )),
+                _ => Err(not_applicable("not a &")),
+            },
+
+            Tactic::PlusLeft(i) | Tactic::PlusRight(i) => match formula(*i)? {
+                Formula::Plus(a, _) if matches!(tactic, Tactic::PlusLeft(_)) => Ok(open(
+                    Rule::PlusIntroLeft,
+                    vec![replaced(linear, *i, &[(**a).clone()])],
+                )),
+                Formula::Plus(_, b) => Ok(open(
+                    Rule::PlusIntroRight,
+                    vec![replaced(linear, *i, &[(**b).clone()])],
+                )),
+                _ => Err(not_applicable("not a ⊕")),
+            },
+
+            Tactic::Tensor { formula: i, left } => {
+                let Formula::Tensor(a, b) = formula(*i)? else {
+                    return Err(not_applicable("not a ⊗"));
+                };
+                let context: Vec<usize> = (0..linear.len()).filter(|j| j != i).collect();
+                let premises = |left: &[usize]| {
+                    let (mut l, mut r): (Vec<Formula>, Vec<Formula>) = (vec![], vec![]);
+                    for &j in &context {
+                        let side = if left.contains(&j) { &mut l } else { &mut r };
+                        side.push(linear[j].clone());
+                    }
+                    l.push((**a).clone());
+                    r.push((**b).clone());
+                    vec![l, r]
+                };
+
+                match left {
+                    Some(left) => {
+                        for &j in left {
+                            if j == *i {
+                                return Err(not_applicable("the tensor cannot go to a premise"));
+                            }
+                            formula(j)?;
+                        }
+                        Ok(open(Rule::TensorIntro, premises(left)))
+                    }
+                    None => {
+                        // The left premise of a proof with the tensor at its
+                        // root shows which formulas go left
+                        let proof = Prover::new(self.max_depth)
+                            .prove_tensor(goal, *i)
+                            .ok_or_else(|| {
+                                not_applicable("no split of the context proves both halves")
+                            })?;
+                        let mut taken = without(&proof.premises[0].conclusion.linear, a)
+                            .expect("the left premise proves the left half");
+                        let left: Vec<usize> = context
+                            .iter()
+                            .copied()
+                            .filter(|&j| match without(&taken, &linear[j]) {
+                                Some(rest) => {
+                                    taken = rest;
+                                    true
+                                }
+                                None => false,
+                            })
+                            .collect();
+                        Ok(open(Rule::TensorIntro, premises(&left)))
+                    }
+                }
+            }
+
+            Tactic::Promotion(i) => {
+                let Formula::OfCourse(a) = formula(*i)? else {
+                    return Err(not_applicable("not a !"));
+                };
+                let all_why_not = linear
+                    .iter()
+                    .enumerate()
+                    .all(|(j, f)| j == *i || matches!(f, Formula::WhyNot(_)));
+                if !all_why_not {
+                    return Err(not_applicable("every other formula must be a ?"));
+                }
+                Ok(open(
+                    Rule::OfCourseIntro,
+                    vec![replaced(linear, *i, &[(**a).clone()])],
+                ))
+            }
+
+            Tactic::Dereliction(i) | Tactic::Weakening(i) | Tactic::Contraction(i) => {
+                let Formula::WhyNot(a) = formula(*i)? else {
+                    return Err(not_applicable("not a ?"));
+                };
+                let why_not = &linear[*i];
+                Ok(match tactic {
+                    Tactic::Dereliction(_) => open(
+                        Rule::Dereliction,
+                        vec![replaced(linear, *i, &[(**a).clone()])],
+                    ),
+                    Tactic::Weakening(_) => open(Rule::Weakening, vec![replaced(linear, *i, &[])]),
+                    _ => open(
+                        Rule::Contraction,
+                        vec![replaced(linear, *i, &[why_not.clone(), why_not.clone()])],
+                    ),
+                })
+            }
+
+            Tactic::Auto => match Prover::new(self.max_depth).decide(goal) {
+                ProofOutcome::Provable(proof) => Ok(Node::Closed(Proof {
+                    conclusion: goal.clone(),
+                    ..proof
+                })),
+                _ => Err(TacticError::NoProof),
+            },
+        }
+    }
+}
+
+impl Node {
+    fn collect_goals<'a>(&'a self, goals: &mut Vec<&'a Sequent>) {
+        match self {
+            Node::Open(goal) => goals.push(goal),
+            Node::Step { premises, .. } => premises.iter().for_each(|p| p.collect_goals(goals)),
+            Node::Closed(_) => {}
+        }
+    }
+
+    fn first_open(&mut self) -> Option<&mut Node> {
+        match self {
+            Node::Open(_) => Some(self),
+            Node::Step { premises, .. } => premises.iter_mut().find_map(Node::first_open),
+            Node::Closed(_) => None,
+        }
+    }
+
+    fn to_proof(&self) -> Option<Proof> {
+        match self {
+            Node::Open(_) => None,
+            Node::Step {
+                conclusion,
+                rule,
+                premises,
+            } => Some(Proof {
+                conclusion: conclusion.clone(),
+                rule: rule.clone(),
+                premises: premises.iter().map(Node::to_proof).collect::<Option<_>>()?,
+            }),
+            Node::Closed(proof) => Some(proof.clone()),
+        }
+    }
+}
+
+/// The formulas of `linear` with the one at `i` replaced by `with`.
+fn replaced(linear: &[Formula], i: usize, with: &[Formula]) -> Vec<Formula> {
+    let mut formulas = linear.to_vec();
+    formulas.splice(i..=i, with.iter().cloned());
+    formulas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify_proof;
+    use lolli_core::TwoSidedSequent;
+
+    fn start(antecedent: Vec<Formula>, succedent: Vec<Formula>) -> ProofState {
+        ProofState::new(TwoSidedSequent::new(antecedent, succedent).to_one_sided())
+    }
+
+    fn a() -> Formula {
+        Formula::atom("A")
+    }
+
+    fn b() -> Formula {
+        Formula::atom("B")
+    }
+
+    #[test]
+    fn test_tensor_split() {
+        // ⊢ A⊥, B⊥, A ⊗ B
+        let mut state = start(vec![a(), b()], vec![Formula::tensor(a(), b())]);
+        state
+            .apply(Tactic::Tensor {
+                formula: 2,
+                left: Some(vec![0]),
+            })
+            .unwrap();
+        assert_eq!(
+            state.goals(),
+            vec![
+                &Sequent::new(vec![a().negate(), a()]),
+                &Sequent::new(vec![b().negate(), b()]),
+            ]
+        );
+        assert!(state.proof().is_none());
+
+        state.apply(Tactic::Axiom).unwrap();
+        state.apply(Tactic::Axiom).unwrap();
+        assert!(state.is_complete());
+        assert!(verify_proof(&state.proof().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_tensor_finds_split() {
+        // ⊢ B⊥, A⊥, A ⊗ B needs B⊥ on the right
+        let mut state = start(vec![b(), a()], vec![Formula::tensor(a(), b())]);
+        state
+            .apply(Tactic::Tensor {
+                formula: 2,
+                left: None,
+            })
+            .unwrap();
+        assert_eq!(
+            state.current(),
+            Some(&Sequent::new(vec![a().negate(), a()]))
+        );
+
+        // Twenty context formulas, interleaved, are split in one search
+        let atoms = |side: &str| -> Vec<Formula> {
+            (0..10)
+                .map(|k| Formula::atom(format!("{}{}", side, k)))
+                .collect()
+        };
+        let product = |side| atoms(side).into_iter().reduce(Formula::tensor).unwrap();
+        let context = atoms("A")
+            .into_iter()
+            .zip(atoms("B"))
+            .flat_map(|(x, y)| [x, y]);
+        let goal = Formula::tensor(product("A"), product("B"));
+        let mut state = start(context.collect(), vec![goal]);
+        state
+            .apply(Tactic::Tensor {
+                formula: 20,
+                left: None,
+            })
+            .unwrap();
+        state.apply(Tactic::Auto).unwrap();
+        state.apply(Tactic::Auto).unwrap();
+        assert!(verify_proof(&state.proof().unwrap()).is_ok());
+
+        // ⊢ A⊥, A ⊗ B has no split at all
+        let mut state =
+            ProofState::new(Sequent::new(vec![a().negate(), Formula::tensor(a(), b())]));
+        let err = state.apply(Tactic::Tensor {
+            formula: 1,
+            left: None,
+        });
+        assert!(matches!(
+            err,
+            Err(TacticError::NotApplicable {
+                tactic: "tensor",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_lolli_and_with() {
+        // ⊢ (A & B) ⊸ (B & A)
+        let mut state = start(
+            vec![],
+            vec![Formula::lolli(
+                Formula::with(a(), b()),
+                Formula::with(b(), a()),
+            )],
+        );
+        state.apply(Tactic::Par(0)).unwrap();
+        state.apply(Tactic::With(1)).unwrap();
+        assert_eq!(state.goals().len(), 2);
+        state.apply(Tactic::PlusRight(0)).unwrap();
+        state.apply(Tactic::Axiom).unwrap();
+        state.apply(Tactic::PlusLeft(0)).unwrap();
+        state.apply(Tactic::Axiom).unwrap();
+        assert!(verify_proof(&state.proof().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_exponentials() {
+        // !A ⊢ !(A ⊗ A), that is ⊢ ?A⊥, !(A ⊗ A)
+        let mut state = start(
+            vec![Formula::of_course(a())],
+            vec![Formula::of_course(Formula::tensor(a(), a()))],
+        );
+        state.apply(Tactic::Promotion(1)).unwrap();
+        state.apply(Tactic::Contraction(0)).unwrap();
+        state
+            .apply(Tactic::Tensor {
+                formula: 2,
+                left: Some(vec![0]),
+            })
+            .unwrap();
+        state.apply(Tactic::Dereliction(0)).unwrap();
+        state.apply(Tactic::Axiom).unwrap();
+        state.apply(Tactic::Dereliction(0)).unwrap();
+        state.apply(Tactic::Axiom).unwrap();
+        assert!(verify_proof(&state.proof().unwrap()).is_ok());
+
+        // Promotion needs a ? context
+        let mut state = start(vec![a()], vec![Formula::of_course(a())]);
+        assert!(matches!(
+            state.apply(Tactic::Promotion(1)),
+            Err(TacticError::NotApplicable { .. })
+        ));
+    }
+
+    #[test]
+    fn test_auto_and_undo() {
+        let mut state = start(
+            vec![a(), Formula::lolli(a(), b())],
+            vec![Formula::tensor(b(), Formula::One)],
+        );
+        assert_eq!(state.undo(), Err(TacticError::NothingToUndo));
+
+        state.apply(Tactic::Auto).unwrap();
+        assert!(state.is_complete());
+        assert_eq!(state.apply(Tactic::Axiom), Err(TacticError::NoGoals));
+        assert!(verify_proof(&state.proof().unwrap()).is_ok());
+
+        state.undo().unwrap();
+        assert_eq!(state.goals().len(), 1);
+        assert_eq!(state.apply(Tactic::Par(7)), Err(TacticError::NoFormula(7)));
+        assert!(matches!(
+            state.apply(Tactic::Axiom),
+            Err(TacticError::NotApplicable {
+                tactic: "axiom",
+                ..
+            })
+        ));
+
+        let mut state = start_one_sided(vec![a(), b().negate()]);
+        assert_eq!(state.apply(Tactic::Auto), Err(TacticError::NoProof));
+    }
+
+    fn start_one_sided(formulas: Vec<Formula>) -> ProofState {
+        ProofState::new(Sequent::new(formulas))
+    }
+}
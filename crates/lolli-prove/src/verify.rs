@@ -1,8 +1,16 @@
 //! Proof verification.
 //!
 //! This module provides verification of proofs to ensure they are valid.
+//!
+//! Every step is checked against the linear and unrestricted zones of its
+//! sequents, with formulas compared modulo [`Formula::desugar`]. Both the
+//! dyadic rules the provers use, where `?A` moves `A` to the unrestricted
+//! zone, and the textbook rules on `?A` in the linear zone are accepted.
+//! Premises may only use unrestricted formulas of the conclusion, plus the
+//! one a `?` rule adds, so leaves may ignore the unrestricted zone.
 
-use lolli_core::{Formula, Proof, Rule};
+use crate::multiset::{same_multiset, without};
+use lolli_core::{Formula, Proof, ProofPath, Rule, Sequent};
 
 /// Error type for proof verification.
 #[derive(Debug, Clone, thiserror::Error)]
@@ -31,283 +39,322 @@ pub enum ProofError {
         /// Description of the mismatch
         message: String,
     },
+}
 
-    /// Premise verification failed
-    #[error("Premise verification failed: {0}")]
-    PremiseFailed(Box<ProofError>),
+/// A proof step that failed verification.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{path}: {error}")]
+pub struct StepError {
+    /// Where the step is in the proof tree
+    pub path: ProofPath,
+    /// The conclusion of the step
+    pub conclusion: Sequent,
+    /// What is wrong with it
+    pub error: ProofError,
 }
 
 /// Verify that a proof is valid.
 ///
+/// Every step is checked, even below or above a faulty one.
+///
 /// # Errors
 ///
-/// Returns a `ProofError` if the proof is invalid.
-pub fn verify_proof(proof: &Proof) -> Result<(), ProofError> {
-    // First, verify the rule application is valid for the conclusion
-    verify_rule_application(proof)?;
-
-    // Then recursively verify all premises
-    for premise in &proof.premises {
-        verify_proof(premise).map_err(|e| ProofError::PremiseFailed(Box::new(e)))?;
+/// Returns a `StepError` for each invalid step, from the root up, premises
+/// left to right.
+pub fn verify_proof(proof: &Proof) -> Result<(), Vec<StepError>> {
+    let mut errors = Vec::new();
+    verify_steps(proof, ProofPath::root(), &mut errors);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
     }
+}
 
-    Ok(())
+fn verify_steps(proof: &Proof, path: ProofPath, errors: &mut Vec<StepError>) {
+    if let Err(error) = verify_rule_application(proof) {
+        errors.push(StepError {
+            path: path.clone(),
+            conclusion: proof.conclusion.clone(),
+            error,
+        });
+    }
+    for (i, premise) in proof.premises.iter().enumerate() {
+        verify_steps(premise, path.child(i), errors);
+    }
 }
 
 fn verify_rule_application(proof: &Proof) -> Result<(), ProofError> {
     let seq = &proof.conclusion;
+    let expected = match &proof.rule {
+        Rule::Axiom | Rule::OneIntro | Rule::TopIntro => 0,
+        Rule::TensorIntro | Rule::WithIntro | Rule::Cut(_) => 2,
+        _ => 1,
+    };
+    if proof.premises.len() != expected {
+        return Err(ProofError::WrongPremiseCount {
+            expected,
+            got: proof.premises.len(),
+        });
+    }
 
+    let linear = seq.linear_zone();
+    let unrestricted = seq.unrestricted_zone();
+    let premises: Vec<Vec<Formula>> = proof
+        .premises
+        .iter()
+        .map(|p| p.conclusion.linear_zone())
+        .collect();
+    let rest = |f: &Formula| without(&linear, f).expect("a formula of the zone");
+    let plus = |mut items: Vec<Formula>, extra: &[&Formula]| {
+        items.extend(extra.iter().map(|f| (*f).clone()));
+        items
+    };
+
+    // Each way the rule can apply to the linear zones, given by the formula
+    // it adds to the unrestricted zone of its premises, if any
+    let mut readings: Vec<Option<Formula>> = Vec::new();
     match &proof.rule {
         Rule::Axiom => {
-            // Axiom: ⊢ A⊥, A - exactly two formulas that are negations of each other
-            if seq.linear.len() != 2 {
-                return Err(ProofError::InvalidRule {
-                    rule: proof.rule.clone(),
-                    conclusion: seq.pretty(),
-                });
-            }
-
-            let is_valid = matches!(
-                (&seq.linear[0], &seq.linear[1]),
-                (Formula::Atom(a), Formula::NegAtom(b)) |
-                (Formula::NegAtom(b), Formula::Atom(a)) if a == b
-            );
-
-            if !is_valid {
-                return Err(ProofError::InvalidRule {
-                    rule: proof.rule.clone(),
-                    conclusion: seq.pretty(),
-                });
-            }
-
-            if !proof.premises.is_empty() {
-                return Err(ProofError::WrongPremiseCount {
-                    expected: 0,
-                    got: proof.premises.len(),
-                });
+            // ⊢ A⊥, A for an atom A
+            if matches!(
+                linear.as_slice(),
+                [x @ (Formula::Atom(_) | Formula::NegAtom(_)), y] if *y == x.negate()
+            ) {
+                readings.push(None);
             }
-
-            Ok(())
         }
 
         Rule::OneIntro => {
-            // One: ⊢ 1 - exactly one formula which is 1
-            if seq.linear.len() != 1 || seq.linear[0] != Formula::One {
-                return Err(ProofError::InvalidRule {
-                    rule: proof.rule.clone(),
-                    conclusion: seq.pretty(),
-                });
-            }
-
-            if !proof.premises.is_empty() {
-                return Err(ProofError::WrongPremiseCount {
-                    expected: 0,
-                    got: proof.premises.len(),
-                });
+            if linear == [Formula::One] {
+                readings.push(None);
             }
-
-            Ok(())
         }
 
         Rule::TopIntro => {
-            // Top: ⊢ Γ, ⊤ - context contains ⊤
-            if !seq.linear.contains(&Formula::Top) {
-                return Err(ProofError::InvalidRule {
-                    rule: proof.rule.clone(),
-                    conclusion: seq.pretty(),
-                });
-            }
-
-            if !proof.premises.is_empty() {
-                return Err(ProofError::WrongPremiseCount {
-                    expected: 0,
-                    got: proof.premises.len(),
-                });
+            if linear.contains(&Formula::Top) {
+                readings.push(None);
             }
-
-            Ok(())
         }
 
         Rule::BottomIntro => {
-            // Bottom: ⊢ Γ, ⊥ from ⊢ Γ
-            if !seq.linear.contains(&Formula::Bottom) {
-                return Err(ProofError::InvalidRule {
-                    rule: proof.rule.clone(),
-                    conclusion: seq.pretty(),
-                });
-            }
-
-            if proof.premises.len() != 1 {
-                return Err(ProofError::WrongPremiseCount {
-                    expected: 1,
-                    got: proof.premises.len(),
-                });
+            if without(&linear, &Formula::Bottom).is_some_and(|r| same_multiset(&r, &premises[0])) {
+                readings.push(None);
             }
-
-            Ok(())
         }
 
         Rule::TensorIntro => {
-            // Tensor: needs exactly 2 premises
-            if proof.premises.len() != 2 {
-                return Err(ProofError::WrongPremiseCount {
-                    expected: 2,
-                    got: proof.premises.len(),
-                });
-            }
-
-            // Check there's a tensor in the conclusion
-            let has_tensor = seq
-                .linear
-                .iter()
-                .any(|f| matches!(f, Formula::Tensor(_, _)));
-            if !has_tensor {
-                return Err(ProofError::InvalidRule {
-                    rule: proof.rule.clone(),
-                    conclusion: seq.pretty(),
-                });
+            for f in &linear {
+                if let Formula::Tensor(a, b) = f {
+                    if let (Some(mut left), Some(right)) =
+                        (without(&premises[0], a), without(&premises[1], b))
+                    {
+                        left.extend(right);
+                        if same_multiset(&left, &rest(f)) {
+                            readings.push(None);
+                        }
+                    }
+                }
             }
-
-            Ok(())
         }
 
         Rule::ParIntro => {
-            // Par: needs exactly 1 premise
-            if proof.premises.len() != 1 {
-                return Err(ProofError::WrongPremiseCount {
-                    expected: 1,
-                    got: proof.premises.len(),
-                });
-            }
-
-            // Check there's a par in the conclusion
-            let has_par = seq.linear.iter().any(|f| matches!(f, Formula::Par(_, _)));
-            if !has_par {
-                return Err(ProofError::InvalidRule {
-                    rule: proof.rule.clone(),
-                    conclusion: seq.pretty(),
-                });
+            for f in &linear {
+                if let Formula::Par(a, b) = f {
+                    if same_multiset(&plus(rest(f), &[a, b]), &premises[0]) {
+                        readings.push(None);
+                    }
+                }
             }
-
-            Ok(())
         }
 
         Rule::WithIntro => {
-            // With: needs exactly 2 premises
-            if proof.premises.len() != 2 {
-                return Err(ProofError::WrongPremiseCount {
-                    expected: 2,
-                    got: proof.premises.len(),
-                });
+            for f in &linear {
+                if let Formula::With(a, b) = f {
+                    if same_multiset(&plus(rest(f), &[a]), &premises[0])
+                        && same_multiset(&plus(rest(f), &[b]), &premises[1])
+                    {
+                        readings.push(None);
+                    }
+                }
             }
-
-            // Check there's a with in the conclusion
-            let has_with = seq
-                .linear
-                .iter()
-                .any(|f| matches!(f, Formula::With(_, _)));
-            if !has_with {
-                return Err(ProofError::InvalidRule {
-                    rule: proof.rule.clone(),
-                    conclusion: seq.pretty(),
-                });
-            }
-
-            Ok(())
         }
 
         Rule::PlusIntroLeft | Rule::PlusIntroRight => {
-            // Plus: needs exactly 1 premise
-            if proof.premises.len() != 1 {
-                return Err(ProofError::WrongPremiseCount {
-                    expected: 1,
-                    got: proof.premises.len(),
-                });
+            for f in &linear {
+                if let Formula::Plus(a, b) = f {
+                    let part = if proof.rule == Rule::PlusIntroLeft {
+                        a
+                    } else {
+                        b
+                    };
+                    if same_multiset(&plus(rest(f), &[part]), &premises[0]) {
+                        readings.push(None);
+                    }
+                }
             }
+        }
 
-            // Check there's a plus in the conclusion
-            let has_plus = seq
-                .linear
-                .iter()
-                .any(|f| matches!(f, Formula::Plus(_, _)));
-            if !has_plus {
-                return Err(ProofError::InvalidRule {
-                    rule: proof.rule.clone(),
-                    conclusion: seq.pretty(),
-                });
+        Rule::OfCourseIntro => {
+            // ⊢ ?Γ, !A from ⊢ ?Γ, A, where ?Γ may also be kept in the
+            // unrestricted zone
+            for f in &linear {
+                if let Formula::OfCourse(a) = f {
+                    if same_multiset(&plus(rest(f), &[a]), &premises[0]) {
+                        if let Some(other) =
+                            rest(f).iter().find(|g| !matches!(g, Formula::WhyNot(_)))
+                        {
+                            return Err(ProofError::ContextMismatch {
+                                message: format!(
+                                    "promotion of {} with {} in the linear zone",
+                                    f.pretty(),
+                                    other.pretty()
+                                ),
+                            });
+                        }
+                        readings.push(None);
+                    }
+                }
             }
-
-            Ok(())
         }
 
         Rule::WhyNotIntro => {
-            // WhyNot: needs exactly 1 premise
-            if proof.premises.len() != 1 {
-                return Err(ProofError::WrongPremiseCount {
-                    expected: 1,
-                    got: proof.premises.len(),
-                });
+            // Either moves A to the unrestricted zone or uses it once
+            for f in &linear {
+                if let Formula::WhyNot(a) = f {
+                    if same_multiset(&rest(f), &premises[0]) {
+                        readings.push(Some(a.as_ref().clone()));
+                    }
+                    if same_multiset(&plus(rest(f), &[a]), &premises[0]) {
+                        readings.push(None);
+                    }
+                }
             }
+        }
 
-            Ok(())
+        Rule::Weakening => {
+            // Drops a formula of the unrestricted zone, or adds a ?A
+            if same_multiset(&linear, &premises[0]) {
+                readings.push(None);
+            }
+            for f in &linear {
+                if matches!(f, Formula::WhyNot(_)) && same_multiset(&rest(f), &premises[0]) {
+                    readings.push(None);
+                }
+            }
         }
 
-        Rule::OfCourseIntro => {
-            // OfCourse: needs exactly 1 premise
-            if proof.premises.len() != 1 {
-                return Err(ProofError::WrongPremiseCount {
-                    expected: 1,
-                    got: proof.premises.len(),
-                });
+        Rule::Contraction => {
+            // Copies a formula of the unrestricted zone twice, or merges two
+            // copies of a ?A
+            for a in &unrestricted {
+                if same_multiset(&plus(linear.clone(), &[a, a]), &premises[0]) {
+                    readings.push(None);
+                }
+            }
+            for f in &linear {
+                if matches!(f, Formula::WhyNot(_))
+                    && same_multiset(&plus(linear.clone(), &[f]), &premises[0])
+                {
+                    readings.push(None);
+                }
             }
+        }
 
-            // Check there's an ofcourse in the conclusion
-            let has_ofcourse = seq
-                .linear
-                .iter()
-                .any(|f| matches!(f, Formula::OfCourse(_)));
-            if !has_ofcourse {
-                return Err(ProofError::InvalidRule {
-                    rule: proof.rule.clone(),
-                    conclusion: seq.pretty(),
-                });
+        Rule::Dereliction => {
+            // Copies a formula of the unrestricted zone, or uses a ?A once
+            for a in &unrestricted {
+                if same_multiset(&plus(linear.clone(), &[a]), &premises[0]) {
+                    readings.push(None);
+                }
             }
+            for f in &linear {
+                if let Formula::WhyNot(a) = f {
+                    if same_multiset(&plus(rest(f), &[a]), &premises[0]) {
+                        readings.push(None);
+                    }
+                }
+            }
+        }
 
-            Ok(())
+        Rule::Cut(a) => {
+            // ⊢ Γ, Δ from ⊢ Γ, A and ⊢ Δ, A⊥
+            let a = a.desugar();
+            if let (Some(left), Some(right)) = (
+                without(&premises[0], &a),
+                without(&premises[1], &a.negate()),
+            ) {
+                if same_multiset(&plus(left, &right.iter().collect::<Vec<_>>()), &linear) {
+                    readings.push(None);
+                }
+            }
         }
 
-        // For other rules, just check premise count for now
-        Rule::Cut(_) => {
-            if proof.premises.len() != 2 {
-                return Err(ProofError::WrongPremiseCount {
-                    expected: 2,
-                    got: proof.premises.len(),
-                });
+        Rule::FocusPositive(i) | Rule::FocusNegative(i) => {
+            // Moves the formula at index i into the focus
+            let premise = &proof.premises[0].conclusion;
+            if let (None, Some(f), Some(focus)) = (&seq.focus, seq.linear.get(*i), &premise.focus) {
+                let polarity = match proof.rule {
+                    Rule::FocusPositive(_) => f.desugar().is_positive(),
+                    _ => f.desugar().is_negative(),
+                };
+                if polarity
+                    && focus.desugar() == f.desugar()
+                    && same_multiset(&linear, &premises[0])
+                {
+                    readings.push(None);
+                }
             }
-            Ok(())
         }
 
-        Rule::Weakening | Rule::Contraction | Rule::Dereliction => {
-            if proof.premises.len() != 1 {
-                return Err(ProofError::WrongPremiseCount {
-                    expected: 1,
-                    got: proof.premises.len(),
-                });
+        Rule::Blur => {
+            // Returns the focused formula to the linear zone
+            let premise = &proof.premises[0].conclusion;
+            if seq.focus.is_some()
+                && premise.focus.is_none()
+                && same_multiset(&linear, &premises[0])
+            {
+                readings.push(None);
             }
-            Ok(())
         }
+    }
+
+    if readings.is_empty() {
+        return Err(ProofError::InvalidRule {
+            rule: proof.rule.clone(),
+            conclusion: seq.pretty(),
+        });
+    }
 
-        Rule::FocusPositive(_) | Rule::FocusNegative(_) | Rule::Blur => {
-            // Focus rules are internal to the prover
-            Ok(())
+    // Premises may only use the unrestricted formulas of the conclusion,
+    // plus the one the rule adds
+    let mut missing = None;
+    for extra in readings {
+        let allowed = |f: &Formula| unrestricted.contains(f) || extra.as_ref() == Some(f);
+        let unused = proof.premises.iter().find_map(|p| {
+            p.conclusion
+                .unrestricted_zone()
+                .into_iter()
+                .find(|f| !allowed(f))
+        });
+        match unused {
+            None => return Ok(()),
+            Some(f) => missing = Some(f),
         }
     }
+    let missing = missing.expect("a reading was rejected");
+    Err(ProofError::ContextMismatch {
+        message: format!(
+            "unrestricted {} of a premise is not in {}",
+            missing.pretty(),
+            seq.pretty()
+        ),
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use lolli_core::Sequent;
 
     #[test]
     fn test_verify_axiom() {
@@ -358,4 +405,299 @@ mod tests {
 
         assert!(verify_proof(&proof).is_ok());
     }
+
+    fn step(
+        linear: Vec<Formula>,
+        unrestricted: Vec<Formula>,
+        rule: Rule,
+        premises: Vec<Proof>,
+    ) -> Proof {
+        Proof {
+            conclusion: Sequent {
+                linear,
+                unrestricted,
+                focus: None,
+            },
+            rule,
+            premises,
+        }
+    }
+
+    fn first_error(proof: &Proof) -> ProofError {
+        verify_proof(proof).unwrap_err().remove(0).error
+    }
+
+    fn axiom(name: &str, unrestricted: Vec<Formula>) -> Proof {
+        step(
+            vec![Formula::neg_atom(name), Formula::atom(name)],
+            unrestricted,
+            Rule::Axiom,
+            vec![],
+        )
+    }
+
+    #[test]
+    fn test_verify_cut() {
+        let a = Formula::atom("A");
+        let cut = |formula: Formula| {
+            step(
+                vec![Formula::neg_atom("A"), a.clone()],
+                vec![],
+                Rule::Cut(formula),
+                vec![axiom("A", vec![]), axiom("A", vec![])],
+            )
+        };
+        assert!(verify_proof(&cut(a.clone())).is_ok());
+        assert!(verify_proof(&cut(Formula::neg_atom("A"))).is_ok());
+        assert!(matches!(
+            first_error(&cut(Formula::atom("B"))),
+            ProofError::InvalidRule { .. }
+        ));
+    }
+
+    #[test]
+    fn test_verify_dyadic_exponentials() {
+        let a = Formula::atom("A");
+        let not_a = Formula::neg_atom("A");
+
+        // ⊢ A⊥ ; A from ⊢ A⊥ ; A⊥, A, with or without A⊥ kept in the premise
+        for kept in [vec![not_a.clone()], vec![]] {
+            let proof = step(
+                vec![a.clone()],
+                vec![not_a.clone()],
+                Rule::Dereliction,
+                vec![axiom("A", kept)],
+            );
+            assert!(verify_proof(&proof).is_ok());
+        }
+
+        // ⊢ ?A⊥, A from ⊢ A⊥ ; A
+        let proof = step(
+            vec![Formula::why_not(not_a.clone()), a.clone()],
+            vec![],
+            Rule::WhyNotIntro,
+            vec![step(
+                vec![a.clone()],
+                vec![not_a.clone()],
+                Rule::Dereliction,
+                vec![axiom("A", vec![not_a.clone()])],
+            )],
+        );
+        assert!(verify_proof(&proof).is_ok());
+
+        // Dereliction of a formula missing from the unrestricted zone
+        let proof = step(
+            vec![a.clone()],
+            vec![],
+            Rule::Dereliction,
+            vec![axiom("A", vec![])],
+        );
+        assert!(verify_proof(&proof).is_err());
+
+        // Contraction copies twice; weakening drops a formula of the zone
+        let proof = step(
+            vec![Formula::tensor(a.clone(), a.clone())],
+            vec![not_a.clone()],
+            Rule::Contraction,
+            vec![step(
+                vec![
+                    not_a.clone(),
+                    not_a.clone(),
+                    Formula::tensor(a.clone(), a.clone()),
+                ],
+                vec![],
+                Rule::TensorIntro,
+                vec![axiom("A", vec![]), axiom("A", vec![])],
+            )],
+        );
+        assert!(verify_proof(&proof).is_ok());
+        let proof = step(
+            vec![not_a.clone(), a.clone()],
+            vec![Formula::atom("B")],
+            Rule::Weakening,
+            vec![axiom("A", vec![])],
+        );
+        assert!(verify_proof(&proof).is_ok());
+    }
+
+    #[test]
+    fn test_verify_monadic_exponentials() {
+        let a = Formula::atom("A");
+        let why_not = Formula::why_not(Formula::atom("B"));
+
+        // ⊢ A⊥, A, ?B by weakening, then contracted from two copies
+        let weakened = step(
+            vec![Formula::neg_atom("A"), a.clone(), why_not.clone()],
+            vec![],
+            Rule::Weakening,
+            vec![axiom("A", vec![])],
+        );
+        assert!(verify_proof(&weakened).is_ok());
+        let twice = step(
+            vec![
+                Formula::neg_atom("A"),
+                a.clone(),
+                why_not.clone(),
+                why_not.clone(),
+            ],
+            vec![],
+            Rule::Weakening,
+            vec![weakened.clone()],
+        );
+        let contracted = step(
+            weakened.conclusion.linear.clone(),
+            vec![],
+            Rule::Contraction,
+            vec![twice],
+        );
+        assert!(verify_proof(&contracted).is_ok());
+
+        // Contraction that changes the context
+        let wrong = step(vec![why_not], vec![], Rule::Contraction, vec![weakened]);
+        assert!(verify_proof(&wrong).is_err());
+    }
+
+    #[test]
+    fn test_verify_promotion() {
+        let a = Formula::atom("A");
+        let premise = axiom("A", vec![]);
+
+        // ⊢ ?A⊥, !A from ⊢ ?A⊥, A
+        let dereliction = step(
+            vec![Formula::why_not(Formula::neg_atom("A")), a.clone()],
+            vec![],
+            Rule::Dereliction,
+            vec![premise.clone()],
+        );
+        let proof = step(
+            vec![
+                Formula::why_not(Formula::neg_atom("A")),
+                Formula::of_course(a.clone()),
+            ],
+            vec![],
+            Rule::OfCourseIntro,
+            vec![dereliction],
+        );
+        assert!(verify_proof(&proof).is_ok());
+
+        // ⊢ A⊥, !A: the context is not all ?-formulas
+        let proof = step(
+            vec![Formula::neg_atom("A"), Formula::of_course(a)],
+            vec![],
+            Rule::OfCourseIntro,
+            vec![premise],
+        );
+        assert!(matches!(
+            first_error(&proof),
+            ProofError::ContextMismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn test_verify_unrestricted_zone() {
+        // The premise uses B, which the conclusion does not have
+        let proof = step(
+            vec![Formula::neg_atom("A"), Formula::atom("A"), Formula::Bottom],
+            vec![],
+            Rule::BottomIntro,
+            vec![axiom("A", vec![Formula::atom("B")])],
+        );
+        assert!(matches!(
+            first_error(&proof),
+            ProofError::ContextMismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn test_verify_focus() {
+        let a = Formula::atom("A");
+        let not_a = Formula::neg_atom("A");
+        let focused = |formula: Formula, rest: Formula, rule: Rule| Proof {
+            conclusion: Sequent {
+                linear: vec![rest],
+                unrestricted: vec![],
+                focus: Some(formula),
+            },
+            rule,
+            premises: vec![axiom("A", vec![])],
+        };
+
+        // Focus on A, then blur
+        let blur = focused(a.clone(), not_a.clone(), Rule::Blur);
+        assert!(verify_proof(&blur).is_ok());
+        let proof = step(
+            vec![a.clone(), not_a.clone()],
+            vec![],
+            Rule::FocusPositive(0),
+            vec![blur.clone()],
+        );
+        assert!(verify_proof(&proof).is_ok());
+
+        // A is not negative, and index 1 is A⊥
+        for rule in [Rule::FocusNegative(0), Rule::FocusPositive(1)] {
+            let proof = step(
+                vec![a.clone(), not_a.clone()],
+                vec![],
+                rule,
+                vec![blur.clone()],
+            );
+            assert!(verify_proof(&proof).is_err());
+        }
+    }
+
+    #[test]
+    fn test_verify_lolli_as_par() {
+        // ⊢ A ⊸ A from ⊢ A⊥, A
+        let a = Formula::atom("A");
+        let proof = step(
+            vec![Formula::lolli(a.clone(), a)],
+            vec![],
+            Rule::ParIntro,
+            vec![axiom("A", vec![])],
+        );
+        assert!(verify_proof(&proof).is_ok());
+    }
+
+    #[test]
+    fn test_verify_collects_every_error() {
+        // A valid root over a bad axiom and a bad one-intro
+        let bad_axiom = step(
+            vec![Formula::neg_atom("A"), Formula::Bottom, Formula::atom("B")],
+            vec![],
+            Rule::Axiom,
+            vec![],
+        );
+        let bad_one = step(
+            vec![Formula::neg_atom("A"), Formula::One],
+            vec![],
+            Rule::OneIntro,
+            vec![],
+        );
+        let proof = step(
+            vec![
+                Formula::neg_atom("A"),
+                Formula::Bottom,
+                Formula::with(Formula::atom("B"), Formula::One),
+            ],
+            vec![],
+            Rule::WithIntro,
+            vec![
+                bad_axiom,
+                step(
+                    vec![Formula::neg_atom("A"), Formula::Bottom, Formula::One],
+                    vec![],
+                    Rule::BottomIntro,
+                    vec![bad_one],
+                ),
+            ],
+        );
+
+        let errors = verify_proof(&proof).unwrap_err();
+        let paths: Vec<String> = errors.iter().map(|e| e.path.to_string()).collect();
+        assert_eq!(paths, vec!["premise 0", "premise 1 → premise 0"]);
+        assert_eq!(errors[1].conclusion.linear.len(), 2);
+        assert!(errors[1]
+            .to_string()
+            .starts_with("premise 1 → premise 0: Invalid rule"));
+    }
 }
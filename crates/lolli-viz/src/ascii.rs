@@ -2,7 +2,7 @@
 //!
 //! Renders proofs as text trees suitable for terminal display.
 
-use lolli_core::Proof;
+use lolli_core::{Proof, ProofPath};
 
 /// Proof tree renderer for ASCII/Unicode output.
 pub struct TreeRenderer {
@@ -12,6 +12,8 @@ pub struct TreeRenderer {
     pub show_rules: bool,
     /// Indent width
     pub indent_width: usize,
+    /// Subproofs to mark, such as the steps that failed verification
+    pub highlight: Vec<ProofPath>,
 }
 
 impl Default for TreeRenderer {
@@ -20,6 +22,7 @@ impl Default for TreeRenderer {
             unicode: true,
             show_rules: true,
             indent_width: 2,
+            highlight: Vec::new(),
         }
     }
 }
@@ -33,19 +36,33 @@ impl TreeRenderer {
     /// Render a proof as a text tree.
     pub fn render(&self, proof: &Proof) -> String {
         let mut lines = Vec::new();
-        self.render_proof(proof, 0, &mut lines);
+        self.render_proof(proof, &ProofPath::root(), 0, &mut lines);
         lines.join("\n")
     }
 
     /// Render a proof recursively, building up lines.
-    fn render_proof(&self, proof: &Proof, indent: usize, lines: &mut Vec<String>) {
+    fn render_proof(
+        &self,
+        proof: &Proof,
+        path: &ProofPath,
+        indent: usize,
+        lines: &mut Vec<String>,
+    ) {
         let prefix = " ".repeat(indent * self.indent_width);
 
         // Render premises first (they appear above the conclusion)
-        for premise in &proof.premises {
-            self.render_proof(premise, indent + 1, lines);
+        for (i, premise) in proof.premises.iter().enumerate() {
+            self.render_proof(premise, &path.child(i), indent + 1, lines);
         }
 
+        let mark = if !self.highlight.contains(path) {
+            ""
+        } else if self.unicode {
+            "  ← error"
+        } else {
+            "  <- error"
+        };
+
         // Format the conclusion
         let conclusion = self.format_sequent(proof);
         let rule_name = format!("{:?}", proof.rule);
@@ -63,12 +80,15 @@ impl TreeRenderer {
             }
         } else if self.show_rules {
             // Leaf node - show rule name inline
-            lines.push(format!("{}⊢ {}  ({})", prefix, conclusion, rule_name));
+            lines.push(format!(
+                "{}⊢ {}  ({}){}",
+                prefix, conclusion, rule_name, mark
+            ));
             return;
         }
 
         // Add the conclusion
-        lines.push(format!("{}⊢ {}", prefix, conclusion));
+        lines.push(format!("{}⊢ {}{}", prefix, conclusion, mark));
     }
 
     /// Format a sequent for display.
@@ -168,4 +188,29 @@ mod tests {
         let output = renderer.render(&proof);
         assert!(!output.contains("Axiom"));
     }
+
+    #[test]
+    fn test_highlight() {
+        let leaf = |name: &str| Proof {
+            conclusion: Sequent::new(vec![Formula::atom(name)]),
+            rule: Rule::Axiom,
+            premises: vec![],
+        };
+        let proof = Proof {
+            conclusion: Sequent::new(vec![Formula::tensor(
+                Formula::atom("A"),
+                Formula::atom("B"),
+            )]),
+            rule: Rule::TensorIntro,
+            premises: vec![leaf("A"), leaf("B")],
+        };
+
+        let mut renderer = TreeRenderer::new();
+        renderer.highlight = vec![ProofPath::root().child(1)];
+
+        let output = renderer.render(&proof);
+        let marked: Vec<&str> = output.lines().filter(|l| l.ends_with("← error")).collect();
+        assert_eq!(marked.len(), 1);
+        assert!(marked[0].contains("⊢ B"));
+    }
 }
@@ -2,7 +2,7 @@
 //!
 //! Generates DOT format for visualizing proofs as graphs.
 
-use lolli_core::Proof;
+use lolli_core::{Link, Proof, ProofNet, ProofPath};
 
 /// Graphviz DOT renderer for proofs.
 pub struct DotRenderer {
@@ -14,6 +14,8 @@ pub struct DotRenderer {
     pub font: String,
     /// Show rule names in nodes
     pub show_rules: bool,
+    /// Subproofs to draw in red, such as the steps that failed verification
+    pub highlight: Vec<ProofPath>,
 }
 
 /// Graph direction.
@@ -71,6 +73,7 @@ impl Default for DotRenderer {
             node_shape: NodeShape::Box,
             font: "Helvetica".to_string(),
             show_rules: true,
+            highlight: Vec::new(),
         }
     }
 }
@@ -96,14 +99,20 @@ impl DotRenderer {
         lines.push("  edge [arrowhead=none];".to_string());
         lines.push(String::new());
 
-        self.render_proof(proof, &mut lines, &mut counter);
+        self.render_proof(proof, &ProofPath::root(), &mut lines, &mut counter);
 
         lines.push("}".to_string());
         lines.join("\n")
     }
 
     /// Render a proof recursively, returning the node ID.
-    fn render_proof(&self, proof: &Proof, lines: &mut Vec<String>, counter: &mut usize) -> usize {
+    fn render_proof(
+        &self,
+        proof: &Proof,
+        path: &ProofPath,
+        lines: &mut Vec<String>,
+        counter: &mut usize,
+    ) -> usize {
         let my_id = *counter;
         *counter += 1;
 
@@ -120,11 +129,16 @@ impl DotRenderer {
         // Escape for DOT
         let label = label.replace('"', "\\\"");
 
-        lines.push(format!("  n{} [label=\"{}\"];", my_id, label));
+        let color = if self.highlight.contains(path) {
+            ", color=red, fontcolor=red"
+        } else {
+            ""
+        };
+        lines.push(format!("  n{} [label=\"{}\"{}];", my_id, label, color));
 
         // Render premises and add edges
-        for premise in &proof.premises {
-            let child_id = self.render_proof(premise, lines, counter);
+        for (i, premise) in proof.premises.iter().enumerate() {
+            let child_id = self.render_proof(premise, &path.child(i), lines, counter);
             lines.push(format!("  n{} -> n{};", child_id, my_id));
         }
 
@@ -142,42 +156,69 @@ impl DotRenderer {
             .join(", ")
     }
 
-    /// Render as a proof net (for multiplicative fragment).
-    pub fn render_proof_net(&self, proof: &Proof) -> String {
+    /// Render a proof net: one node per link, one edge per formula
+    /// occurrence, from the link that produces it to the link that consumes
+    /// it or to its place in the conclusion.
+    pub fn render_proof_net(&self, net: &ProofNet) -> String {
         let mut lines = Vec::new();
-        let mut counter = 0;
 
         lines.push("digraph proof_net {".to_string());
         lines.push("  rankdir=TB;".to_string());
-        lines.push("  node [shape=circle, width=0.3];".to_string());
-        lines.push("  edge [dir=none];".to_string());
+        lines.push(format!(
+            "  node [shape=circle, width=0.3, fontname=\"{}\"];",
+            self.font
+        ));
+        lines.push(format!("  edge [dir=none, fontname=\"{}\"];", self.font));
         lines.push(String::new());
 
-        self.render_net_nodes(proof, &mut lines, &mut counter);
-
-        lines.push("}".to_string());
-        lines.join("\n")
-    }
-
-    /// Render proof net nodes.
-    fn render_net_nodes(
-        &self,
-        proof: &Proof,
-        lines: &mut Vec<String>,
-        counter: &mut usize,
-    ) -> usize {
-        let my_id = *counter;
-        *counter += 1;
+        // Where each occurrence comes from and goes to
+        let mut producer = vec![None; net.formulas.len()];
+        let mut consumer = vec![None; net.formulas.len()];
+        for (i, link) in net.links.iter().enumerate() {
+            let (label, above, below) = match *link {
+                Link::Axiom(a, b) => ("ax", vec![a, b], vec![]),
+                Link::Cut(a, b) => ("cut", vec![], vec![a, b]),
+                Link::Tensor {
+                    left,
+                    right,
+                    conclusion,
+                } => ("⊗", vec![conclusion], vec![left, right]),
+                Link::Par {
+                    left,
+                    right,
+                    conclusion,
+                } => ("⅋", vec![conclusion], vec![left, right]),
+            };
+            lines.push(format!("  l{} [label=\"{}\"];", i, label));
+            for o in above {
+                producer[o] = Some(format!("l{}", i));
+            }
+            for o in below {
+                consumer[o] = Some(format!("l{}", i));
+            }
+        }
+        for (i, &o) in net.conclusions.iter().enumerate() {
+            lines.push(format!("  c{} [shape=point];", i));
+            consumer[o] = Some(format!("c{}", i));
+        }
+        lines.push(String::new());
 
-        let rule_name = format!("{:?}", proof.rule);
-        lines.push(format!("  n{} [label=\"{}\"];", my_id, rule_name));
+        for (o, formula) in net.formulas.iter().enumerate() {
+            if let (Some(from), Some(to)) = (&producer[o], &consumer[o]) {
+                let label = formula.pretty().replace('"', "\\\"");
+                lines.push(format!("  {} -> {} [label=\"{}\"];", from, to, label));
+            }
+        }
 
-        for premise in &proof.premises {
-            let child_id = self.render_net_nodes(premise, lines, counter);
-            lines.push(format!("  n{} -> n{};", child_id, my_id));
+        if !net.conclusions.is_empty() {
+            let ends: Vec<String> = (0..net.conclusions.len())
+                .map(|i| format!("c{};", i))
+                .collect();
+            lines.push(format!("  {{ rank=same; {} }}", ends.join(" ")));
         }
 
-        my_id
+        lines.push("}".to_string());
+        lines.join("\n")
     }
 }
 
@@ -259,16 +300,66 @@ mod tests {
 
     #[test]
     fn test_proof_net() {
-        let proof = Proof {
-            conclusion: Sequent::new(vec![Formula::atom("A")]),
+        // ⊢ A⊥ ⅋ B⊥, A ⊗ B
+        let a = Proof {
+            conclusion: Sequent::new(vec![Formula::neg_atom("A"), Formula::atom("A")]),
+            rule: Rule::Axiom,
+            premises: vec![],
+        };
+        let b = Proof {
+            conclusion: Sequent::new(vec![Formula::neg_atom("B"), Formula::atom("B")]),
             rule: Rule::Axiom,
             premises: vec![],
         };
+        let tensor = Formula::tensor(Formula::atom("A"), Formula::atom("B"));
+        let par = Formula::par(Formula::neg_atom("A"), Formula::neg_atom("B"));
+        let proof = Proof {
+            conclusion: Sequent::new(vec![par, tensor.clone()]),
+            rule: Rule::ParIntro,
+            premises: vec![Proof {
+                conclusion: Sequent::new(vec![
+                    Formula::neg_atom("A"),
+                    Formula::neg_atom("B"),
+                    tensor,
+                ]),
+                rule: Rule::TensorIntro,
+                premises: vec![a, b],
+            }],
+        };
+        let net = ProofNet::from_proof(&proof).unwrap();
 
         let renderer = DotRenderer::new();
-        let output = renderer.render_proof_net(&proof);
+        let output = renderer.render_proof_net(&net);
 
         assert!(output.contains("proof_net"));
-        assert!(output.contains("circle"));
+        assert_eq!(output.matches("[label=\"ax\"]").count(), 2);
+        assert!(output.contains("[label=\"⊗\"]"));
+        assert!(output.contains("[label=\"⅋\"]"));
+        assert!(output.contains("[label=\"(A ⊗ B)\"]"));
+        assert!(output.contains("rank=same; c0; c1;"));
+    }
+
+    #[test]
+    fn test_highlight() {
+        let leaf = |name: &str| Proof {
+            conclusion: Sequent::new(vec![Formula::atom(name)]),
+            rule: Rule::Axiom,
+            premises: vec![],
+        };
+        let proof = Proof {
+            conclusion: Sequent::new(vec![Formula::tensor(
+                Formula::atom("A"),
+                Formula::atom("B"),
+            )]),
+            rule: Rule::TensorIntro,
+            premises: vec![leaf("A"), leaf("B")],
+        };
+
+        let mut renderer = DotRenderer::new();
+        renderer.highlight = vec![ProofPath::root()];
+
+        let output = renderer.render(&proof);
+        assert_eq!(output.matches(", color=red").count(), 1);
+        assert!(output.contains("n0 [label=\"⊢ (A ⊗ B)\\n(TensorIntro)\", color=red"));
     }
 }
@@ -2,23 +2,18 @@
 //!
 //! Generates LaTeX code for typesetting proofs.
 
-use lolli_core::Proof;
+use lolli_core::{Proof, ProofPath};
 
 /// LaTeX proof renderer using bussproofs package.
+#[derive(Default)]
 pub struct LatexRenderer {
     /// Include package imports
     pub include_preamble: bool,
     /// Use shorthand rule labels
     pub short_labels: bool,
-}
-
-impl Default for LatexRenderer {
-    fn default() -> Self {
-        Self {
-            include_preamble: false,
-            short_labels: false,
-        }
-    }
+    /// Subproofs to color red, such as the steps that failed verification;
+    /// needs the xcolor package
+    pub highlight: Vec<ProofPath>,
 }
 
 impl LatexRenderer {
@@ -35,11 +30,14 @@ impl LatexRenderer {
             lines.push(r"\usepackage{bussproofs}".to_string());
             lines.push(r"\usepackage{amsmath}".to_string());
             lines.push(r"\usepackage{amssymb}".to_string());
+            if !self.highlight.is_empty() {
+                lines.push(r"\usepackage{xcolor}".to_string());
+            }
             lines.push(String::new());
         }
 
         lines.push(r"\begin{prooftree}".to_string());
-        self.render_proof(proof, &mut lines);
+        self.render_proof(proof, &ProofPath::root(), &mut lines);
         lines.push(r"\end{prooftree}".to_string());
 
         lines.join("\n")
@@ -47,18 +45,23 @@ impl LatexRenderer {
 
     /// Render a complete LaTeX document.
     pub fn render_document(&self, proof: &Proof) -> String {
-        let mut lines = Vec::new();
-
-        lines.push(r"\documentclass{article}".to_string());
-        lines.push(r"\usepackage{bussproofs}".to_string());
-        lines.push(r"\usepackage{amsmath}".to_string());
-        lines.push(r"\usepackage{amssymb}".to_string());
-        lines.push(String::new());
-        lines.push(r"\begin{document}".to_string());
-        lines.push(String::new());
+        let mut lines = vec![
+            r"\documentclass{article}".to_string(),
+            r"\usepackage{bussproofs}".to_string(),
+            r"\usepackage{amsmath}".to_string(),
+            r"\usepackage{amssymb}".to_string(),
+        ];
+        if !self.highlight.is_empty() {
+            lines.push(r"\usepackage{xcolor}".to_string());
+        }
+        lines.extend([
+            String::new(),
+            r"\begin{document}".to_string(),
+            String::new(),
+        ]);
 
         lines.push(r"\begin{prooftree}".to_string());
-        self.render_proof(proof, &mut lines);
+        self.render_proof(proof, &ProofPath::root(), &mut lines);
         lines.push(r"\end{prooftree}".to_string());
 
         lines.push(String::new());
@@ -68,41 +71,46 @@ impl LatexRenderer {
     }
 
     /// Render a proof recursively.
-    fn render_proof(&self, proof: &Proof, lines: &mut Vec<String>) {
+    fn render_proof(&self, proof: &Proof, path: &ProofPath, lines: &mut Vec<String>) {
         // Render premises first
-        for premise in &proof.premises {
-            self.render_proof(premise, lines);
+        for (i, premise) in proof.premises.iter().enumerate() {
+            self.render_proof(premise, &path.child(i), lines);
         }
 
         // Format the conclusion
-        let conclusion = self.format_sequent(proof);
+        let color = if self.highlight.contains(path) {
+            r"\color{red}"
+        } else {
+            ""
+        };
+        let conclusion = format!(r"{}\vdash {}", color, self.format_sequent(proof));
         let rule_label = self.format_rule(&proof.rule);
 
         // Generate the appropriate inference command
         match proof.premises.len() {
             0 => {
-                lines.push(format!(r"  \AxiomC{{$\vdash {}$}}", conclusion));
+                lines.push(format!(r"  \AxiomC{{${}$}}", conclusion));
             }
             1 => {
                 lines.push(format!(
                     r"  \RightLabel{{\scriptsize {}}}",
                     rule_label
                 ));
-                lines.push(format!(r"  \UnaryInfC{{$\vdash {}$}}", conclusion));
+                lines.push(format!(r"  \UnaryInfC{{${}$}}", conclusion));
             }
             2 => {
                 lines.push(format!(
                     r"  \RightLabel{{\scriptsize {}}}",
                     rule_label
                 ));
-                lines.push(format!(r"  \BinaryInfC{{$\vdash {}$}}", conclusion));
+                lines.push(format!(r"  \BinaryInfC{{${}$}}", conclusion));
             }
             3 => {
                 lines.push(format!(
                     r"  \RightLabel{{\scriptsize {}}}",
                     rule_label
                 ));
-                lines.push(format!(r"  \TrinaryInfC{{$\vdash {}$}}", conclusion));
+                lines.push(format!(r"  \TrinaryInfC{{${}$}}", conclusion));
             }
             _ => {
                 // For more than 3 premises, we'd need a different approach
@@ -110,7 +118,7 @@ impl LatexRenderer {
                     r"  \RightLabel{{\scriptsize {}}}",
                     rule_label
                 ));
-                lines.push(format!(r"  \QuaternaryInfC{{$\vdash {}$}}", conclusion));
+                lines.push(format!(r"  \QuaternaryInfC{{${}$}}", conclusion));
             }
         }
     }
@@ -268,4 +276,30 @@ mod tests {
         assert!(output.contains(r"\begin{document}"));
         assert!(output.contains(r"\end{document}"));
     }
+
+    #[test]
+    fn test_highlight() {
+        let leaf = |name: &str| Proof {
+            conclusion: Sequent::new(vec![Formula::atom(name)]),
+            rule: Rule::Axiom,
+            premises: vec![],
+        };
+        let proof = Proof {
+            conclusion: Sequent::new(vec![Formula::tensor(
+                Formula::atom("A"),
+                Formula::atom("B"),
+            )]),
+            rule: Rule::TensorIntro,
+            premises: vec![leaf("A"), leaf("B")],
+        };
+
+        let mut renderer = LatexRenderer::new();
+        renderer.include_preamble = true;
+        renderer.highlight = vec![ProofPath::root().child(0)];
+
+        let output = renderer.render(&proof);
+        assert!(output.contains(r"\usepackage{xcolor}"));
+        assert!(output.contains(r"\AxiomC{$\color{red}\vdash A$}"));
+        assert!(output.contains(r"\AxiomC{$\vdash B$}"));
+    }
 }
@@ -8,7 +8,8 @@
 //!
 //! - **ASCII/Unicode**: Terminal-friendly proof trees
 //! - **LaTeX**: Using bussproofs package
-//! - **DOT**: Graphviz format for graph visualization
+//! - **DOT**: Graphviz format for graph visualization, of proof trees or
+//!   proof nets
 //!
 //! ## Example
 //!
@@ -29,7 +30,7 @@
 #![warn(missing_docs)]
 #![warn(clippy::all)]
 
-pub use lolli_core::{Formula, Proof, Rule, Sequent};
+pub use lolli_core::{Formula, Proof, ProofNet, ProofPath, Rule, Sequent};
 
 mod ascii;
 mod latex;
@@ -60,3 +61,8 @@ pub fn render_latex(proof: &Proof) -> String {
 pub fn render_dot(proof: &Proof) -> String {
     DotRenderer::new().render(proof)
 }
+
+/// Render a proof net as Graphviz DOT format.
+pub fn render_proof_net(net: &ProofNet) -> String {
+    DotRenderer::new().render_proof_net(net)
+}